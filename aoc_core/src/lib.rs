@@ -0,0 +1,84 @@
+//! Helpers shared between the days of Advent of Code 2025
+
+use std::str::FromStr;
+
+pub mod ranges;
+pub mod rng;
+pub mod union_find;
+
+/// The answer to a puzzle part, wide enough for the areas and counts of every
+/// day so far.
+pub type Answer = u128;
+
+/// All pairs of indices `(i, j)` with `i < j < n`, ordered by `i` and then
+/// by `j`.
+pub fn index_pairs(n: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..n).flat_map(move |i| (i + 1..n).map(move |j| (i, j)))
+}
+
+/// All pairs of distinct elements of `items`, each pair once, in the order of
+/// [`index_pairs`].
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    index_pairs(items.len()).map(|(i, j)| (&items[i], &items[j]))
+}
+
+/// Parse `D` comma-separated coordinates, as in `X,Y,Z`.
+pub fn parse_coordinates<T: FromStr + Copy + Default, const D: usize>(
+    s: &str,
+) -> Result<[T; D], &'static str> {
+    let mut coordinates = [T::default(); D];
+    let mut parts = s.split(',');
+
+    for coordinate in &mut coordinates {
+        *coordinate = parts
+            .next()
+            .ok_or("Missing coordinate")?
+            .parse()
+            .map_err(|_| "Invalid coordinate")?;
+    }
+
+    if parts.next().is_some() {
+        return Err("Too many coordinates");
+    }
+
+    Ok(coordinates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pairs() {
+        assert_eq!(
+            index_pairs(3).collect::<Vec<_>>(),
+            vec![(0, 1), (0, 2), (1, 2)]
+        );
+        assert_eq!(index_pairs(1).count(), 0);
+        assert_eq!(index_pairs(0).count(), 0);
+
+        assert_eq!(
+            pairs(&['a', 'b', 'c']).collect::<Vec<_>>(),
+            vec![(&'a', &'b'), (&'a', &'c'), (&'b', &'c')]
+        );
+        assert_eq!(pairs(&[1; 10]).count(), 45);
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        assert_eq!(parse_coordinates::<u64, 3>("1,2,3"), Ok([1, 2, 3]));
+        assert_eq!(parse_coordinates::<i64, 2>("-1,2"), Ok([-1, 2]));
+        assert_eq!(
+            parse_coordinates::<u64, 3>("1,2"),
+            Err("Missing coordinate")
+        );
+        assert_eq!(
+            parse_coordinates::<u64, 2>("1,x"),
+            Err("Invalid coordinate")
+        );
+        assert_eq!(
+            parse_coordinates::<u64, 2>("1,2,3"),
+            Err("Too many coordinates")
+        );
+    }
+}
@@ -0,0 +1,168 @@
+// Closed ID ranges shared between days
+//
+// Day 5 counts the IDs covered by a list of ranges and Day 2 scans the IDs of
+// its input ranges, and both must look at an ID covered by several
+// overlapping ranges only once. `MultipleRanges::merge_overlapping` turns such
+// a list into disjoint ranges. Ranges hold `u64` IDs unless another ordered
+// type is given, such as the `u128` IDs of extended Day 2 inputs.
+
+use std::cmp::Ordering;
+use std::ops::RangeInclusive;
+
+/// A closed interval [min, max] of IDs.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Range<T = u64> {
+    min: T,
+    max: T,
+}
+
+impl<T: Copy + Ord> Range<T> {
+    /// Construct a new closed interval [min, max].
+    pub fn new(min: T, max: T) -> Self {
+        Self { min, max }
+    }
+
+    /// Whether `value` lies inside [min, max].
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.min && value <= self.max
+    }
+
+    /// Two closed intervals overlap if they share at least one point.
+    pub fn is_overlapping(&self, other: &Self) -> bool {
+        self.max >= other.min && self.min <= other.max
+    }
+
+    /// Merge two overlapping ranges into their union; return `None` if disjoint.
+    pub fn merge(&self, other: &Self) -> Option<Self> {
+        if self.is_overlapping(other) {
+            Some(Self::new(self.min.min(other.min), self.max.max(other.max)))
+        } else {
+            None
+        }
+    }
+}
+
+impl Range<u64> {
+    /// Size of the closed interval: max - min + 1
+    pub fn size(&self) -> u64 {
+        self.max - self.min + 1
+    }
+}
+
+impl<T: Copy + Ord> From<RangeInclusive<T>> for Range<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        Self::new(*range.start(), *range.end())
+    }
+}
+
+impl<T> From<Range<T>> for RangeInclusive<T> {
+    fn from(range: Range<T>) -> Self {
+        range.min..=range.max
+    }
+}
+
+impl<T: Ord> PartialOrd<Self> for Range<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Range<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.min, &self.max).cmp(&(&other.min, &other.max))
+    }
+}
+
+/// A set of ranges. After `merge_overlapping`, the inner vector is pairwise-disjoint
+/// and sorted by (min, max).
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct MultipleRanges<T = u64>(pub Vec<Range<T>>);
+
+impl<T: Copy + Ord> MultipleRanges<T> {
+    /// Construct from a raw vector of (possibly unsorted/overlapping) ranges.
+    pub fn new(ranges: Vec<Range<T>>) -> Self {
+        Self(ranges)
+    }
+
+    /// Check if any range contains `value`.
+    pub fn contains(&self, value: T) -> bool {
+        self.0.iter().any(|range| range.contains(value))
+    }
+
+    /// In-place merge of overlapping ranges.
+    ///
+    /// Algorithm:
+    /// - Sort ranges by (min, max).
+    /// - Sweep once, merging the current range with the next if they overlap,
+    ///   otherwise flush the current range to the result and continue.
+    pub fn merge_overlapping(&mut self) {
+        if self.0.is_empty() {
+            return;
+        }
+        self.0.sort();
+
+        let mut merged: Vec<Range<T>> = Vec::new();
+
+        let mut current = self.0[0].clone();
+
+        for next_range in self.0.iter().skip(1) {
+            if let Some(m) = current.merge(next_range) {
+                // If they overlap, update `current` to be the merged version
+                current = m;
+            } else {
+                // If they don't, push 'current' to results and start a new 'current'
+                merged.push(current);
+                current = next_range.clone();
+            }
+        }
+        merged.push(current);
+
+        self.0 = merged;
+    }
+}
+
+impl<T: Copy + Ord> FromIterator<RangeInclusive<T>> for MultipleRanges<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(ranges: I) -> Self {
+        Self::new(ranges.into_iter().map(Range::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_overlapping_with_overlapping_ranges() {
+        let mut ranges = MultipleRanges::new(vec![Range::new(10, 14), Range::new(12, 18)]);
+        ranges.merge_overlapping();
+        assert_eq!(ranges.0, vec![Range::new(10, 18)])
+    }
+
+    #[test]
+    fn test_merge_overlapping_with_disjoint_ranges() {
+        let mut ranges = MultipleRanges::new(vec![Range::new(2, 5), Range::new(12, 18)]);
+        ranges.merge_overlapping();
+
+        assert_eq!(ranges.0, vec![Range::new(2, 5), Range::new(12, 18)],)
+    }
+
+    #[test]
+    fn test_range_inclusive_conversion() {
+        let mut ranges: MultipleRanges = [12..=18, 2..=5, 5..=7, 0..=u64::MAX - 1]
+            .into_iter()
+            .collect();
+        assert!(ranges.contains(u64::MAX - 1));
+        assert!(!ranges.contains(u64::MAX));
+
+        ranges.0.truncate(3);
+        ranges.merge_overlapping();
+        let merged: Vec<RangeInclusive<u64>> = ranges.0.into_iter().map(Into::into).collect();
+        assert_eq!(merged, vec![2..=7, 12..=18]);
+
+        let mut wide: MultipleRanges<u128> = [1 << 100..=1 << 101, 1 << 64..=(1 << 100) + 1]
+            .into_iter()
+            .collect();
+        wide.merge_overlapping();
+        assert_eq!(wide.0, vec![Range::new(1 << 64, 1 << 101)]);
+    }
+}
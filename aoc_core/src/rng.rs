@@ -0,0 +1,53 @@
+// Seeded pseudo-random numbers for randomized tests
+//
+// Several days check a fast solution against a slow reference on generated
+// inputs. A test must see the same inputs on every run, so instead of a
+// random number crate they draw from this linear congruential generator,
+// which depends on nothing but its seed. The constants are Knuth's MMIX
+// ones; only the upper bits of the state are used, as the lower bits of an
+// LCG have short periods.
+
+/// A seeded linear congruential generator for reproducible test inputs. Not
+/// suitable for anything that needs good randomness.
+#[derive(Debug, Clone)]
+pub struct TestRng {
+    state: u64,
+}
+
+impl TestRng {
+    /// A generator whose numbers are determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        TestRng { state: seed }
+    }
+
+    /// The next number in `0..range`. Panics if `range` is zero.
+    pub fn below(&mut self, range: u64) -> u64 {
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (self.state >> 33) % range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_below() {
+        let mut rng = TestRng::new(1);
+        let numbers: Vec<u64> = (0..1000).map(|_| rng.below(10)).collect();
+        assert!(numbers.iter().all(|&n| n < 10));
+        assert!((0..10).all(|n| numbers.contains(&n)));
+
+        // The same seed gives the same numbers
+        let mut again = TestRng::new(1);
+        assert!(numbers.iter().all(|&n| again.below(10) == n));
+        assert_ne!(
+            TestRng::new(2).below(1 << 31),
+            TestRng::new(1).below(1 << 31)
+        );
+    }
+}
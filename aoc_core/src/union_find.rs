@@ -0,0 +1,208 @@
+// Union-Find (Disjoint Set Union) with rollback
+//
+// Day 8 joins junction boxes into circuits with it, and rolls unions back to
+// ask what the circuits would be without an edge.
+//
+// Sets are merged by rank, without path compression: `find` then never
+// changes the structure, and every `union` changes at most one parent, one
+// size and one rank. Logging those changes lets `rollback` undo the latest
+// unions in reverse order, at the price of O(log n) instead of nearly
+// constant time per `find`.
+
+/// Union-Find over the elements `0..n`, maintaining disjoint sets (such as
+/// the circuits of day 8), that can undo its latest unions.
+///
+/// ```
+/// use aoc_core::union_find::UnionFind;
+///
+/// let mut uf = UnionFind::new(3);
+/// uf.union(0, 2);
+/// assert!(uf.connected(2, 0));
+/// uf.rollback(1);
+/// assert_eq!(uf.component_count(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u32>,
+    size: Vec<usize>,
+    components: usize,
+    /// One entry per call to [`UnionFind::union`]: the merge it made, if any.
+    log: Vec<Option<Merge>>,
+}
+
+/// A union of the root `child` into the root `root`.
+#[derive(Debug, Clone, Copy)]
+struct Merge {
+    child: usize,
+    root: usize,
+    rank_increased: bool,
+}
+
+impl UnionFind {
+    /// `n` elements, each in a set of its own.
+    pub fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+            size: vec![1; n],
+            components: n,
+            log: Vec::new(),
+        }
+    }
+
+    /// The number of elements.
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /// Whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Find the representative (root) of the set containing `i`.
+    pub fn find(&self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            i = self.parent[i];
+        }
+        i
+    }
+
+    /// Unite the sets containing `i` and `j`. Returns whether they were
+    /// separate sets before.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
+        let root_i = self.find(i);
+        let root_j = self.find(j);
+
+        // If they are already in the same set, only log the call
+        if root_i == root_j {
+            self.log.push(None);
+            return false;
+        }
+
+        // Hang the lower tree below the higher one
+        let (child, root) = if self.rank[root_i] < self.rank[root_j] {
+            (root_i, root_j)
+        } else {
+            (root_j, root_i)
+        };
+        let rank_increased = self.rank[child] == self.rank[root];
+
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        if rank_increased {
+            self.rank[root] += 1;
+        }
+        self.components -= 1;
+        self.log.push(Some(Merge {
+            child,
+            root,
+            rank_increased,
+        }));
+
+        true
+    }
+
+    /// The number of calls to [`UnionFind::union`] that can be rolled back.
+    pub fn operations(&self) -> usize {
+        self.log.len()
+    }
+
+    /// Undo the last `n_ops` calls to [`UnionFind::union`], or all of them
+    /// if there were fewer.
+    pub fn rollback(&mut self, n_ops: usize) {
+        for _ in 0..n_ops {
+            let Some(entry) = self.log.pop() else {
+                return;
+            };
+
+            if let Some(merge) = entry {
+                self.parent[merge.child] = merge.child;
+                self.size[merge.root] -= self.size[merge.child];
+                if merge.rank_increased {
+                    self.rank[merge.root] -= 1;
+                }
+                self.components += 1;
+            }
+        }
+    }
+
+    /// Whether `i` and `j` are in the same set.
+    pub fn connected(&self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    /// The size of the set containing `i`.
+    pub fn size_of(&self, i: usize) -> usize {
+        self.size[self.find(i)]
+    }
+
+    /// The number of sets.
+    pub fn component_count(&self) -> usize {
+        self.components
+    }
+
+    /// The sizes of all sets, in the order of their roots.
+    pub fn circuit_sizes(&self) -> Vec<usize> {
+        (0..self.len())
+            .filter(|&i| self.parent[i] == i)
+            .map(|i| self.size[i])
+            .collect()
+    }
+
+    /// The elements of every set in ascending order, with the sets ordered by
+    /// their smallest element.
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        let mut component_of_root = vec![usize::MAX; self.len()];
+
+        for i in 0..self.len() {
+            let root = self.find(i);
+            if component_of_root[root] == usize::MAX {
+                component_of_root[root] = components.len();
+                components.push(Vec::new());
+            }
+            components[component_of_root[root]].push(i);
+        }
+
+        components
+    }
+
+    /// Whether all elements are in a single set.
+    pub fn all_connected(&self) -> bool {
+        self.components <= 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find_rollback() {
+        let mut uf = UnionFind::new(5);
+        assert!(uf.union(0, 1));
+        assert!(uf.union(2, 3));
+        assert!(!uf.union(1, 0));
+        assert!(uf.union(1, 3));
+        assert_eq!(uf.component_count(), 2);
+        assert_eq!(uf.size_of(2), 4);
+
+        // Connectivity without the edge (1, 3), then with it again
+        uf.rollback(1);
+        assert!(!uf.connected(0, 3));
+        assert_eq!(uf.circuit_sizes(), vec![2, 2, 1]);
+        assert!(uf.union(1, 3));
+
+        uf.rollback(2);
+        assert_eq!(uf.operations(), 2);
+        assert_eq!(uf.component_count(), 3);
+        assert!(uf.connected(0, 1) && uf.connected(2, 3));
+
+        uf.rollback(10);
+        assert_eq!(uf.operations(), 0);
+        assert_eq!(uf.circuit_sizes(), vec![1; 5]);
+        assert!(!uf.all_connected());
+    }
+}
@@ -0,0 +1,81 @@
+// Several dials turned by the same commands
+//
+// A combination lock with several rings turns all of them at once. Every
+// dial keeps its own size and position, and counts the clicks on `0` like
+// part 2 does, so a bank of one puzzle dial reproduces the part 2 answer.
+
+use crate::{Command, CommandStream, Day1Error, Dial};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Dials that all apply the same commands, each counting how many times it
+/// hits `0`.
+///
+/// ```
+/// use day_1::{Dial, DialBank};
+///
+/// let mut bank = DialBank::new(vec![Dial::default(), Dial::new(10, 0).unwrap()]);
+/// bank.simulate(include_str!("sample_input.txt")).unwrap();
+/// assert_eq!(bank.zero_hits(), [6, 45]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialBank {
+    dials: Vec<Dial>,
+    zero_hits: Vec<u64>,
+}
+
+impl DialBank {
+    /// Create a bank of `dials`, none of which has hit `0` yet.
+    pub fn new(dials: Vec<Dial>) -> Self {
+        let zero_hits = vec![0; dials.len()];
+
+        Self { dials, zero_hits }
+    }
+
+    /// The dials, in the order they were added.
+    pub fn dials(&self) -> &[Dial] {
+        &self.dials
+    }
+
+    /// How many times every dial hit `0`, in the order of the dials.
+    pub fn zero_hits(&self) -> &[u64] {
+        &self.zero_hits
+    }
+
+    /// Apply `command` to every dial, counting the hits of `0`. Fails with
+    /// [`Day1Error::Overflow`], and leaves every dial as it was, if a count
+    /// would exceed `u64::MAX`.
+    pub fn apply(&mut self, command: &Command) -> Result<(), Day1Error> {
+        let overflows = self
+            .dials
+            .iter()
+            .zip(&self.zero_hits)
+            .any(|(dial, hits)| hits.checked_add(dial.count_zeros(command)).is_none());
+        if overflows {
+            return Err(Day1Error::Overflow);
+        }
+
+        for (dial, hits) in self.dials.iter_mut().zip(&mut self.zero_hits) {
+            *hits += dial.count_zeros(command);
+            dial.move_position(command);
+        }
+
+        Ok(())
+    }
+
+    /// Apply the commands of `input` to every dial, stopping at the first
+    /// invalid line. The commands before it stay applied.
+    pub fn simulate(&mut self, input: &str) -> Result<(), Day1Error> {
+        for command in CommandStream::new(input) {
+            self.apply(&command?)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromIterator<Dial> for DialBank {
+    fn from_iter<I: IntoIterator<Item = Dial>>(dials: I) -> Self {
+        Self::new(dials.into_iter().collect())
+    }
+}
@@ -0,0 +1,97 @@
+// A compact binary encoding of the commands
+//
+// Every command is a single unsigned LEB128 varint: seven bits per byte,
+// least significant first, with the high bit set on every byte but the last.
+// The encoded value is the steps shifted left by one, with the direction in
+// the lowest bit: `0` for left and `1` for right. A command of the puzzle
+// takes two bytes at most, and no command takes more than ten.
+
+use crate::{Command, Day1Error, Direction};
+use alloc::vec::Vec;
+
+/// The most bytes of a varint, enough for the 65 bits of a command.
+const MAX_BYTES: usize = 10;
+
+/// Encode `commands` in the binary format, decoded by [`BinaryCommands`].
+///
+/// ```
+/// use day_1::{BinaryCommands, Command, Direction, encode};
+///
+/// let commands = [
+///     Command::new(Direction::Left, 68),
+///     Command::new(Direction::Right, 300),
+/// ];
+/// let bytes = encode(commands);
+/// assert_eq!(bytes, [0x88, 0x01, 0xd9, 0x04]);
+///
+/// let decoded: Vec<Command> = BinaryCommands::new(&bytes).map(Result::unwrap).collect();
+/// assert_eq!(decoded, commands);
+/// ```
+pub fn encode(commands: impl IntoIterator<Item = Command>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+
+    for command in commands {
+        let direction = match command.direction {
+            Direction::Left => 0,
+            Direction::Right => 1,
+        };
+        let mut value = u128::from(command.steps) << 1 | direction;
+
+        while value >= 0x80 {
+            bytes.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+
+    bytes
+}
+
+/// The commands of a binary input, decoded lazily one at a time.
+///
+/// A command that is cut off at the end of the input, or whose steps do not
+/// fit in a `u64`, yields [`Day1Error::InvalidEncoding`] with the offset of
+/// its first byte, after which the iterator ends.
+pub struct BinaryCommands<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BinaryCommands<'a> {
+    /// Decode the commands of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+}
+
+impl Iterator for BinaryCommands<'_> {
+    type Item = Result<Command, Day1Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let rest = self.bytes.get(start..).filter(|rest| !rest.is_empty())?;
+
+        let mut value: u128 = 0;
+        for (index, &byte) in rest.iter().take(MAX_BYTES).enumerate() {
+            value |= u128::from(byte & 0x7f) << (7 * index);
+
+            if byte & 0x80 == 0 {
+                self.offset = start + index + 1;
+
+                let Ok(steps) = u64::try_from(value >> 1) else {
+                    break;
+                };
+                let direction = if value & 1 == 0 {
+                    Direction::Left
+                } else {
+                    Direction::Right
+                };
+
+                return Some(Ok(Command::new(direction, steps)));
+            }
+        }
+
+        self.offset = self.bytes.len();
+        Some(Err(Day1Error::InvalidEncoding { offset: start }))
+    }
+}
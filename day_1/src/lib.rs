@@ -1,6 +1,138 @@
 //! Advent of Code 2025 — Day 1: Secret Entrance
+//!
+//! The dial can also be driven directly:
+//!
+//! ```
+//! use day_1::{Command, Dial, Direction};
+//!
+//! let mut dial = Dial::new(100, 50).unwrap();
+//! dial.move_position(&Command::new(Direction::Left, 68));
+//! assert_eq!(dial.current_position(), 82);
+//!
+//! let command = Command::try_from("R48").unwrap();
+//! assert_eq!(dial.count_zeros(&command), 1);
+//! dial.move_position(&command);
+//! assert_eq!(dial.current_position(), 30);
+//! ```
+//!
+//! Without the default `std` feature the crate is `no_std` and only needs
+//! `alloc`. Reading inputs from `std::io`, file paths, sets of positions and
+//! drawing the dial face need `std`.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-struct Dial {
+extern crate alloc;
+
+pub use crate::bank::DialBank;
+pub use crate::binary::{BinaryCommands, encode};
+pub use crate::observer::Observer;
+#[cfg(feature = "parallel")]
+pub use crate::parallel::solve_parallel;
+pub use crate::program::Program;
+pub use crate::wide::{WideCommand, WideDial, solution_part_1_u128, solution_part_2_u128};
+
+use alloc::string::{String, ToString};
+use core::iter::{Enumerate, Sum};
+use core::num::TryFromIntError;
+use core::ops::{Add, Div, Neg, Rem, Sub};
+use core::str::{FromStr, Lines};
+use core::{error, fmt};
+#[cfg(feature = "std")]
+use std::collections::HashSet;
+#[cfg(feature = "std")]
+use std::io::{self, BufRead};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+mod bank;
+mod binary;
+mod observer;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod program;
+#[cfg(feature = "slow-reference")]
+mod reference;
+mod render;
+mod wide;
+
+/// Errors that can occur while parsing or solving the Day 1 puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Day1Error {
+    /// A line holds no command.
+    EmptyLine {
+        /// The 1-based line number in the input.
+        line: usize,
+    },
+    /// A command does not start with `L` or `R`.
+    InvalidDirection {
+        /// The 1-based line number in the input.
+        line: usize,
+        /// The offending direction.
+        direction: String,
+    },
+    /// The steps of a command are not an unsigned integer.
+    InvalidSteps {
+        /// The 1-based line number in the input.
+        line: usize,
+        /// The offending steps.
+        steps: String,
+    },
+    /// The dial has no positions, or the start is not one of them.
+    InvalidDial {
+        /// The number of positions on the dial.
+        size: u128,
+        /// The position the dial should point at.
+        start: u128,
+    },
+    /// The input is not valid in its [`InputFormat`]: text that is not
+    /// UTF-8, or a binary command that is cut off or too large.
+    InvalidEncoding {
+        /// The offset in bytes of the invalid data.
+        offset: usize,
+    },
+    /// The number of times the dial hits `0` does not fit in the count, a
+    /// `u64` or, for a [`WideDial`], a `u128`.
+    Overflow,
+    /// Reading the input failed.
+    #[cfg(feature = "std")]
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Day1Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Day1Error::EmptyLine { line } => write!(f, "line {line}: empty command"),
+            Day1Error::InvalidDirection { line, direction } => {
+                write!(f, "line {line}: invalid direction {direction:?}")
+            }
+            Day1Error::InvalidSteps { line, steps } => {
+                write!(f, "line {line}: invalid step count {steps:?}")
+            }
+            Day1Error::InvalidDial { size, start } => {
+                write!(f, "invalid start {start} for a dial of size {size}")
+            }
+            Day1Error::InvalidEncoding { offset } => {
+                write!(f, "byte {offset}: invalid encoding")
+            }
+            Day1Error::Overflow => write!(f, "the number of zero hits overflows"),
+            #[cfg(feature = "std")]
+            Day1Error::Io(kind) => write!(f, "could not read the input: {kind}"),
+        }
+    }
+}
+
+impl error::Error for Day1Error {}
+
+/// A circular dial with positions `0` to `size - 1`.
+///
+/// With the `serde` feature a dial serializes as its [`Dial::snapshot`],
+/// and deserializing rejects snapshots that are not a valid dial.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "DialOptions", into = "DialOptions")
+)]
+pub struct Dial {
     /// The current position of the dial. This value should always
     /// be between `0` (inclusive) and `size` (exclusive).
     current_position: u64,
@@ -10,29 +142,197 @@ struct Dial {
 }
 
 /// A rotation direction on the dial.
-enum Direction {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
     Left,
     Right,
 }
 
 /// A single rotation command with a direction and number of steps.
-struct Command {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Command {
     direction: Direction,
     steps: u64,
 }
 
+/// A rotation as a signed number of steps: negative turns left, positive
+/// turns right.
+///
+/// Turns add up like the rotations they stand for, so a sequence of them can
+/// be summed before it is applied to a dial.
+///
+/// ```
+/// use day_1::{Command, Dial, Direction, Turn};
+///
+/// let right = Turn::try_from(Command::new(Direction::Right, 48)).unwrap();
+/// let turns = [Turn(-68), Turn(-30), right];
+/// let mut dial = Dial::default();
+/// dial.apply_turn(turns.into_iter().sum());
+/// assert_eq!(dial.current_position(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Turn(pub i64);
+
 impl Dial {
+    /// Create a dial with `size` positions, pointing at `start`. Fails with
+    /// [`Day1Error::InvalidDial`] if the dial has no positions or `start` is
+    /// not one of them.
+    ///
+    /// ```
+    /// let dial = day_1::Dial::new(10, 3).unwrap();
+    /// assert_eq!(dial.current_position(), 3);
+    /// assert_eq!(dial.size(), 10);
+    ///
+    /// assert!(day_1::Dial::new(10, 10).is_err());
+    /// ```
+    pub fn new(size: u64, start: u64) -> Result<Self, Day1Error> {
+        if start >= size {
+            return Err(Day1Error::InvalidDial {
+                size: size.into(),
+                start: start.into(),
+            });
+        }
+
+        Ok(Self {
+            current_position: start,
+            size,
+        })
+    }
+
+    /// The position the dial points at.
+    pub fn current_position(&self) -> u64 {
+        self.current_position
+    }
+
+    /// The number of positions on the dial.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// The size and the current position of the dial, from which
+    /// [`Dial::restore`] resumes.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    ///
+    /// let mut dial = Dial::default();
+    /// dial.move_position(&Command::new(Direction::Left, 68));
+    /// let snapshot = dial.snapshot();
+    ///
+    /// dial.move_position(&Command::new(Direction::Right, 48));
+    /// dial.restore(&snapshot).unwrap();
+    /// assert_eq!(dial.current_position(), 82);
+    /// ```
+    pub fn snapshot(&self) -> DialOptions {
+        DialOptions {
+            size: self.size,
+            start: self.current_position,
+        }
+    }
+
+    /// Return the dial to `snapshot`, which fails with
+    /// [`Day1Error::InvalidDial`] and leaves the dial unchanged if the
+    /// snapshot is not a valid dial.
+    pub fn restore(&mut self, snapshot: &DialOptions) -> Result<(), Day1Error> {
+        *self = Dial::try_from(*snapshot)?;
+
+        Ok(())
+    }
+
     /// Apply a rotation command to the dial, updating `current_position`.
     ///
     /// The dial wraps around using modulo arithmetic, so positions remain
-    /// within `0..size`.
-    fn move_position(&mut self, command: &Command) {
-        let steps = command.steps % self.size;
+    /// within `0..size`. No intermediate value exceeds `size`, so every size
+    /// and step count of a `u64` is supported.
+    pub fn move_position(&mut self, command: &Command) {
+        self.current_position = rotate(
+            self.size,
+            self.current_position,
+            command.direction,
+            command.steps,
+        );
+    }
 
-        self.current_position = match command.direction {
-            Direction::Right => (self.current_position + steps) % self.size,
-            Direction::Left => (self.current_position + self.size - steps) % self.size,
-        }
+    /// Calculates how many clicks of a given command land on any of
+    /// `targets`, without actually moving the dial. Each target is counted
+    /// like [`Dial::count_visits`], so the time does not depend on the
+    /// number of steps.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    /// use std::collections::HashSet;
+    ///
+    /// let dial = Dial::default();
+    /// let alarms = HashSet::from([0, 25, 75]);
+    /// assert_eq!(dial.count_hits_in_set(&Command::new(Direction::Right, 150), &alarms), 5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn count_hits_in_set(&self, command: &Command, targets: &HashSet<u64>) -> u64 {
+        targets
+            .iter()
+            .map(|&target| self.count_visits(command, target))
+            .sum()
+    }
+
+    /// Undo `command`, returning the dial to where it pointed before the
+    /// command was applied.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    ///
+    /// let mut dial = Dial::default();
+    /// let commands = [
+    ///     Command::new(Direction::Left, 68),
+    ///     Command::new(Direction::Right, 48),
+    /// ];
+    /// for command in &commands {
+    ///     dial.move_position(command);
+    /// }
+    /// for command in commands.iter().rev() {
+    ///     dial.undo(command);
+    /// }
+    /// assert_eq!(dial.current_position(), 50);
+    /// ```
+    pub fn undo(&mut self, command: &Command) {
+        self.move_position(&command.inverse());
+    }
+
+    /// Apply `commands` to the dial one at a time, yielding the position
+    /// after each of them.
+    ///
+    /// The commands are applied lazily, as the positions are taken.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    ///
+    /// let mut dial = Dial::default();
+    /// let commands = [
+    ///     Command::new(Direction::Left, 68),
+    ///     Command::new(Direction::Right, 48),
+    /// ];
+    /// let positions: Vec<u64> = dial.positions_after(commands).collect();
+    /// assert_eq!(positions, [82, 30]);
+    /// assert_eq!(dial.current_position(), 30);
+    /// ```
+    pub fn positions_after(
+        &mut self,
+        commands: impl IntoIterator<Item = Command>,
+    ) -> impl Iterator<Item = u64> {
+        commands.into_iter().map(|command| {
+            self.move_position(&command);
+            self.current_position
+        })
+    }
+
+    /// Apply a signed turn to the dial, updating `current_position`.
+    pub fn apply_turn(&mut self, turn: Turn) {
+        let position = (i128::from(self.current_position) + i128::from(turn.0))
+            .rem_euclid(i128::from(self.size));
+
+        // The remainder is below `size`, so it fits in a `u64`
+        self.current_position = position as u64;
     }
 
     /// Calculates how many times the dial will hit `0` for a given command,
@@ -45,83 +345,352 @@ impl Dial {
     /// - This function does not mutate the dial; it is a pure counter.
     /// - Beware large step counts (e.g., `R1000` from `p=50` on `N=100` yields
     ///   `10` hits of `0`).
-    fn count_zeros(&self, command: &Command) -> u64 {
-        match command.direction {
-            Direction::Right => (self.current_position + command.steps) / self.size,
-            Direction::Left => {
-                let dist_to_zero = if self.current_position == 0 {
-                    self.size
-                } else {
-                    self.current_position
-                };
+    pub fn count_zeros(&self, command: &Command) -> u64 {
+        self.count_visits(command, 0)
+    }
 
-                if command.steps < dist_to_zero {
-                    0
-                } else {
-                    1 + (command.steps - dist_to_zero) / self.size
-                }
-            }
-        }
+    /// Calculates how many times the dial will hit `target` for a given
+    /// command, without actually moving the dial, counting clicks like
+    /// [`Dial::count_zeros`]. A target that is not on the dial is never hit.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    ///
+    /// let dial = Dial::default();
+    /// assert_eq!(dial.count_visits(&Command::new(Direction::Right, 250), 20), 2);
+    /// assert_eq!(dial.count_visits(&Command::new(Direction::Left, 30), 20), 1);
+    /// assert_eq!(dial.count_visits(&Command::new(Direction::Left, 30), 100), 0);
+    /// ```
+    pub fn count_visits(&self, command: &Command, target: u64) -> u64 {
+        visits(
+            self.size,
+            self.current_position,
+            command.direction,
+            command.steps,
+            target,
+        )
+    }
+}
+
+/// The unsigned integers dial positions, sizes and step counts are held in:
+/// `u64` for a [`Dial`] and `u128` for a [`WideDial`].
+trait Unsigned:
+    Copy
+    + Ord
+    + From<u8>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+}
+
+impl Unsigned for u64 {}
+impl Unsigned for u128 {}
+
+/// The position `steps` clicks in `direction` from `position` on a dial with
+/// `size` positions. No intermediate value exceeds `size`, so every size and
+/// step count of `T` is supported.
+fn rotate<T: Unsigned>(size: T, position: T, direction: Direction, steps: T) -> T {
+    let steps = steps % size;
+
+    match direction {
+        Direction::Right if steps < size - position => position + steps,
+        Direction::Right => steps - (size - position),
+        Direction::Left if steps <= position => position - steps,
+        Direction::Left => size - (steps - position),
+    }
+}
+
+/// How many of `steps` clicks in `direction` from `position` land on
+/// `target`, on a dial with `size` positions. The count is at most `steps`,
+/// so it fits in a `T`.
+fn visits<T: Unsigned>(size: T, position: T, direction: Direction, steps: T, target: T) -> T {
+    if target >= size {
+        return T::from(0);
+    }
+
+    // The number of clicks to the first hit of `target`, which is a full
+    // turn when the dial already points at it
+    let (from, to) = match direction {
+        Direction::Right => (position, target),
+        Direction::Left => (target, position),
+    };
+    let distance = if from < to {
+        to - from
+    } else {
+        size - (from - to)
+    };
+
+    if steps < distance {
+        T::from(0)
+    } else {
+        T::from(1) + (steps - distance) / size
     }
 }
 
 impl Command {
     /// Create a new turn command
-    fn new(direction: Direction, steps: u64) -> Self {
+    pub fn new(direction: Direction, steps: u64) -> Self {
         Self { direction, steps }
     }
+
+    /// The command turning the same number of steps the other way, which
+    /// undoes this one.
+    pub fn inverse(&self) -> Self {
+        let direction = match self.direction {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        };
+
+        Self::new(direction, self.steps)
+    }
+}
+
+impl From<Turn> for Command {
+    /// A turn of zero steps becomes a right turn.
+    fn from(turn: Turn) -> Self {
+        let direction = if turn.0 < 0 {
+            Direction::Left
+        } else {
+            Direction::Right
+        };
+
+        Command::new(direction, turn.0.unsigned_abs())
+    }
+}
+
+impl TryFrom<Command> for Turn {
+    type Error = TryFromIntError;
+
+    /// Fails if the steps do not fit in an `i64`.
+    fn try_from(command: Command) -> Result<Self, Self::Error> {
+        let steps = i64::try_from(command.steps)?;
+
+        Ok(match command.direction {
+            Direction::Left => Turn(-steps),
+            Direction::Right => Turn(steps),
+        })
+    }
+}
+
+impl Turn {
+    /// The sum of two turns, or `None` if its steps do not fit in an `i64`.
+    pub fn checked_add(self, other: Turn) -> Option<Turn> {
+        self.0.checked_add(other.0).map(Turn)
+    }
+
+    /// The opposite turn, or `None` for `Turn(i64::MIN)`, whose opposite
+    /// does not fit in an `i64`.
+    pub fn checked_neg(self) -> Option<Turn> {
+        self.0.checked_neg().map(Turn)
+    }
+}
+
+impl Add for Turn {
+    type Output = Turn;
+
+    /// Panics if the steps of the sum do not fit in an `i64`; see
+    /// [`Turn::checked_add`].
+    fn add(self, other: Turn) -> Turn {
+        self.checked_add(other).expect("turn overflows i64")
+    }
+}
+
+impl Neg for Turn {
+    type Output = Turn;
+
+    /// Panics for `Turn(i64::MIN)`; see [`Turn::checked_neg`].
+    fn neg(self) -> Turn {
+        self.checked_neg().expect("turn overflows i64")
+    }
+}
+
+impl Sum for Turn {
+    /// Panics if a partial sum does not fit in an `i64`; see
+    /// [`Turn::checked_add`].
+    fn sum<I: Iterator<Item = Turn>>(iter: I) -> Turn {
+        iter.fold(Turn(0), Add::add)
+    }
 }
 
 impl TryFrom<&str> for Direction {
-    type Error = String;
+    type Error = Day1Error;
 
+    /// Errors report the text as line 1.
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "L" => Ok(Direction::Left),
-            "R" => Ok(Direction::Right),
-            _ => Err(format!("Invalid direction: {}", value)),
-        }
+        parse_direction(1, value)
     }
 }
 
 impl TryFrom<&str> for Command {
-    type Error = String;
+    type Error = Day1Error;
 
+    /// Errors report the text as line 1.
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        // Expected format: one of `L` or `R` followed by an unsigned integer.
-        // Examples: "L68", "R14", "L1".
-        let direction = Direction::try_from(&value[0..1])?;
-        let steps = value[1..]
-            .parse()
-            .map_err(|_| format!("Invalid step count"))?;
+        parse_line(1, value)
+    }
+}
 
-        Ok(Command { direction, steps })
+/// Parse the direction `value` on the line with 1-based number `number`.
+///
+/// Both `L` and `LEFT` are left and both `R` and `RIGHT` are right, in any
+/// case.
+fn parse_direction(number: usize, value: &str) -> Result<Direction, Day1Error> {
+    match value.to_ascii_uppercase().as_str() {
+        "L" | "LEFT" => Ok(Direction::Left),
+        "R" | "RIGHT" => Ok(Direction::Right),
+        _ => Err(Day1Error::InvalidDirection {
+            line: number,
+            direction: value.to_string(),
+        }),
     }
 }
 
-impl Default for Dial {
+/// Parse the command on the line with 1-based number `number`.
+fn parse_line(number: usize, line: &str) -> Result<Command, Day1Error> {
+    let (direction, steps) = parse_parts(number, line)?;
+
+    Ok(Command { direction, steps })
+}
+
+/// Parse the direction and the steps of the command on the line with 1-based
+/// number `number`, with the steps in any integer type.
+fn parse_parts<T: FromStr>(number: usize, line: &str) -> Result<(Direction, T), Day1Error> {
+    // Expected format: a direction followed by an unsigned integer, optionally
+    // separated by whitespace. Examples: "L68", "R14", "LEFT 68", "right 14".
+    let line = line.trim();
+    let Some(first) = line.chars().next() else {
+        return Err(Day1Error::EmptyLine { line: number });
+    };
+    // The direction is the leading letters, or the first character if that
+    // is not a letter
+    let split = match line.find(|c: char| !c.is_alphabetic()) {
+        Some(0) => first.len_utf8(),
+        Some(split) => split,
+        None => line.len(),
+    };
+    let (direction, steps) = line.split_at(split);
+
+    let direction = parse_direction(number, direction)?;
+    let steps = steps.trim_start();
+    let steps = steps.parse().map_err(|_| Day1Error::InvalidSteps {
+        line: number,
+        steps: steps.to_string(),
+    })?;
+
+    Ok((direction, steps))
+}
+
+/// The commands of an input, parsed lazily one line at a time.
+///
+/// Every line yields its command or the [`Day1Error`] describing why it is
+/// invalid, so callers can stop at the first error or skip invalid lines.
+///
+/// ```
+/// use day_1::{Command, CommandStream, Day1Error, Direction};
+///
+/// let mut commands = CommandStream::new("L68\nX3\nR48");
+/// assert_eq!(commands.next(), Some(Ok(Command::new(Direction::Left, 68))));
+/// assert!(matches!(commands.next(), Some(Err(Day1Error::InvalidDirection { line: 2, .. }))));
+/// assert_eq!(commands.next(), Some(Ok(Command::new(Direction::Right, 48))));
+/// assert_eq!(commands.next(), None);
+/// ```
+pub struct CommandStream<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> CommandStream<'a> {
+    /// Parse the commands of `input`, one per line.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines().enumerate(),
+        }
+    }
+}
+
+impl Iterator for CommandStream<'_> {
+    type Item = Result<Command, Day1Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, line) = self.lines.next()?;
+        Some(parse_line(index + 1, line))
+    }
+}
+
+/// Parse the commands of the puzzle input lazily, reading it line by line
+/// from `reader`. Every command is yielded as soon as its line is read, so
+/// only the current line of the input is kept in memory.
+#[cfg(feature = "std")]
+pub fn parse_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Command, Day1Error>> {
+    reader.lines().enumerate().map(|(index, line)| {
+        let line = line.map_err(|err| Day1Error::Io(err.kind()))?;
+        parse_line(index + 1, &line)
+    })
+}
+
+/// The dial the puzzle input is simulated on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DialOptions {
+    /// The number of positions on the dial.
+    pub size: u64,
+    /// The position the dial points at before the first command.
+    pub start: u64,
+}
+
+impl Default for DialOptions {
+    /// The dial of the puzzle: 100 positions, pointing at 50.
     fn default() -> Self {
         Self {
-            current_position: 50,
             size: 100,
+            start: 50,
         }
     }
 }
 
+impl TryFrom<DialOptions> for Dial {
+    type Error = Day1Error;
+
+    fn try_from(options: DialOptions) -> Result<Self, Self::Error> {
+        Dial::new(options.size, options.start)
+    }
+}
+
+impl From<Dial> for DialOptions {
+    fn from(dial: Dial) -> Self {
+        dial.snapshot()
+    }
+}
+
+impl Default for Dial {
+    /// The dial of the puzzle: 100 positions, pointing at 50.
+    fn default() -> Self {
+        let options = DialOptions::default();
+        Self::new(options.size, options.start).expect("The default dial is valid")
+    }
+}
+
 /// Solve Part 1: count how many times the dial is at position `0`
 /// after executing each command from the given input.
 ///
 /// The `input` is expected to contain one command per line (e.g., `L68`, `R14`).
-/// Invalid lines will cause a panic via `expect`, matching AoC-style assumptions
-/// for trusted inputs.
+/// Invalid lines are reported as a [`Day1Error`] with their line number.
 ///
 /// Returns the number of times the dial points at `0` immediately after a move.
-fn solution_part_1(input: &str) -> u64 {
-    let mut dial = Dial::default();
+pub fn solution_part_1(input: &str) -> Result<u64, Day1Error> {
+    solution_part_1_with_options(input, &DialOptions::default())
+}
+
+/// Solve Part 1 on the dial described by `options`.
+///
+/// Invalid options are reported as [`Day1Error::InvalidDial`].
+pub fn solution_part_1_with_options(input: &str, options: &DialOptions) -> Result<u64, Day1Error> {
+    let mut dial = Dial::new(options.size, options.start)?;
     let mut password = 0;
 
-    for line in input.lines() {
-        let command = Command::try_from(line).expect("Could not read command");
+    for command in CommandStream::new(input) {
+        let command = command?;
         dial.move_position(&command);
 
         if dial.current_position == 0 {
@@ -129,7 +698,20 @@ fn solution_part_1(input: &str) -> u64 {
         }
     }
 
-    password
+    Ok(password)
+}
+
+/// Solve Part 1 on the dial described by `options` while reading the
+/// commands line by line from `reader`, without keeping the text of the
+/// input in memory.
+#[cfg(feature = "std")]
+pub fn solution_part_1_from_reader<R: BufRead>(
+    reader: R,
+    options: &DialOptions,
+) -> Result<u64, Day1Error> {
+    let (part_1, _) = solve_commands(parse_reader(reader), options, &mut ())?;
+
+    Ok(part_1)
 }
 
 /// Solve Part 2: count how many times the dial hits `0` during each rotation.
@@ -139,24 +721,137 @@ fn solution_part_1(input: &str) -> u64 {
 ///    (using `Dial::count_zeros`).
 /// 2) Apply the rotation to update the dial position (`Dial::move_position`).
 ///
+/// Invalid lines are reported as a [`Day1Error`] with their line number.
+///
 /// Returns the total count of `0` hits across all rotations.
-fn solution_part_2(input: &str) -> u64 {
-    let mut dial = Dial::default();
-    let mut password = 0;
+pub fn solution_part_2(input: &str) -> Result<u64, Day1Error> {
+    solution_part_2_with_options(input, &DialOptions::default())
+}
 
-    for line in input.lines() {
-        let command = Command::try_from(line).expect("Could not read command");
+/// Solve Part 2 on the dial described by `options`.
+///
+/// Invalid options are reported as [`Day1Error::InvalidDial`], and a count
+/// beyond `u64::MAX` as [`Day1Error::Overflow`].
+pub fn solution_part_2_with_options(input: &str, options: &DialOptions) -> Result<u64, Day1Error> {
+    let mut dial = Dial::new(options.size, options.start)?;
+    let mut password: u64 = 0;
 
-        password += dial.count_zeros(&command);
+    for command in CommandStream::new(input) {
+        let command = command?;
+
+        password = password
+            .checked_add(dial.count_zeros(&command))
+            .ok_or(Day1Error::Overflow)?;
         dial.move_position(&command);
     }
 
-    password
+    Ok(password)
+}
+
+/// How the commands of an input are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputFormat {
+    /// One command per line, as parsed by [`CommandStream`].
+    #[default]
+    Text,
+    /// The compact encoding written by [`encode`].
+    Binary,
+}
+
+impl InputFormat {
+    /// The format of the file at `path`: [`InputFormat::Binary`] for the
+    /// extension `bin`, and [`InputFormat::Text`] otherwise.
+    #[cfg(feature = "std")]
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension() {
+            Some(extension) if extension == "bin" => InputFormat::Binary,
+            _ => InputFormat::Text,
+        }
+    }
+}
+
+/// Solve both parts for `input` stored in `format` on the dial described by
+/// `options`.
+///
+/// ```
+/// use day_1::{DialOptions, InputFormat, encode, solve};
+///
+/// let text = include_str!("sample_input.txt");
+/// let options = DialOptions::default();
+/// assert_eq!(solve(text.as_bytes(), InputFormat::Text, &options), Ok((3, 6)));
+///
+/// let binary = encode(day_1::CommandStream::new(text).map(Result::unwrap));
+/// assert_eq!(solve(&binary, InputFormat::Binary, &options), Ok((3, 6)));
+/// ```
+pub fn solve(
+    input: &[u8],
+    format: InputFormat,
+    options: &DialOptions,
+) -> Result<(u64, u64), Day1Error> {
+    solve_observed(input, format, options, &mut ())
+}
+
+/// [`solve`], telling `observer` about every move of the dial. The events
+/// before an invalid command are still reported.
+pub fn solve_observed(
+    input: &[u8],
+    format: InputFormat,
+    options: &DialOptions,
+    observer: &mut impl Observer,
+) -> Result<(u64, u64), Day1Error> {
+    match format {
+        InputFormat::Text => {
+            let input = core::str::from_utf8(input).map_err(|err| Day1Error::InvalidEncoding {
+                offset: err.valid_up_to(),
+            })?;
+            solve_commands(CommandStream::new(input), options, observer)
+        }
+        InputFormat::Binary => solve_commands(BinaryCommands::new(input), options, observer),
+    }
+}
+
+/// Both parts for `commands` on the dial described by `options`, stopping at
+/// the first invalid command.
+fn solve_commands(
+    commands: impl Iterator<Item = Result<Command, Day1Error>>,
+    options: &DialOptions,
+    observer: &mut impl Observer,
+) -> Result<(u64, u64), Day1Error> {
+    let mut dial = Dial::new(options.size, options.start)?;
+    let (mut part_1, mut part_2): (u64, u64) = (0, 0);
+
+    for command in commands {
+        let command = command?;
+
+        part_2 = part_2
+            .checked_add(dial.move_with_events(&command, observer))
+            .ok_or(Day1Error::Overflow)?;
+        if dial.current_position == 0 {
+            part_1 += 1;
+        }
+    }
+
+    Ok((part_1, part_2))
+}
+
+/// Solve Part 2 on the dial described by `options` while reading the
+/// commands line by line from `reader`, without keeping the text of the
+/// input in memory.
+#[cfg(feature = "std")]
+pub fn solution_part_2_from_reader<R: BufRead>(
+    reader: R,
+    options: &DialOptions,
+) -> Result<u64, Day1Error> {
+    let (_, part_2) = solve_commands(parse_reader(reader), options, &mut ())?;
+
+    Ok(part_2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::rng::TestRng;
+    use std::collections::HashMap;
 
     #[test]
     fn test_turn_left() {
@@ -224,15 +919,962 @@ mod tests {
         assert_eq!(dial.current_position, 0);
     }
 
+    #[test]
+    fn test_overflow() {
+        // The largest dial, starting at its last position
+        let mut dial = Dial::new(u64::MAX, u64::MAX - 1).unwrap();
+        assert_eq!(dial.count_zeros(&Command::new(Direction::Right, 1)), 1);
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Right, u64::MAX)),
+            1
+        );
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Left, u64::MAX)),
+            1
+        );
+        assert_eq!(
+            dial.count_visits(&Command::new(Direction::Left, u64::MAX), 3),
+            1
+        );
+
+        dial.move_position(&Command::new(Direction::Right, 1));
+        assert_eq!(dial.current_position, 0);
+        dial.move_position(&Command::new(Direction::Left, 1));
+        assert_eq!(dial.current_position, u64::MAX - 1);
+        dial.move_position(&Command::new(Direction::Right, u64::MAX - 2));
+        assert_eq!(dial.current_position, u64::MAX - 3);
+        dial.move_position(&Command::new(Direction::Left, u64::MAX - 1));
+        assert_eq!(dial.current_position, u64::MAX - 2);
+        dial.apply_turn(Turn(i64::MAX));
+        assert_eq!(dial.current_position, (1 << 63) - 3);
+        dial.undo(&Command::new(Direction::Left, u64::MAX - 1));
+        dial.undo(&Command::new(Direction::Right, u64::MAX - 2));
+        assert_eq!(dial.current_position, (1 << 63) - 2);
+
+        // The largest step count on a dial just over half of it
+        let size = (1 << 63) + 1;
+        let mut dial = Dial::new(size, size - 1).unwrap();
+        dial.move_position(&Command::new(Direction::Right, u64::MAX));
+        assert_eq!(dial.current_position, size - 4);
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Right, u64::MAX)),
+            2
+        );
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Left, u64::MAX)),
+            2
+        );
+
+        // Every click lands on `0` on a dial of one position
+        let dial = Dial::new(1, 0).unwrap();
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Right, u64::MAX)),
+            u64::MAX
+        );
+
+        let options = DialOptions {
+            size: u64::MAX,
+            start: u64::MAX - 1,
+        };
+        let input = format!("R{}\nR1\nL1", u64::MAX);
+        assert_eq!(solution_part_1_with_options(&input, &options), Ok(1));
+        assert_eq!(solution_part_2_with_options(&input, &options), Ok(2));
+
+        // Two full turns of the one position dial hit `0` too often
+        let options = DialOptions { size: 1, start: 0 };
+        let input = format!("R{}\nR{}", u64::MAX, u64::MAX);
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Err(Day1Error::Overflow)
+        );
+        assert_eq!(
+            solve(input.as_bytes(), InputFormat::Text, &options),
+            Err(Day1Error::Overflow)
+        );
+        #[cfg(feature = "parallel")]
+        assert_eq!(solve_parallel(&input, &options), Err(Day1Error::Overflow));
+        assert_eq!(
+            solution_part_2_with_options(&format!("R{}\nL0", u64::MAX), &options),
+            Ok(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_wide_dial() {
+        // The one position dial that overflows a `u64` count
+        let one = WideDial::new(1, 0).unwrap();
+        let input = format!("R{}\nR{}", u64::MAX, u64::MAX);
+        assert_eq!(
+            solution_part_2_u128(&input, one.clone()),
+            Ok(2 * u128::from(u64::MAX))
+        );
+        assert_eq!(
+            solution_part_2_u128(&format!("R{}\nR1", u128::MAX), one.clone()),
+            Err(Day1Error::Overflow)
+        );
+        assert_eq!(
+            one.count_zeros(&WideCommand::new(Direction::Left, u128::MAX)),
+            u128::MAX
+        );
+
+        // The largest dial, starting at its last position
+        let mut dial = WideDial::new(u128::MAX, u128::MAX - 1).unwrap();
+        for direction in [Direction::Left, Direction::Right] {
+            let command = WideCommand::new(direction, u128::MAX);
+            assert_eq!(dial.count_zeros(&command), 1);
+            assert_eq!(dial.count_visits(&command, u128::MAX - 2), 1);
+        }
+        dial.move_position(&WideCommand::new(Direction::Right, 1));
+        assert_eq!(dial.current_position(), 0);
+        dial.move_position(&WideCommand::new(Direction::Left, u128::MAX - 1));
+        assert_eq!(dial.current_position(), 1);
+
+        // Sizes and step counts beyond `u64::MAX`
+        let size = 1 << 70;
+        let mut dial = WideDial::new(size, 1 << 69).unwrap();
+        let command = WideCommand::try_from(format!("R{}", 3 * size + 5).as_str()).unwrap();
+        assert_eq!(command, WideCommand::new(Direction::Right, 3 * size + 5));
+        assert_eq!(dial.count_zeros(&command), 3);
+        dial.move_position(&command);
+        assert_eq!(dial.current_position(), (1 << 69) + 5);
+        let input = format!("L{}\nR{}\n", (1u128 << 69) + 5, 2 * size);
+        assert_eq!(solution_part_1_u128(&input, dial.clone()), Ok(2));
+        assert_eq!(solution_part_2_u128(&input, dial), Ok(3));
+
+        assert_eq!(
+            WideDial::new(size, size),
+            Err(Day1Error::InvalidDial { size, start: size })
+        );
+        assert!(matches!(
+            WideCommand::try_from(format!("R{}0", u128::MAX).as_str()),
+            Err(Day1Error::InvalidSteps { line: 1, .. })
+        ));
+        assert_eq!(
+            solution_part_2_u128(include_str!("sample_input.txt"), WideDial::default()),
+            Ok(6)
+        );
+
+        // A wide dial counts and moves like the dial it was made from
+        let mut rng = TestRng::new(1);
+        for _ in 0..500 {
+            let size = rng.below(20) + 1;
+            let mut dial = Dial::new(size, rng.below(size)).unwrap();
+            let mut wide = WideDial::from(dial.clone());
+            let direction = if rng.below(2) == 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            let command = Command::new(direction, rng.below(100));
+            let target = rng.below(size + 1);
+
+            assert_eq!(
+                u128::from(dial.count_visits(&command, target)),
+                wide.count_visits(&command.into(), target.into())
+            );
+            dial.move_position(&command);
+            wide.move_position(&command.into());
+            assert_eq!(u128::from(dial.current_position), wide.current_position());
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        assert!(Dial::new(10, 10).is_err());
+        assert!(Dial::new(0, 0).is_err());
+
+        let dial = Dial::new(1, 0).unwrap();
+        assert_eq!(dial.count_zeros(&Command::new(Direction::Left, 3)), 3);
+    }
+
+    #[test]
+    fn test_options() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(
+            solution_part_1_with_options(input, &DialOptions::default()),
+            Ok(3)
+        );
+
+        let options = DialOptions { size: 10, start: 0 };
+        assert_eq!(solution_part_1_with_options(input, &options), Ok(3));
+        assert_eq!(solution_part_2_with_options(input, &options), Ok(45));
+
+        let options = DialOptions { size: 7, start: 3 };
+        assert_eq!(solution_part_1_with_options(input, &options), Ok(2));
+        assert_eq!(solution_part_2_with_options(input, &options), Ok(67));
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        assert_eq!(
+            solution_part_1_with_options("L1", &DialOptions { size: 5, start: 5 }),
+            Err(Day1Error::InvalidDial { size: 5, start: 5 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_lines() {
+        assert_eq!(
+            solution_part_1("L68\n\nR48"),
+            Err(Day1Error::EmptyLine { line: 2 })
+        );
+        assert_eq!(
+            solution_part_2("L68\nX3"),
+            Err(Day1Error::InvalidDirection {
+                line: 2,
+                direction: "X".to_string()
+            })
+        );
+        assert_eq!(
+            solution_part_2("R-4"),
+            Err(Day1Error::InvalidSteps {
+                line: 1,
+                steps: "-4".to_string()
+            })
+        );
+        assert_eq!(
+            Command::try_from("éL1").err(),
+            Some(Day1Error::InvalidDirection {
+                line: 1,
+                direction: "éL".to_string()
+            })
+        );
+        assert_eq!(
+            Command::try_from("3R").err(),
+            Some(Day1Error::InvalidDirection {
+                line: 1,
+                direction: "3".to_string()
+            })
+        );
+        assert_eq!(
+            Command::try_from("LEFTY 3").err(),
+            Some(Day1Error::InvalidDirection {
+                line: 1,
+                direction: "LEFTY".to_string()
+            })
+        );
+        assert_eq!(
+            Command::try_from("Left").err(),
+            Some(Day1Error::InvalidSteps {
+                line: 1,
+                steps: String::new()
+            })
+        );
+    }
+
+    #[test]
+    fn test_command_grammars() {
+        for (text, expected) in [
+            ("L68", Command::new(Direction::Left, 68)),
+            ("R14", Command::new(Direction::Right, 14)),
+            ("l68", Command::new(Direction::Left, 68)),
+            ("r 14", Command::new(Direction::Right, 14)),
+            ("LEFT 68", Command::new(Direction::Left, 68)),
+            ("LEFT68", Command::new(Direction::Left, 68)),
+            ("Right 14", Command::new(Direction::Right, 14)),
+            ("right\t14", Command::new(Direction::Right, 14)),
+            ("  rIgHt   14  ", Command::new(Direction::Right, 14)),
+        ] {
+            assert_eq!(Command::try_from(text), Ok(expected), "{text:?}");
+        }
+
+        let verbose = "LEFT 68\nleft 30\nRight 48\nL 5\nr60\nLeft55\nL1\nL 99\nRIGHT 14\nl82";
+        assert_eq!(solution_part_1(verbose), Ok(3));
+        assert_eq!(solution_part_2(verbose), Ok(6));
+    }
+
+    #[test]
+    fn test_count_visits() {
+        for size in 1..6 {
+            for start in 0..size {
+                for target in 0..size + 1 {
+                    for steps in 0..20 {
+                        for direction in [Direction::Left, Direction::Right] {
+                            let command = Command::new(direction, steps);
+                            let dial = Dial::new(size, start).unwrap();
+
+                            let mut clicks = Dial::new(size, start).unwrap();
+                            let mut expected = 0;
+                            for _ in 0..steps {
+                                clicks.move_position(&Command::new(direction, 1));
+                                if clicks.current_position == target {
+                                    expected += 1;
+                                }
+                            }
+
+                            assert_eq!(dial.count_visits(&command, target), expected);
+                        }
+                    }
+                }
+            }
+        }
+
+        let dial = Dial::new(100, 99).unwrap();
+        assert_eq!(
+            dial.count_zeros(&Command::new(Direction::Right, u64::MAX)),
+            (u64::MAX - 1) / 100 + 1
+        );
+    }
+
+    #[test]
+    fn test_dial_bank() {
+        let input = include_str!("sample_input.txt");
+        let options = [
+            DialOptions::default(),
+            DialOptions { size: 10, start: 0 },
+            DialOptions { size: 7, start: 3 },
+            DialOptions { size: 1, start: 0 },
+        ];
+
+        let mut bank: DialBank = options
+            .iter()
+            .map(|options| Dial::new(options.size, options.start).unwrap())
+            .collect();
+        assert_eq!(bank.zero_hits(), [0, 0, 0, 0]);
+        assert_eq!(bank.simulate(input), Ok(()));
+
+        let expected: Vec<u64> = options
+            .iter()
+            .map(|options| solution_part_2_with_options(input, options).unwrap())
+            .collect();
+        assert_eq!(bank.zero_hits(), expected);
+        assert_eq!(bank.dials()[0].current_position, 32);
+
+        // Every click lands on `0` on a dial of one position
+        assert_eq!(bank.zero_hits()[3], 462);
+
+        let mut bank = DialBank::new(vec![Dial::default()]);
+        assert_eq!(
+            bank.simulate("L50\nR\nL100"),
+            Err(Day1Error::InvalidSteps {
+                line: 2,
+                steps: String::new()
+            })
+        );
+        assert_eq!(bank.zero_hits(), [1]);
+
+        // The one position dial overflows, and no dial moves
+        let mut bank = DialBank::new(vec![Dial::default(), Dial::new(1, 0).unwrap()]);
+        let turn = Command::new(Direction::Right, u64::MAX);
+        assert_eq!(bank.apply(&turn), Ok(()));
+        assert_eq!(bank.apply(&turn), Err(Day1Error::Overflow));
+        assert_eq!(bank.zero_hits()[1], u64::MAX);
+        assert_eq!(bank.dials()[0].current_position, 65);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_count_hits_in_set() {
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..500 {
+            let size = rng.below(20) + 1;
+            let start = rng.below(size);
+            let targets: HashSet<u64> = (0..rng.below(6)).map(|_| rng.below(size + 2)).collect();
+            let direction = if rng.below(2) == 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            let steps = rng.below(60);
+
+            let mut clicks = Dial::new(size, start).unwrap();
+            let mut expected = 0;
+            for _ in 0..steps {
+                clicks.move_position(&Command::new(direction, 1));
+                if targets.contains(&clicks.current_position) {
+                    expected += 1;
+                }
+            }
+
+            let dial = Dial::new(size, start).unwrap();
+            assert_eq!(
+                dial.count_hits_in_set(&Command::new(direction, steps), &targets),
+                expected
+            );
+        }
+
+        let dial = Dial::new(1 << 40, 0).unwrap();
+        assert_eq!(
+            dial.count_hits_in_set(
+                &Command::new(Direction::Left, u64::MAX),
+                &HashSet::from([0, 1])
+            ),
+            (1 << 25) - 1
+        );
+        assert_eq!(
+            dial.count_hits_in_set(&Command::new(Direction::Left, 5), &HashSet::new()),
+            0
+        );
+    }
+
+    #[cfg(feature = "slow-reference")]
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_reference_matches_counting() {
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..2000 {
+            let size = rng.below(30) + 1;
+            let start = rng.below(size);
+            let direction = if rng.below(2) == 0 {
+                Direction::Left
+            } else {
+                Direction::Right
+            };
+            let command = Command::new(direction, rng.below(200));
+            let target = rng.below(size + 1);
+            let targets: HashSet<u64> = (0..rng.below(5)).map(|_| rng.below(size)).collect();
+
+            let mut dial = Dial::new(size, start).unwrap();
+            let clicks = dial.simulate_clicks(&command);
+            let hits = |target: u64| clicks.iter().filter(|&&click| click == target).count();
+
+            assert_eq!(clicks.len() as u64, command.steps);
+            assert_eq!(dial.count_zeros(&command), hits(0) as u64);
+            assert_eq!(dial.count_visits(&command, target), hits(target) as u64);
+            assert_eq!(
+                dial.count_hits_in_set(&command, &targets),
+                clicks
+                    .iter()
+                    .filter(|click| targets.contains(click))
+                    .count() as u64
+            );
+
+            dial.move_position(&command);
+            assert_eq!(dial.current_position, *clicks.last().unwrap_or(&start));
+        }
+    }
+
+    #[test]
+    fn test_positions_after() {
+        let commands: Vec<Command> = CommandStream::new(include_str!("sample_input.txt"))
+            .map(Result::unwrap)
+            .collect();
+
+        let mut dial = Dial::default();
+        let positions: Vec<u64> = dial.positions_after(commands.iter().copied()).collect();
+        assert_eq!(positions, [82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+        assert_eq!(dial.current_position, 32);
+
+        // Part 1 counts the positions at `0`
+        let mut dial = Dial::default();
+        let zeros = dial
+            .positions_after(commands.iter().copied())
+            .filter(|&position| position == 0)
+            .count();
+        assert_eq!(zeros, 3);
+
+        // Only the positions taken are moved to
+        let mut dial = Dial::default();
+        assert_eq!(dial.positions_after(commands).nth(1), Some(52));
+        assert_eq!(dial.current_position, 52);
+    }
+
+    #[test]
+    fn test_undo() {
+        let commands: Vec<Command> = CommandStream::new(include_str!("sample_input.txt"))
+            .map(Result::unwrap)
+            .collect();
+
+        let mut dial = Dial::new(7, 3).unwrap();
+        let positions: Vec<u64> = dial.positions_after(commands.iter().copied()).collect();
+
+        // Replaying backwards passes the same positions in reverse
+        for (index, command) in commands.iter().enumerate().rev() {
+            assert_eq!(dial.current_position, positions[index]);
+            dial.undo(command);
+        }
+        assert_eq!(dial.current_position, 3);
+
+        let command = Command::new(Direction::Left, 5);
+        assert_eq!(command.inverse(), Command::new(Direction::Right, 5));
+        assert_eq!(command.inverse().inverse(), command);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let commands: Vec<Command> = CommandStream::new(include_str!("sample_input.txt"))
+            .map(Result::unwrap)
+            .collect();
+        let (first, second) = commands.split_at(4);
+
+        let mut dial = Dial::new(7, 3).unwrap();
+        dial.positions_after(first.iter().copied()).for_each(drop);
+        let snapshot = dial.snapshot();
+
+        let mut resumed = Dial::default();
+        resumed.restore(&snapshot).unwrap();
+        assert_eq!(resumed, dial);
+
+        dial.positions_after(second.iter().copied()).for_each(drop);
+        resumed
+            .positions_after(second.iter().copied())
+            .for_each(drop);
+        assert_eq!(resumed, dial);
+
+        assert_eq!(
+            resumed.restore(&DialOptions { size: 7, start: 7 }),
+            Err(Day1Error::InvalidDial { size: 7, start: 7 })
+        );
+        assert_eq!(resumed, dial);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let mut dial = Dial::default();
+        dial.move_position(&Command::new(Direction::Left, 68));
+
+        let json = serde_json::to_string(&dial).unwrap();
+        assert_eq!(json, r#"{"size":100,"start":82}"#);
+        assert_eq!(serde_json::from_str::<Dial>(&json).unwrap(), dial);
+        assert!(serde_json::from_str::<Dial>(r#"{"size":100,"start":100}"#).is_err());
+
+        let command = Command::new(Direction::Right, 48);
+        let json = serde_json::to_string(&command).unwrap();
+        assert_eq!(json, r#"{"direction":"Right","steps":48}"#);
+        assert_eq!(serde_json::from_str::<Command>(&json).unwrap(), command);
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(
+            Turn::try_from(Command::new(Direction::Left, 68)),
+            Ok(Turn(-68))
+        );
+        assert_eq!(
+            Turn::try_from(Command::new(Direction::Right, 14)),
+            Ok(Turn(14))
+        );
+        assert!(Turn::try_from(Command::new(Direction::Right, u64::MAX)).is_err());
+        assert_eq!(Command::from(Turn(-5)), Command::new(Direction::Left, 5));
+        assert_eq!(Command::from(Turn(0)), Command::new(Direction::Right, 0));
+        assert_eq!(
+            Command::from(Turn(i64::MIN)),
+            Command::new(Direction::Left, 1 << 63)
+        );
+        assert_eq!(Turn(3) + -Turn(5), Turn(-2));
+
+        // Summing the turns first ends where applying them one by one does
+        let turns: Vec<Turn> = CommandStream::new(include_str!("sample_input.txt"))
+            .map(|command| Turn::try_from(command.unwrap()).unwrap())
+            .collect();
+        let mut one_by_one = Dial::default();
+        for &turn in &turns {
+            one_by_one.apply_turn(turn);
+        }
+        let mut summed = Dial::default();
+        summed.apply_turn(turns.iter().copied().sum());
+        assert_eq!(summed.current_position, one_by_one.current_position);
+        assert_eq!(summed.current_position, 32);
+
+        let mut dial = Dial::new(7, 3).unwrap();
+        dial.apply_turn(Turn(i64::MIN));
+        let mut expected = Dial::new(7, 3).unwrap();
+        expected.move_position(&Command::new(Direction::Left, 1 << 63));
+        assert_eq!(dial.current_position, expected.current_position);
+    }
+
+    #[test]
+    fn test_turn_overflow() {
+        assert_eq!(Turn(i64::MAX).checked_add(Turn(1)), None);
+        assert_eq!(Turn(i64::MIN).checked_add(Turn(-1)), None);
+        assert_eq!(Turn(i64::MAX).checked_add(Turn(i64::MIN)), Some(Turn(-1)));
+        assert_eq!(Turn(i64::MIN).checked_neg(), None);
+        assert_eq!(Turn(i64::MAX).checked_neg(), Some(Turn(-i64::MAX)));
+
+        // Partial sums may not overflow, even if the total would fit
+        let turns = [Turn(i64::MAX), Turn(1), Turn(-1)];
+        assert!(
+            turns
+                .into_iter()
+                .try_fold(Turn(0), Turn::checked_add)
+                .is_none()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "turn overflows i64")]
+    fn test_turn_sum_overflow_panics() {
+        let _: Turn = [Turn(i64::MAX), Turn(1)].into_iter().sum();
+    }
+
+    #[test]
+    #[should_panic(expected = "turn overflows i64")]
+    fn test_turn_neg_overflow_panics() {
+        let _ = -Turn(i64::MIN);
+    }
+
+    #[test]
+    fn test_command_stream_skip_invalid() {
+        let commands: Vec<Command> = CommandStream::new("L5\n\nR7x\nR7\n")
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                Command::new(Direction::Left, 5),
+                Command::new(Direction::Right, 7)
+            ]
+        );
+
+        let errors: Vec<Day1Error> = CommandStream::new("L5\n\nR7x\nR7\n")
+            .filter_map(Result::err)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                Day1Error::EmptyLine { line: 2 },
+                Day1Error::InvalidSteps {
+                    line: 3,
+                    steps: "7x".to_string()
+                }
+            ]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_solve_parallel() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(solve_parallel(input, &DialOptions::default()), Ok((3, 6)));
+        assert_eq!(solve_parallel("", &DialOptions::default()), Ok((0, 0)));
+
+        let mut rng = TestRng::new(1);
+
+        // Enough lines for several chunks
+        let mut input = String::new();
+        for _ in 0..100_000 {
+            let direction = if rng.below(2) == 0 { "L" } else { "R" };
+            input.push_str(&format!("{direction}{}\n", rng.below(1000)));
+        }
+
+        for options in [
+            DialOptions::default(),
+            DialOptions { size: 7, start: 3 },
+            DialOptions { size: 1, start: 0 },
+        ] {
+            assert_eq!(
+                solve_parallel(&input, &options),
+                Ok((
+                    solution_part_1_with_options(&input, &options).unwrap(),
+                    solution_part_2_with_options(&input, &options).unwrap()
+                ))
+            );
+        }
+
+        // The first invalid line is reported, even in a later chunk
+        input.push_str("X1\nR\n");
+        assert_eq!(
+            solve_parallel(&input, &DialOptions::default()),
+            Err(Day1Error::InvalidDirection {
+                line: 100_001,
+                direction: "X".to_string()
+            })
+        );
+        assert_eq!(
+            solve_parallel(&input, &DialOptions { size: 0, start: 0 }),
+            Err(Day1Error::InvalidDial { size: 0, start: 0 })
+        );
+    }
+
+    #[test]
+    fn test_binary_format() {
+        let input = include_str!("sample_input.txt");
+        let commands: Vec<Command> = CommandStream::new(input).map(Result::unwrap).collect();
+
+        let mut edge_cases = commands.clone();
+        edge_cases.extend([
+            Command::new(Direction::Left, 0),
+            Command::new(Direction::Right, 63),
+            Command::new(Direction::Left, 64),
+            Command::new(Direction::Right, u64::MAX),
+        ]);
+        let bytes = encode(edge_cases.iter().copied());
+        let decoded: Vec<Command> = BinaryCommands::new(&bytes).map(Result::unwrap).collect();
+        assert_eq!(decoded, edge_cases);
+        assert_eq!(encode([Command::new(Direction::Right, 63)]), [0x7f]);
+        assert_eq!(encode([Command::new(Direction::Left, 64)]), [0x80, 0x01]);
+        assert_eq!(encode([Command::new(Direction::Right, u64::MAX)]).len(), 10);
+
+        for options in [DialOptions::default(), DialOptions { size: 7, start: 3 }] {
+            let expected = (
+                solution_part_1_with_options(input, &options).unwrap(),
+                solution_part_2_with_options(input, &options).unwrap(),
+            );
+            assert_eq!(
+                solve(input.as_bytes(), InputFormat::Text, &options),
+                Ok(expected)
+            );
+            assert_eq!(
+                solve(
+                    &encode(commands.iter().copied()),
+                    InputFormat::Binary,
+                    &options
+                ),
+                Ok(expected)
+            );
+        }
+
+        // A command cut off at the end
+        let mut bytes = encode(commands.iter().copied());
+        let offset = bytes.len();
+        bytes.push(0x80);
+        assert_eq!(
+            solve(&bytes, InputFormat::Binary, &DialOptions::default()),
+            Err(Day1Error::InvalidEncoding { offset })
+        );
+
+        // Steps too large for a `u64`, and a varint longer than ten bytes
+        let mut too_large = vec![0xff; 9];
+        too_large.push(0x07);
+        let mut too_long = vec![0x80; 10];
+        too_long.push(0x00);
+        for bytes in [too_large, too_long] {
+            let mut decoded = BinaryCommands::new(&bytes);
+            assert_eq!(
+                decoded.next(),
+                Some(Err(Day1Error::InvalidEncoding { offset: 0 }))
+            );
+            assert_eq!(decoded.next(), None);
+        }
+
+        assert_eq!(
+            solve(b"L1\nR\xff", InputFormat::Text, &DialOptions::default()),
+            Err(Day1Error::InvalidEncoding { offset: 4 })
+        );
+
+        #[cfg(feature = "std")]
+        {
+            assert_eq!(InputFormat::from_path("input.bin"), InputFormat::Binary);
+            assert_eq!(InputFormat::from_path("input.txt"), InputFormat::Text);
+            assert_eq!(InputFormat::from_path("bin"), InputFormat::Text);
+        }
+    }
+
+    #[test]
+    fn test_program() {
+        let program: Program = CommandStream::new(include_str!("sample_input.txt"))
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(program.commands().len(), 10);
+
+        // The sample ends at 32, 18 to the left of the start
+        assert_eq!(
+            program.canonical(100).commands(),
+            [Command::new(Direction::Left, 18)]
+        );
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let size = rng.below(12) + 1;
+            let program: Program = (0..rng.below(8))
+                .map(|_| {
+                    let direction = if rng.below(2) == 0 {
+                        Direction::Left
+                    } else {
+                        Direction::Right
+                    };
+                    Command::new(direction, rng.below(30))
+                })
+                .collect();
+            let canonical = program.canonical(size);
+            let merged = program.merge_runs();
+
+            assert!(canonical.commands().len() <= 1);
+            assert!(
+                canonical
+                    .commands()
+                    .iter()
+                    .all(|command| 0 < command.steps && 2 * command.steps <= size)
+            );
+            assert_eq!(canonical.canonical(size), canonical);
+            assert!(program.equivalent(&canonical, size));
+            assert!(
+                merged
+                    .commands()
+                    .windows(2)
+                    .all(|pair| pair[0].direction != pair[1].direction)
+            );
+
+            for start in 0..size {
+                let run = |program: &Program| {
+                    let mut dial = Dial::new(size, start).unwrap();
+                    let mut zeros = 0;
+                    for command in program.commands() {
+                        zeros += dial.count_zeros(command);
+                        dial.move_position(command);
+                    }
+                    (dial.current_position, zeros)
+                };
+
+                assert_eq!(run(&canonical).0, run(&program).0);
+                assert_eq!(run(&merged), run(&program));
+            }
+        }
+
+        let left = Program::new(vec![Command::new(Direction::Left, 3)]);
+        let right = Program::new(vec![Command::new(Direction::Right, 7)]);
+        assert!(left.equivalent(&right, 10));
+        assert!(!left.equivalent(&right, 11));
+        assert_eq!(
+            Program::new(vec![Command::new(Direction::Left, 5)]).canonical(10),
+            Program::new(vec![Command::new(Direction::Right, 5)])
+        );
+        assert_eq!(
+            Program::new(vec![
+                Command::new(Direction::Right, u64::MAX),
+                Command::new(Direction::Right, 1)
+            ])
+            .merge_runs()
+            .commands()
+            .len(),
+            2
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_render() {
+        let mut dial = Dial::new(12, 0).unwrap();
+        assert_eq!(dial.render_compact(), "@........... 0/12");
+        assert!(dial.to_string().starts_with("            @\n"));
+
+        dial.move_position(&Command::new(Direction::Right, 3));
+        assert_eq!(dial.render_compact(), "0..*........ 3/12");
+        assert_eq!(
+            dial.to_string(),
+            [
+                "            0",
+                "      .           .",
+                "",
+                "  .                   .",
+                "",
+                "",
+                ".         3/12          *",
+                "",
+                "",
+                "  .                   .",
+                "",
+                "      .           .",
+                "            .",
+            ]
+            .join("\n")
+        );
+
+        let dial = Dial::default();
+        assert_eq!(
+            dial.render_compact(),
+            "0.......................*....................... 50/100"
+        );
+        assert_eq!(Dial::new(1, 0).unwrap().render_compact(), "@ 0/1");
+    }
+
+    #[test]
+    fn test_observer() {
+        /// Counts the visits of every position and the clicks on `0`.
+        #[derive(Default)]
+        struct Statistics {
+            visits: HashMap<u64, usize>,
+            moves: usize,
+            zero_hits: u64,
+            last: Option<u64>,
+        }
+
+        impl Observer for Statistics {
+            fn on_move(&mut self, _command: &Command, from: u64, to: u64) {
+                assert!(self.last.is_none_or(|last| last == from));
+                self.last = Some(to);
+                self.moves += 1;
+                *self.visits.entry(to).or_default() += 1;
+            }
+
+            fn on_zero_hit(&mut self, _command: &Command, hits: u64) {
+                assert!(hits > 0);
+                self.zero_hits += hits;
+            }
+        }
+
+        let input = include_str!("sample_input.txt");
+        let mut statistics = Statistics::default();
+        assert_eq!(
+            solve_observed(
+                input.as_bytes(),
+                InputFormat::Text,
+                &DialOptions::default(),
+                &mut statistics
+            ),
+            Ok((3, 6))
+        );
+        assert_eq!(statistics.moves, 10);
+        assert_eq!(statistics.zero_hits, 6);
+        assert_eq!(statistics.visits[&0], 3);
+        assert_eq!(statistics.last, Some(32));
+
+        // The moves before an invalid line are reported
+        let mut statistics = Statistics::default();
+        assert!(
+            solve_observed(
+                b"L50\nL100\nX",
+                InputFormat::Text,
+                &DialOptions::default(),
+                &mut statistics
+            )
+            .is_err()
+        );
+        assert_eq!(statistics.moves, 2);
+        assert_eq!(statistics.zero_hits, 2);
+
+        let mut dial = Dial::new(1, 0).unwrap();
+        assert_eq!(
+            dial.move_with_events(&Command::new(Direction::Left, u64::MAX), &mut ()),
+            u64::MAX
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_from_reader() {
+        let input = include_str!("sample_input.txt");
+        for options in [DialOptions::default(), DialOptions { size: 7, start: 3 }] {
+            assert_eq!(
+                solution_part_1_from_reader(input.as_bytes(), &options),
+                solution_part_1_with_options(input, &options)
+            );
+            assert_eq!(
+                solution_part_2_from_reader(input.as_bytes(), &options),
+                solution_part_2_with_options(input, &options)
+            );
+        }
+
+        // Windows line endings
+        let crlf = input.replace('\n', "\r\n");
+        assert_eq!(
+            solution_part_2_from_reader(crlf.as_bytes(), &DialOptions::default()),
+            Ok(6)
+        );
+
+        assert_eq!(
+            solution_part_1_from_reader(&b"L1\n\nR2"[..], &DialOptions::default()),
+            Err(Day1Error::EmptyLine { line: 2 })
+        );
+        assert_eq!(
+            solution_part_1_from_reader(&b"L1\nR\xff"[..], &DialOptions::default()),
+            Err(Day1Error::Io(io::ErrorKind::InvalidData))
+        );
+    }
+
     #[test]
     fn test_sample_input_part_1() {
         let result = solution_part_1(include_str!("sample_input.txt"));
-        assert_eq!(result, 3);
+        assert_eq!(result, Ok(3));
     }
 
     #[test]
     fn test_sample_input_part_2() {
         let result = solution_part_2(include_str!("sample_input.txt"));
-        assert_eq!(result, 6);
+        assert_eq!(result, Ok(6));
     }
 }
@@ -0,0 +1,91 @@
+//! Command line interface for Advent of Code Day 1 — Secret Entrance
+//!
+//! Usage: `day_1 --input <input file> --part <1|2>`
+//!
+//! Prints the answer to one part and the time it took to solve. The input
+//! file `-` is read from stdin. Text inputs are read line by line, so they
+//! never have to fit in memory at once, and a file with the extension `bin`
+//! is read in the binary format of [`day_1::encode`].
+
+use day_1::{
+    Day1Error, DialOptions, InputFormat, solution_part_1_from_reader, solution_part_2_from_reader,
+    solve,
+};
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::time::Instant;
+use std::{env, process};
+
+const USAGE: &str = "Usage: day_1 --input <input file> --part <1|2>";
+
+fn main() {
+    let mut path = None;
+    let mut part = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => path = args.next(),
+            "--part" => {
+                part = match args.next().as_deref() {
+                    Some("1") => Some(1),
+                    Some("2") => Some(2),
+                    _ => None,
+                }
+            }
+            _ => {
+                eprintln!("{USAGE}");
+                process::exit(2);
+            }
+        }
+    }
+
+    let (Some(path), Some(part)) = (path, part) else {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    };
+
+    let options = DialOptions::default();
+    let start = Instant::now();
+    let answer = if path == "-" {
+        solve_reader(io::stdin().lock(), part, &options)
+    } else if InputFormat::from_path(&path) == InputFormat::Binary {
+        let input = fs::read(&path).unwrap_or_else(|err| {
+            eprintln!("Could not read {path}: {err}");
+            process::exit(1);
+        });
+        solve(&input, InputFormat::Binary, &options)
+            .map(|(part_1, part_2)| if part == 1 { part_1 } else { part_2 })
+    } else {
+        let file = File::open(&path).unwrap_or_else(|err| {
+            eprintln!("Could not read {path}: {err}");
+            process::exit(1);
+        });
+        solve_reader(BufReader::new(file), part, &options)
+    };
+    let elapsed = start.elapsed();
+
+    match answer {
+        Ok(answer) => {
+            println!("Part {part}: {answer}");
+            println!("Elapsed: {elapsed:?}");
+        }
+        Err(err) => {
+            eprintln!("Could not solve {path}: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+/// The answer to `part` for the text input read from `reader`.
+fn solve_reader(
+    reader: impl io::BufRead,
+    part: u8,
+    options: &DialOptions,
+) -> Result<u64, Day1Error> {
+    if part == 1 {
+        solution_part_1_from_reader(reader, options)
+    } else {
+        solution_part_2_from_reader(reader, options)
+    }
+}
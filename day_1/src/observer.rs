@@ -0,0 +1,60 @@
+// Events of a moving dial
+//
+// Code that wants to follow the dial, like a visualizer or a statistics
+// collector, implements `Observer` and is told about every move and every
+// move that clicks on `0`. A move of many full turns clicks on `0` many
+// times, so the hits of a move are reported at once as a count, and huge
+// step counts stay as cheap as they are for `count_zeros`.
+
+use crate::{Command, Dial};
+
+/// Receives the events of [`Dial::move_with_events`]. Both methods do
+/// nothing by default.
+pub trait Observer {
+    /// The dial moved from position `from` to position `to` by `command`.
+    fn on_move(&mut self, _command: &Command, _from: u64, _to: u64) {}
+
+    /// The move by `command` clicked on `0` `hits` times, which is at least
+    /// once. Called after [`Observer::on_move`] for the same command.
+    fn on_zero_hit(&mut self, _command: &Command, _hits: u64) {}
+}
+
+/// Ignores every event.
+impl Observer for () {}
+
+impl Dial {
+    /// Apply `command` like [`Dial::move_position`], telling `observer`
+    /// about the move and the clicks on `0`. Returns the number of clicks on
+    /// `0`, like [`Dial::count_zeros`].
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction, Observer};
+    ///
+    /// #[derive(Default)]
+    /// struct Hits(Vec<u64>);
+    ///
+    /// impl Observer for Hits {
+    ///     fn on_zero_hit(&mut self, _command: &Command, hits: u64) {
+    ///         self.0.push(hits);
+    ///     }
+    /// }
+    ///
+    /// let mut dial = Dial::default();
+    /// let mut hits = Hits::default();
+    /// dial.move_with_events(&Command::new(Direction::Right, 250), &mut hits);
+    /// dial.move_with_events(&Command::new(Direction::Left, 10), &mut hits);
+    /// assert_eq!(hits.0, [3]);
+    /// ```
+    pub fn move_with_events(&mut self, command: &Command, observer: &mut impl Observer) -> u64 {
+        let from = self.current_position;
+        let hits = self.count_zeros(command);
+        self.move_position(command);
+
+        observer.on_move(command, from, self.current_position);
+        if hits > 0 {
+            observer.on_zero_hit(command, hits);
+        }
+
+        hits
+    }
+}
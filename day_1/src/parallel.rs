@@ -0,0 +1,110 @@
+// Solving both parts on a rayon thread pool
+//
+// A command moves the dial by its steps modulo the size, whatever the
+// position it starts from, so the position after a run of commands is the
+// position before it plus their summed displacement. The input is split
+// into chunks of whole lines, and the displacement of every chunk is summed
+// in parallel. A sequential scan over the chunks then gives the position the
+// dial enters each of them at, and from there every chunk is simulated in
+// parallel again, counting the zeros of both parts.
+//
+// Every chunk is parsed twice, which doubles the work but keeps the memory
+// bounded by the input itself. Lines that fail to parse are skipped by the
+// first pass, and the second pass reports the first of them in input order.
+
+use crate::{Day1Error, Dial, DialOptions, Direction, parse_line};
+use rayon::prelude::*;
+
+/// The number of bytes after which a chunk ends at the next line break.
+const CHUNK_BYTES: usize = 1 << 16;
+
+/// Solve both parts on the dial described by `options`, spreading the input
+/// over the rayon thread pool. The answers equal those of
+/// [`crate::solution_part_1_with_options`] and
+/// [`crate::solution_part_2_with_options`], and so does the error of an
+/// invalid input.
+pub fn solve_parallel(input: &str, options: &DialOptions) -> Result<(u64, u64), Day1Error> {
+    let dial = Dial::new(options.size, options.start)?;
+    let size = u128::from(dial.size);
+    let chunks = chunks(input);
+
+    // The number of lines and the displacement of every chunk
+    let summaries: Vec<(usize, u128)> = chunks
+        .par_iter()
+        .map(|chunk| {
+            chunk.lines().fold((0, 0), |(lines, displacement), line| {
+                let steps = match parse_line(0, line) {
+                    Ok(command) => match command.direction {
+                        Direction::Right => u128::from(command.steps) % size,
+                        Direction::Left => size - u128::from(command.steps) % size,
+                    },
+                    Err(_) => 0,
+                };
+
+                (lines + 1, (displacement + steps) % size)
+            })
+        })
+        .collect();
+
+    // The line number before and the dial position at the start of every
+    // chunk
+    let mut starts = Vec::with_capacity(chunks.len());
+    let (mut line, mut position) = (0, u128::from(dial.current_position));
+    for (lines, displacement) in summaries {
+        starts.push((line, position as u64));
+        line += lines;
+        position = (position + displacement) % size;
+    }
+
+    let counts: Vec<Result<(u64, u64), Day1Error>> = chunks
+        .par_iter()
+        .zip(starts)
+        .map(|(chunk, (line, start))| {
+            let mut dial = Dial::new(dial.size, start)?;
+            let (mut part_1, mut part_2): (u64, u64) = (0, 0);
+
+            for (index, text) in chunk.lines().enumerate() {
+                let command = parse_line(line + index + 1, text)?;
+
+                part_2 = part_2
+                    .checked_add(dial.count_zeros(&command))
+                    .ok_or(Day1Error::Overflow)?;
+                dial.move_position(&command);
+                if dial.current_position == 0 {
+                    part_1 += 1;
+                }
+            }
+
+            Ok((part_1, part_2))
+        })
+        .collect();
+
+    counts
+        .into_iter()
+        .try_fold((0, 0), |(part_1, part_2): (u64, u64), counts| {
+            let (chunk_1, chunk_2) = counts?;
+            let part_2 = part_2.checked_add(chunk_2).ok_or(Day1Error::Overflow)?;
+            Ok((part_1 + chunk_1, part_2))
+        })
+}
+
+/// Split `input` into chunks of whole lines of about [`CHUNK_BYTES`] bytes.
+fn chunks(input: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let end = match rest.as_bytes().get(CHUNK_BYTES..) {
+            Some(tail) => tail
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(rest.len(), |offset| CHUNK_BYTES + offset + 1),
+            None => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+
+    chunks
+}
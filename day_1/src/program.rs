@@ -0,0 +1,114 @@
+// Sequences of commands and their canonical form
+//
+// Only the displacement modulo the size of the dial decides where a program
+// leaves it, so on a dial of a given size every program is equivalent to a
+// single command of at most half a turn, or to no command at all. That
+// command is the canonical form: two programs are equivalent exactly when
+// their canonical forms are equal. The clicks on `0` along the way are not
+// preserved, except by `merge_runs`, which only joins commands turning the
+// same way.
+
+use crate::{Command, Direction};
+use alloc::vec::Vec;
+
+/// A sequence of commands.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Program {
+    commands: Vec<Command>,
+}
+
+impl Program {
+    /// Create a program of `commands`, applied in order.
+    pub fn new(commands: Vec<Command>) -> Self {
+        Self { commands }
+    }
+
+    /// The commands of the program.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// The program with every run of commands turning the same way joined
+    /// into one command, as long as its steps fit in a `u64`.
+    ///
+    /// This keeps both the final position and the clicks on `0` of every
+    /// dial.
+    pub fn merge_runs(&self) -> Program {
+        let mut commands: Vec<Command> = Vec::with_capacity(self.commands.len());
+
+        for &command in &self.commands {
+            if let Some(last) = commands.last_mut()
+                && last.direction == command.direction
+                && let Some(steps) = last.steps.checked_add(command.steps)
+            {
+                last.steps = steps;
+            } else {
+                commands.push(command);
+            }
+        }
+
+        Program::new(commands)
+    }
+
+    /// The smallest program leaving a dial of `size` positions where this
+    /// program does: no command for a whole number of turns, and otherwise
+    /// one command of at most half a turn. Half a turn exactly turns right.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is `0`.
+    ///
+    /// ```
+    /// use day_1::{Command, Direction, Program};
+    ///
+    /// let program = Program::new(vec![
+    ///     Command::new(Direction::Left, 68),
+    ///     Command::new(Direction::Left, 30),
+    ///     Command::new(Direction::Right, 48),
+    ///     Command::new(Direction::Left, 5),
+    /// ]);
+    /// assert_eq!(
+    ///     program.canonical(100).commands(),
+    ///     [Command::new(Direction::Right, 45)]
+    /// );
+    /// ```
+    pub fn canonical(&self, size: u64) -> Program {
+        assert!(size > 0, "A dial needs at least one position");
+
+        let size = u128::from(size);
+        let displacement = self.commands.iter().fold(0, |displacement, command| {
+            let steps = u128::from(command.steps) % size;
+            match command.direction {
+                Direction::Right => (displacement + steps) % size,
+                Direction::Left => (displacement + size - steps) % size,
+            }
+        });
+
+        // Both fit in a `u64`, as they are below `size`
+        let command = if displacement == 0 {
+            None
+        } else if 2 * displacement <= size {
+            Some(Command::new(Direction::Right, displacement as u64))
+        } else {
+            Some(Command::new(Direction::Left, (size - displacement) as u64))
+        };
+
+        Program::new(command.into_iter().collect())
+    }
+
+    /// Whether this program and `other` leave every dial of `size` positions
+    /// at the same position.
+    ///
+    /// # Panics
+    ///
+    /// If `size` is `0`.
+    pub fn equivalent(&self, other: &Program, size: u64) -> bool {
+        self.canonical(size) == other.canonical(size)
+    }
+}
+
+impl FromIterator<Command> for Program {
+    fn from_iter<I: IntoIterator<Item = Command>>(commands: I) -> Self {
+        Self::new(commands.into_iter().collect())
+    }
+}
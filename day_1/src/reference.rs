@@ -0,0 +1,42 @@
+// Reference click-by-click simulator for Day 1: Secret Entrance
+//
+// The solutions never turn the dial one click at a time: they count the hits
+// of a position with a division. This module does turn it click by click,
+// exactly like the puzzle describes it, and keeps every position it passes.
+// That takes time and memory proportional to the number of steps, but is
+// simple enough to check the counting against on small commands.
+
+use crate::{Command, Dial, Direction};
+use alloc::vec::Vec;
+
+impl Dial {
+    /// The position after every click of `command`, without moving the dial.
+    /// The last one is where [`Dial::move_position`] leaves the dial, and
+    /// counting a position gives [`Dial::count_visits`].
+    ///
+    /// Takes time and memory proportional to the number of steps.
+    ///
+    /// ```
+    /// use day_1::{Command, Dial, Direction};
+    ///
+    /// let dial = Dial::new(5, 3).unwrap();
+    /// let clicks = dial.simulate_clicks(&Command::new(Direction::Right, 4));
+    /// assert_eq!(clicks, [4, 0, 1, 2]);
+    /// ```
+    pub fn simulate_clicks(&self, command: &Command) -> Vec<u64> {
+        let mut position = self.current_position;
+        let mut clicks = Vec::new();
+
+        for _ in 0..command.steps {
+            position = match command.direction {
+                Direction::Right if position == self.size - 1 => 0,
+                Direction::Right => position + 1,
+                Direction::Left if position == 0 => self.size - 1,
+                Direction::Left => position - 1,
+            };
+            clicks.push(position);
+        }
+
+        clicks
+    }
+}
@@ -0,0 +1,92 @@
+// ASCII drawings of the dial
+//
+// The face is a circle of ticks with `0` at the top, and positions increase
+// clockwise, the way a right turn moves the dial. Large dials get at most
+// `MAX_TICKS` ticks, evenly spaced, but `0` and the current position are
+// always drawn: `0` as `0`, the current position as `*`, and `@` when the
+// dial points at `0`. The compact form lays the same marks out on a line.
+// The face needs the trigonometry of `std`, the compact form does not.
+
+use crate::Dial;
+use alloc::format;
+use alloc::string::String;
+#[cfg(feature = "std")]
+use std::{f64::consts::PI, fmt, vec::Vec};
+
+/// The radius of the face in rows. A column is half as wide as a row is
+/// high, so the face is twice as many columns wide.
+#[cfg(feature = "std")]
+const RADIUS: usize = 6;
+
+/// The most ticks on the face, or characters in the compact form.
+const MAX_TICKS: u64 = 48;
+
+impl Dial {
+    /// The dial on a single line: a mark per tick from `0` up, followed by
+    /// the current position and the size.
+    ///
+    /// ```
+    /// let dial = day_1::Dial::new(10, 3).unwrap();
+    /// assert_eq!(dial.render_compact(), "0..*...... 3/10");
+    /// ```
+    pub fn render_compact(&self) -> String {
+        let ticks = self.size.min(MAX_TICKS);
+        let current = self.tick(ticks);
+
+        let marks: String = (0..ticks).map(|tick| mark(tick, current)).collect();
+        format!("{marks} {}/{}", self.current_position, self.size)
+    }
+
+    /// The tick of `ticks` evenly spaced ones closest below the current
+    /// position.
+    fn tick(&self, ticks: u64) -> u64 {
+        (u128::from(self.current_position) * u128::from(ticks) / u128::from(self.size)) as u64
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for Dial {
+    /// The dial face, with the current position and the size in its middle.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ticks = self.size.min(MAX_TICKS);
+        let current = self.tick(ticks);
+
+        let mut grid = vec![vec![' '; 4 * RADIUS + 1]; 2 * RADIUS + 1];
+        let mut draw = |tick: u64| {
+            let angle = 2.0 * PI * tick as f64 / ticks as f64;
+            let column = (2.0 * RADIUS as f64 * (1.0 + angle.sin())).round() as usize;
+            let row = (RADIUS as f64 * (1.0 - angle.cos())).round() as usize;
+            grid[row][column] = mark(tick, current);
+        };
+        for tick in 1..ticks {
+            draw(tick);
+        }
+        // Drawn last, so nearby ticks never hide them
+        draw(current);
+        draw(0);
+
+        let label: Vec<char> = format!("{}/{}", self.current_position, self.size)
+            .chars()
+            .collect();
+        let start = (2 * RADIUS).saturating_sub(label.len() / 2);
+        for (column, &c) in grid[RADIUS].iter_mut().skip(start).zip(&label) {
+            *column = c;
+        }
+
+        let lines: Vec<String> = grid
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// The character of `tick` on a dial pointing at tick `current`.
+fn mark(tick: u64, current: u64) -> char {
+    match (tick == 0, tick == current) {
+        (true, true) => '@',
+        (true, false) => '0',
+        (false, true) => '*',
+        (false, false) => '.',
+    }
+}
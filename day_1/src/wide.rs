@@ -0,0 +1,195 @@
+// Dials with sizes and step counts beyond u64
+//
+// A `Dial` holds its size, position and step counts in `u64`s, which every
+// puzzle input fits in. A `WideDial` holds them in `u128`s instead, for
+// generated inputs whose dials or rotations do not fit in a `u64`. Both move
+// and count with the same arithmetic, which never adds past the size of the
+// dial, so no single command overflows; only the total of part 2 can, and it
+// is reported as `Day1Error::Overflow`.
+
+use crate::{Command, Day1Error, Dial, Direction, parse_parts, rotate, visits};
+
+/// A circular dial with positions `0` to `size - 1`, like [`Dial`] but with
+/// `u128` sizes, positions and step counts.
+///
+/// ```
+/// use day_1::{Direction, WideCommand, WideDial};
+///
+/// let mut dial = WideDial::new(1 << 100, 0).unwrap();
+/// let command = WideCommand::new(Direction::Left, (1 << 101) + 1);
+/// assert_eq!(dial.count_zeros(&command), 2);
+/// dial.move_position(&command);
+/// assert_eq!(dial.current_position(), (1 << 100) - 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WideDial {
+    /// The current position of the dial, always below `size`.
+    current_position: u128,
+
+    /// The size of the dial
+    size: u128,
+}
+
+/// A single rotation command of a [`WideDial`], with a direction and a
+/// `u128` number of steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WideCommand {
+    direction: Direction,
+    steps: u128,
+}
+
+impl WideDial {
+    /// Create a dial with `size` positions, pointing at `start`. Fails with
+    /// [`Day1Error::InvalidDial`] if the dial has no positions or `start` is
+    /// not one of them.
+    pub fn new(size: u128, start: u128) -> Result<Self, Day1Error> {
+        if start >= size {
+            return Err(Day1Error::InvalidDial { size, start });
+        }
+
+        Ok(Self {
+            current_position: start,
+            size,
+        })
+    }
+
+    /// The position the dial points at.
+    pub fn current_position(&self) -> u128 {
+        self.current_position
+    }
+
+    /// The number of positions on the dial.
+    pub fn size(&self) -> u128 {
+        self.size
+    }
+
+    /// Apply a rotation command to the dial, like [`Dial::move_position`].
+    pub fn move_position(&mut self, command: &WideCommand) {
+        self.current_position = rotate(
+            self.size,
+            self.current_position,
+            command.direction,
+            command.steps,
+        );
+    }
+
+    /// How many times the dial hits `0` for a given command, like
+    /// [`Dial::count_zeros`].
+    pub fn count_zeros(&self, command: &WideCommand) -> u128 {
+        self.count_visits(command, 0)
+    }
+
+    /// How many times the dial hits `target` for a given command, like
+    /// [`Dial::count_visits`].
+    pub fn count_visits(&self, command: &WideCommand, target: u128) -> u128 {
+        visits(
+            self.size,
+            self.current_position,
+            command.direction,
+            command.steps,
+            target,
+        )
+    }
+}
+
+impl Default for WideDial {
+    /// The dial of the puzzle: 100 positions, pointing at 50.
+    fn default() -> Self {
+        Dial::default().into()
+    }
+}
+
+impl From<Dial> for WideDial {
+    fn from(dial: Dial) -> Self {
+        Self {
+            current_position: dial.current_position.into(),
+            size: dial.size.into(),
+        }
+    }
+}
+
+impl WideCommand {
+    /// Create a new turn command
+    pub fn new(direction: Direction, steps: u128) -> Self {
+        Self { direction, steps }
+    }
+}
+
+impl From<Command> for WideCommand {
+    fn from(command: Command) -> Self {
+        Self::new(command.direction, command.steps.into())
+    }
+}
+
+impl TryFrom<&str> for WideCommand {
+    type Error = Day1Error;
+
+    /// Errors report the text as line 1.
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (direction, steps) = parse_parts(1, value)?;
+
+        Ok(Self::new(direction, steps))
+    }
+}
+
+/// The commands of `input` with `u128` step counts, parsed like
+/// [`crate::CommandStream`].
+fn wide_commands(input: &str) -> impl Iterator<Item = Result<WideCommand, Day1Error>> {
+    input.lines().enumerate().map(|(index, line)| {
+        let (direction, steps) = parse_parts(index + 1, line)?;
+        Ok(WideCommand::new(direction, steps))
+    })
+}
+
+/// Solve Part 1 on `dial`, with step counts up to `u128::MAX`.
+///
+/// ```
+/// use day_1::{WideDial, solution_part_1_u128};
+///
+/// let input = "R340282366920938463463374607431768211455\nL5\n";
+/// assert_eq!(solution_part_1_u128(input, WideDial::new(7, 0).unwrap()), Ok(0));
+/// assert_eq!(solution_part_1_u128(input, WideDial::new(5, 0).unwrap()), Ok(2));
+/// ```
+pub fn solution_part_1_u128(input: &str, mut dial: WideDial) -> Result<u128, Day1Error> {
+    let mut password = 0;
+
+    for command in wide_commands(input) {
+        dial.move_position(&command?);
+
+        if dial.current_position == 0 {
+            password += 1;
+        }
+    }
+
+    Ok(password)
+}
+
+/// Solve Part 2 on `dial`, with step counts up to `u128::MAX`. A count
+/// beyond `u128::MAX` is reported as [`Day1Error::Overflow`].
+///
+/// ```
+/// use day_1::{WideDial, solution_part_2_u128};
+///
+/// let input = include_str!("sample_input.txt");
+/// assert_eq!(solution_part_2_u128(input, WideDial::default()), Ok(6));
+///
+/// let input = "R18446744073709551615\nR18446744073709551615\n";
+/// assert_eq!(
+///     solution_part_2_u128(input, WideDial::new(1, 0).unwrap()),
+///     Ok(2 * u128::from(u64::MAX))
+/// );
+/// ```
+pub fn solution_part_2_u128(input: &str, mut dial: WideDial) -> Result<u128, Day1Error> {
+    let mut password: u128 = 0;
+
+    for command in wide_commands(input) {
+        let command = command?;
+
+        password = password
+            .checked_add(dial.count_zeros(&command))
+            .ok_or(Day1Error::Overflow)?;
+        dial.move_position(&command);
+    }
+
+    Ok(password)
+}
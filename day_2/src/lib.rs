@@ -1,11 +1,154 @@
-///! Advent of Code Day 2 - Gift Shop
+//! Advent of Code Day 2 - Gift Shop
+
+use std::error;
+use std::fmt;
+use std::iter;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use aoc_core::ranges::MultipleRanges;
+
+pub use aoc_core::Answer;
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// The most digits of an ID of any [`Id`] type in any base, those of a
+/// `u128` in base 2.
+const MAX_DIGITS: usize = 128;
+
+/// The digits of the bases up to 36, as written by [`digits`].
+const DIGIT_CHARS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// The bases IDs can be checked in, see [`RangeOptions::base`].
+pub const BASES: RangeInclusive<u32> = 2..=36;
+
+/// Errors that can occur while parsing the Day 2 puzzle input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Day2Error {
+    /// A token between the separators is not a range of the form `start-end`.
+    InvalidRange {
+        /// The offending token, without surrounding whitespace.
+        token: String,
+        /// Why the token is not a range.
+        reason: &'static str,
+    },
+    /// A sum of invalid IDs does not fit in its result type.
+    Overflow,
+    /// The IDs cannot be checked in a base outside of [`BASES`].
+    InvalidBase {
+        /// The requested base.
+        base: u32,
+    },
+}
+
+impl fmt::Display for Day2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Day2Error::InvalidRange { token, reason } => {
+                write!(f, "invalid range {token:?}: {reason}")
+            }
+            Day2Error::Overflow => write!(f, "the sum of the invalid IDs overflows"),
+            Day2Error::InvalidBase { base } => write!(f, "invalid base {base}"),
+        }
+    }
+}
+
+impl error::Error for Day2Error {}
+
+/// An unsigned integer type holding IDs: `u64` for the puzzle input, or
+/// `u128` for extended inputs with IDs beyond `u64::MAX`.
+pub trait Id: Copy + Ord + From<u8> + Into<Answer> + FromStr + Send + Sync {
+    /// The largest ID of this type.
+    const MAX: Self;
+
+    /// The next ID, or `None` after the largest one.
+    fn successor(self) -> Option<Self>;
+
+    /// The ID without its last digit in `base`, and that digit.
+    fn split_last_digit(self, base: u8) -> (Self, u8);
+}
+
+impl Id for u64 {
+    const MAX: Self = u64::MAX;
+
+    fn successor(self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    fn split_last_digit(self, base: u8) -> (Self, u8) {
+        // Dividing by the constant is much faster for the usual decimal IDs
+        match base {
+            10 => (self / 10, (self % 10) as u8),
+            _ => (self / Self::from(base), (self % Self::from(base)) as u8),
+        }
+    }
+}
+
+impl Id for u128 {
+    const MAX: Self = u128::MAX;
+
+    fn successor(self) -> Option<Self> {
+        self.checked_add(1)
+    }
+
+    fn split_last_digit(self, base: u8) -> (Self, u8) {
+        match base {
+            10 => (self / 10, (self % 10) as u8),
+            _ => (self / Self::from(base), (self % Self::from(base)) as u8),
+        }
+    }
+}
+
+/// The number of digits of the largest `T` in `base`.
+fn max_digits<T: Id>(base: u32) -> u32 {
+    let mut id: Answer = T::MAX.into();
+    let mut count = 0;
+
+    while id > 0 {
+        id /= Answer::from(base);
+        count += 1;
+    }
+
+    count
+}
 
 /// Returns `true` if `id` is valid for Part 1 rules (not exactly two equal halves).
 ///
 /// Logic:
 /// - If the length is odd, it cannot be two equal halves → valid.
 /// - Otherwise compare the first half and the second half; if equal → invalid, else valid.
-fn is_valid_part_1(id: &str) -> bool {
+pub fn is_valid_part_1(id: &str) -> bool {
+    is_valid_digits_part_1(id.as_bytes())
+}
+
+/// Returns `true` if `id` written in `base` is valid for Part 1 rules, see
+/// [`is_valid_part_1`].
+///
+/// The digits are found numerically, and the ID is never formatted.
+///
+/// # Panics
+///
+/// Panics if `base` is not in [`BASES`].
+///
+/// ```
+/// use day_2::is_valid_part_1_in_base;
+///
+/// // 1010 in binary
+/// assert!(!is_valid_part_1_in_base(10u64, 2));
+/// assert!(is_valid_part_1_in_base(11u64, 2));
+/// // ff in hexadecimal
+/// assert!(!is_valid_part_1_in_base(255u64, 16));
+/// ```
+pub fn is_valid_part_1_in_base<T: Id>(id: T, base: u32) -> bool {
+    assert!(BASES.contains(&base), "invalid base {base}");
+
+    is_valid_digits_part_1(digits(id, base, &mut [0; MAX_DIGITS]))
+}
+
+/// [`is_valid_part_1`] for the ASCII digits of an ID, as written by
+/// [`digits`].
+fn is_valid_digits_part_1(id: &[u8]) -> bool {
     let len = id.len();
 
     // if the length is odd, the id is always valid
@@ -22,10 +165,36 @@ fn is_valid_part_1(id: &str) -> bool {
 /// Logic:
 /// - Enumerate all proper divisors of the length (possible part sizes).
 /// - If any partitioning into equal-sized chunks has all chunks equal → invalid.
-fn is_valid_part_2(id: &str) -> bool {
-    let dividers = dividers(id.len());
+pub fn is_valid_part_2(id: &str) -> bool {
+    is_valid_digits_part_2(id.as_bytes())
+}
+
+/// Returns `true` if `id` written in `base` is valid for Part 2 rules, see
+/// [`is_valid_part_2`].
+///
+/// The digits are found numerically, and the ID is never formatted.
+///
+/// # Panics
+///
+/// Panics if `base` is not in [`BASES`].
+///
+/// ```
+/// use day_2::is_valid_part_2_in_base;
+///
+/// // 111 in binary
+/// assert!(!is_valid_part_2_in_base(7u64, 2));
+/// assert!(is_valid_part_2_in_base(6u64, 2));
+/// ```
+pub fn is_valid_part_2_in_base<T: Id>(id: T, base: u32) -> bool {
+    assert!(BASES.contains(&base), "invalid base {base}");
+
+    is_valid_digits_part_2(digits(id, base, &mut [0; MAX_DIGITS]))
+}
 
-    for divider in dividers {
+/// [`is_valid_part_2`] for the ASCII digits of an ID, as written by
+/// [`digits`].
+fn is_valid_digits_part_2(id: &[u8]) -> bool {
+    for divider in dividers(id.len()) {
         if parts_are_equal(id, divider) {
             return false;
         }
@@ -34,9 +203,60 @@ fn is_valid_part_2(id: &str) -> bool {
     true
 }
 
+/// A rule deciding which IDs are valid, given their digits.
+///
+/// The range scanning of [`sum_invalid_ids`] works with any rule, so a new
+/// invalidity predicate only has to implement this trait. Closures taking the
+/// digits implement it as well. Rules are `Sync`, so that the `parallel`
+/// feature can share them between threads.
+///
+/// ```
+/// use day_2::{Part1, sum_invalid_ids};
+///
+/// assert_eq!(sum_invalid_ids("11-22", Part1), Ok(33));
+/// // IDs starting and ending with the same digit
+/// let rule = |digits: &[u8]| digits.first() != digits.last();
+/// assert_eq!(sum_invalid_ids("95-115", rule), Ok(99 + 101 + 111));
+/// ```
+pub trait ValidityRule: Sync {
+    /// Returns `true` if the ID with the ASCII `digits` is valid. The digits
+    /// are decimal unless another [`RangeOptions::base`] is set, with the
+    /// letters `a` to `z` for the digits from 10.
+    fn is_valid(&self, digits: &[u8]) -> bool;
+}
+
+impl<F: Fn(&[u8]) -> bool + Sync> ValidityRule for F {
+    fn is_valid(&self, digits: &[u8]) -> bool {
+        self(digits)
+    }
+}
+
+/// The Part 1 rule: an ID is invalid if it is two equal halves, see
+/// [`is_valid_part_1`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Part1;
+
+impl ValidityRule for Part1 {
+    fn is_valid(&self, digits: &[u8]) -> bool {
+        is_valid_digits_part_1(digits)
+    }
+}
+
+/// The Part 2 rule: an ID is invalid if it is a block repeated at least
+/// twice, see [`is_valid_part_2`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Part2;
+
+impl ValidityRule for Part2 {
+    fn is_valid(&self, digits: &[u8]) -> bool {
+        is_valid_digits_part_2(digits)
+    }
+}
+
 /// Check if all chunks of length `part_len` in `value` equal the first chunk.
 /// Assumes `part_len` divides `value.len()`.
-fn parts_are_equal(value: &str, part_len: usize) -> bool {
+fn parts_are_equal<T: AsRef<[u8]> + ?Sized>(value: &T, part_len: usize) -> bool {
+    let value = value.as_ref();
     let n = value.len() / part_len;
     let first = &value[0..part_len];
 
@@ -46,58 +266,450 @@ fn parts_are_equal(value: &str, part_len: usize) -> bool {
 }
 
 /// Get all proper divisors of `n` (values in 1..n that divide `n`).
-fn dividers(n: usize) -> Vec<usize> {
-    (1..n).filter(|&x| n % x == 0).collect()
+fn dividers(n: usize) -> impl Iterator<Item = usize> {
+    (1..n).filter(move |&x| n.is_multiple_of(x))
+}
+
+/// Write the digits of `id` in `base` to the end of `buffer` as ASCII, and
+/// return them. Unlike `id.to_string()`, this never allocates. `base` must be
+/// in [`BASES`].
+fn digits<T: Id>(mut id: T, base: u32, buffer: &mut [u8; MAX_DIGITS]) -> &[u8] {
+    let mut start = buffer.len();
+
+    loop {
+        let (rest, digit) = id.split_last_digit(base as u8);
+        start -= 1;
+        buffer[start] = DIGIT_CHARS[usize::from(digit)];
+        id = rest;
+
+        if id == T::from(0) {
+            return &buffer[start..];
+        }
+    }
+}
+
+/// Parse the ID ranges of the puzzle input, each of the form `start-end`.
+///
+/// The ranges are separated by commas or newlines. Whitespace around the
+/// ranges and their bounds is ignored, as are empty tokens, so trailing
+/// commas and blank lines are fine. The IDs are parsed as `T`, which is
+/// usually `u64`.
+///
+/// ```
+/// use day_2::parse_ranges;
+///
+/// assert_eq!(
+///     parse_ranges::<u64>("11-22,\n 95 - 115,\n"),
+///     Ok(vec![11..=22, 95..=115])
+/// );
+/// assert!(parse_ranges::<u64>("11-22,95").is_err());
+/// assert!(parse_ranges::<u128>("1-18446744073709551616").is_ok());
+/// ```
+pub fn parse_ranges<T: Id>(input: &str) -> Result<Vec<RangeInclusive<T>>, Day2Error> {
+    input
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+        .map(parse_range)
+        .collect()
+}
+
+/// Parse a single range token of the form `start-end`.
+fn parse_range<T: Id>(token: &str) -> Result<RangeInclusive<T>, Day2Error> {
+    let invalid = |reason| Day2Error::InvalidRange {
+        token: token.to_string(),
+        reason,
+    };
+
+    let (start, end) = token
+        .split_once('-')
+        .ok_or_else(|| invalid("missing '-'"))?;
+    let start: T = start.trim().parse().map_err(|_| invalid("invalid start"))?;
+    let end: T = end.trim().parse().map_err(|_| invalid("invalid end"))?;
+
+    if start > end {
+        return Err(invalid("start is after end"));
+    }
+
+    Ok(start..=end)
+}
+
+/// Options controlling how the input ranges are scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeOptions {
+    /// Scan the ranges as they are, so that an ID in several overlapping
+    /// ranges is counted once for each of them. By default, overlapping
+    /// ranges are merged first and every ID is counted once.
+    pub keep_overlaps: bool,
+    /// The base the IDs are written in when checking them for repeated
+    /// digits, from [`BASES`]. The ranges themselves are always decimal.
+    pub base: u32,
+}
+
+impl Default for RangeOptions {
+    fn default() -> Self {
+        Self {
+            keep_overlaps: false,
+            base: 10,
+        }
+    }
 }
 
-/// Parse a range from a string of the form `start-end`.
-fn min_max(input: &str) -> (u64, u64) {
-    let id_range: Vec<&str> = input.split('-').collect();
-    let min: u64 = id_range[0].parse().expect("Could not parse start of range");
-    let max: u64 = id_range[1].parse().expect("Could not parse end of range");
+/// The ranges of the puzzle input, merged into disjoint ranges in increasing
+/// order unless `options.keep_overlaps` is set. Fails if `options.base` is
+/// not in [`BASES`].
+fn read_ranges<T: Id>(
+    input: &str,
+    options: &RangeOptions,
+) -> Result<Vec<RangeInclusive<T>>, Day2Error> {
+    if !BASES.contains(&options.base) {
+        return Err(Day2Error::InvalidBase { base: options.base });
+    }
+
+    let ranges = parse_ranges(input)?;
+    if options.keep_overlaps {
+        return Ok(ranges);
+    }
+
+    let mut ranges: MultipleRanges<T> = ranges.into_iter().collect();
+    ranges.merge_overlapping();
 
-    (min, max)
+    Ok(ranges.0.into_iter().map(RangeInclusive::from).collect())
 }
 
 /// Brute-force solution for Part 1: sum all invalid IDs across the input ranges.
-fn bruteforce_solution_part_1(input: &str) -> u64 {
-    let mut total = 0;
-    let ranges: Vec<&str> = input.split(',').collect();
+///
+/// Looks at every ID, so it is only fast for small ranges, but serves as the
+/// reference for [`analytic_solution_part_1`]. Fails if the input is not a
+/// list of ranges, see [`parse_ranges`].
+pub fn bruteforce_solution_part_1(input: &str) -> Result<u64, Day2Error> {
+    bruteforce_solution_part_1_with_options(input, &RangeOptions::default())
+}
 
-    for range in ranges {
-        let (min, max) = min_max(range);
+/// [`bruteforce_solution_part_1`] with the ranges scanned as configured by
+/// `options`.
+pub fn bruteforce_solution_part_1_with_options(
+    input: &str,
+    options: &RangeOptions,
+) -> Result<u64, Day2Error> {
+    sum_invalid_ids_with_options(input, Part1, options)
+}
+
+/// [`bruteforce_solution_part_1`] for IDs up to `u128::MAX`. Fails with
+/// [`Day2Error::Overflow`] if the sum does not fit in an [`Answer`].
+pub fn bruteforce_solution_part_1_u128(input: &str) -> Result<Answer, Day2Error> {
+    sum_invalid(
+        read_ranges::<u128>(input, &RangeOptions::default())?,
+        Part1,
+        10,
+    )
+}
+
+/// The sum of the IDs in `min..=max` that are a block of `len` digits in
+/// `base`, not starting with `0`, repeated `times` times.
+///
+/// Such an ID is the block times the multiplier `1` followed by `times - 1`
+/// copies of `len - 1` zeros and a `1` (e.g. `1001` for two blocks of three
+/// decimal digits), so the blocks in the range form an interval that is
+/// summed in closed form. `None` if the sum does not fit in an [`Answer`].
+fn sum_repeated<T: Id>(min: T, max: T, len: u32, times: u32, base: u32) -> Option<Answer> {
+    let base = Answer::from(base);
+
+    // A multiplier beyond an `Answer` leaves no such ID in any range
+    let Some(multiplier) = (0..times).try_fold(0, |multiplier: Answer, i| {
+        multiplier.checked_add(base.checked_pow(len * i)?)
+    }) else {
+        return Some(0);
+    };
+    let lo = min.into().div_ceil(multiplier).max(base.pow(len - 1));
+    let hi = (max.into() / multiplier).min(base.checked_pow(len).map_or(Answer::MAX, |p| p - 1));
+
+    if lo > hi {
+        return Some(0);
+    }
+
+    // One of the count and the sum of the first and last block is even, and
+    // the blocks have at most half of the digits of a `u128`, so the sum of
+    // the blocks only overflows in small bases
+    let count = hi - lo + 1;
+    let blocks = if count.is_multiple_of(2) {
+        (count / 2).checked_mul(lo + hi)?
+    } else {
+        count.checked_mul((lo + hi) / 2)?
+    };
+
+    multiplier.checked_mul(blocks)
+}
+
+/// Analytic solution for Part 1: sum all invalid IDs across the input ranges
+/// without looking at every ID.
+///
+/// The invalid IDs of `2 * len` digits are the blocks of `len` digits
+/// repeated twice, summed per length with [`sum_repeated`]. That takes time
+/// proportional to the number of ranges, however large they are.
+pub fn analytic_solution_part_1(input: &str) -> Result<Answer, Day2Error> {
+    analytic_solution_part_1_with_options(input, &RangeOptions::default())
+}
+
+/// [`analytic_solution_part_1`] with the ranges read as configured by
+/// `options`.
+pub fn analytic_solution_part_1_with_options(
+    input: &str,
+    options: &RangeOptions,
+) -> Result<Answer, Day2Error> {
+    analytic_sum_part_1(read_ranges::<u64>(input, options)?, options.base)
+}
+
+/// [`analytic_solution_part_1`] for IDs up to `u128::MAX`. Fails with
+/// [`Day2Error::Overflow`] if the sum does not fit in an [`Answer`].
+pub fn analytic_solution_part_1_u128(input: &str) -> Result<Answer, Day2Error> {
+    analytic_sum_part_1(read_ranges::<u128>(input, &RangeOptions::default())?, 10)
+}
+
+/// The sum of the Part 1 invalid IDs in `ranges`, see
+/// [`analytic_solution_part_1`], with the IDs written in `base`.
+fn analytic_sum_part_1<T: Id>(
+    ranges: Vec<RangeInclusive<T>>,
+    base: u32,
+) -> Result<Answer, Day2Error> {
+    let max_digits = max_digits::<T>(base);
+
+    ranges
+        .into_iter()
+        .flat_map(|range| {
+            (1..=max_digits / 2)
+                .map(move |len| sum_repeated(*range.start(), *range.end(), len, 2, base))
+        })
+        .try_fold(0, |total: Answer, sum| total.checked_add(sum?))
+        .ok_or(Day2Error::Overflow)
+}
+
+/// Brute-force solution for Part 2: sum all invalid IDs across the input ranges.
+///
+/// Looks at every ID, so it is only fast for small ranges, but serves as the
+/// reference for [`analytic_solution_part_2`]. Fails if the input is not a
+/// list of ranges, see [`parse_ranges`].
+pub fn bruteforce_solution_part_2(input: &str) -> Result<u64, Day2Error> {
+    bruteforce_solution_part_2_with_options(input, &RangeOptions::default())
+}
 
-        for id in min..=max {
-            if !is_valid_part_1(&id.to_string()) {
-                total += id;
+/// [`bruteforce_solution_part_2`] with the ranges scanned as configured by
+/// `options`.
+pub fn bruteforce_solution_part_2_with_options(
+    input: &str,
+    options: &RangeOptions,
+) -> Result<u64, Day2Error> {
+    sum_invalid_ids_with_options(input, Part2, options)
+}
+
+/// [`bruteforce_solution_part_2`] for IDs up to `u128::MAX`. Fails with
+/// [`Day2Error::Overflow`] if the sum does not fit in an [`Answer`].
+pub fn bruteforce_solution_part_2_u128(input: &str) -> Result<Answer, Day2Error> {
+    sum_invalid(
+        read_ranges::<u128>(input, &RangeOptions::default())?,
+        Part2,
+        10,
+    )
+}
+
+/// The IDs in `range` that are invalid under `rule`, in increasing order.
+///
+/// Every ID is checked, so this is only fast for small ranges. The digits are
+/// written to a single buffer, so checking does not allocate.
+///
+/// ```
+/// use day_2::{Part2, invalid_ids};
+///
+/// let ids: Vec<u64> = invalid_ids(95..=115, Part2).collect();
+/// assert_eq!(ids, [99, 111]);
+/// ```
+pub fn invalid_ids<T: Id>(
+    range: RangeInclusive<T>,
+    rule: impl ValidityRule,
+) -> impl Iterator<Item = T> {
+    invalid_ids_in_base(range, rule, 10)
+}
+
+/// [`invalid_ids`] with the IDs written in `base` for `rule`.
+///
+/// # Panics
+///
+/// Panics if `base` is not in [`BASES`].
+///
+/// ```
+/// use day_2::{Part1, invalid_ids_in_base};
+///
+/// // 11, 1010 and 1111 in binary
+/// let ids: Vec<u64> = invalid_ids_in_base(1..=16, Part1, 2).collect();
+/// assert_eq!(ids, [3, 10, 15]);
+/// ```
+pub fn invalid_ids_in_base<T: Id>(
+    range: RangeInclusive<T>,
+    rule: impl ValidityRule,
+    base: u32,
+) -> impl Iterator<Item = T> {
+    assert!(BASES.contains(&base), "invalid base {base}");
+
+    let (start, end) = range.into_inner();
+    let mut buffer = [0; MAX_DIGITS];
+
+    iter::successors((start <= end).then_some(start), move |&id| {
+        if id < end { id.successor() } else { None }
+    })
+    .filter(move |&id| !rule.is_valid(digits(id, base, &mut buffer)))
+}
+
+/// Sum all IDs across the input ranges that are invalid under `rule`,
+/// checking every ID once, even if it is in several ranges.
+pub fn sum_invalid_ids(input: &str, rule: impl ValidityRule) -> Result<u64, Day2Error> {
+    sum_invalid_ids_with_options(input, rule, &RangeOptions::default())
+}
+
+/// [`sum_invalid_ids`] with the ranges scanned as configured by `options`.
+/// Fails with [`Day2Error::Overflow`] if the sum does not fit in a `u64`.
+pub fn sum_invalid_ids_with_options(
+    input: &str,
+    rule: impl ValidityRule,
+    options: &RangeOptions,
+) -> Result<u64, Day2Error> {
+    let sum = sum_invalid(read_ranges::<u64>(input, options)?, rule, options.base)?;
+
+    u64::try_from(sum).map_err(|_| Day2Error::Overflow)
+}
+
+/// Sum the IDs in `ranges` that are invalid under `rule` when written in
+/// `base`, checking every ID with [`invalid_ids_in_base`].
+#[cfg(not(feature = "parallel"))]
+fn sum_invalid<T: Id>(
+    ranges: Vec<RangeInclusive<T>>,
+    rule: impl ValidityRule,
+    base: u32,
+) -> Result<Answer, Day2Error> {
+    ranges
+        .into_iter()
+        .flat_map(|range| invalid_ids_in_base(range, |digits: &[u8]| rule.is_valid(digits), base))
+        .try_fold(0, |total: Answer, id| total.checked_add(id.into()))
+        .ok_or(Day2Error::Overflow)
+}
+
+/// Sum the IDs in `ranges` that are invalid under `rule` when written in
+/// `base`, checking every ID. The ranges are split into chunks on the rayon
+/// thread pool, and the sums of the chunks are merged. Every chunk writes the
+/// digits to a buffer of its own, so checking does not allocate.
+#[cfg(feature = "parallel")]
+fn sum_invalid<T: Id>(
+    ranges: Vec<RangeInclusive<T>>,
+    rule: impl ValidityRule,
+    base: u32,
+) -> Result<Answer, Day2Error>
+where
+    RangeInclusive<T>: IntoParallelIterator<Item = T>,
+{
+    ranges
+        .into_par_iter()
+        .flat_map(|range| range)
+        .map_init(
+            || [0; MAX_DIGITS],
+            |buffer, id| {
+                if rule.is_valid(digits(id, base, buffer)) {
+                    Some(0)
+                } else {
+                    Some(id.into())
+                }
+            },
+        )
+        .try_reduce(|| 0, |total: Answer, sum| total.checked_add(sum))
+        .ok_or(Day2Error::Overflow)
+}
+
+/// The Möbius function of `n`: `0` if a square divides `n`, and otherwise
+/// `1` or `-1` for an even or odd number of prime factors.
+fn mobius(mut n: u32) -> i32 {
+    let mut result = 1;
+    let mut factor = 2;
+
+    while factor * factor <= n {
+        if n.is_multiple_of(factor) {
+            n /= factor;
+            if n.is_multiple_of(factor) {
+                return 0;
             }
+            result = -result;
         }
+        factor += 1;
     }
 
-    total
+    if n > 1 { -result } else { result }
 }
 
-/// Brute-force solution for Part 2: sum all invalid IDs across the input ranges.
-fn bruteforce_solution_part_2(input: &str) -> u64 {
-    let mut total = 0;
-    let ranges: Vec<&str> = input.split(',').collect();
+/// Analytic solution for Part 2: sum all invalid IDs across the input ranges
+/// without looking at every ID.
+///
+/// An ID of `digits` digits is a block of `len` digits repeated when `len`
+/// divides `digits`, and [`sum_repeated`] sums those IDs for every such
+/// `len`. An ID made of blocks of `len` digits is also made of blocks of any
+/// multiple of `len`, so the sums overlap. By Möbius inversion, the IDs
+/// whose shortest block is the whole ID sum to the sum over all divisors
+/// `len` of `mobius(digits / len)` times the sum for `len`. The invalid IDs
+/// are all others, which leaves minus that sum over the proper divisors.
+pub fn analytic_solution_part_2(input: &str) -> Result<Answer, Day2Error> {
+    analytic_solution_part_2_with_options(input, &RangeOptions::default())
+}
+
+/// [`analytic_solution_part_2`] with the ranges read as configured by
+/// `options`.
+pub fn analytic_solution_part_2_with_options(
+    input: &str,
+    options: &RangeOptions,
+) -> Result<Answer, Day2Error> {
+    analytic_sum_part_2(read_ranges::<u64>(input, options)?, options.base)
+}
+
+/// [`analytic_solution_part_2`] for IDs up to `u128::MAX`. Fails with
+/// [`Day2Error::Overflow`] if the sum, or the sum of the IDs added by the
+/// inversion, does not fit in an [`Answer`].
+pub fn analytic_solution_part_2_u128(input: &str) -> Result<Answer, Day2Error> {
+    analytic_sum_part_2(read_ranges::<u128>(input, &RangeOptions::default())?, 10)
+}
+
+/// The sum of the Part 2 invalid IDs in `ranges`, see
+/// [`analytic_solution_part_2`], with the IDs written in `base`.
+fn analytic_sum_part_2<T: Id>(
+    ranges: Vec<RangeInclusive<T>>,
+    base: u32,
+) -> Result<Answer, Day2Error> {
+    let max_digits = max_digits::<T>(base);
+    let mut total: Answer = 0;
 
     for range in ranges {
-        let (min, max) = min_max(range);
+        let (min, max) = (*range.start(), *range.end());
+        let (mut added, mut subtracted): (Answer, Answer) = (0, 0);
 
-        for id in min..=max {
-            if !is_valid_part_2(&id.to_string()) {
-                total += id;
+        for digits in 2..=max_digits {
+            for len in (1..digits).filter(|&len| digits.is_multiple_of(len)) {
+                let sum =
+                    sum_repeated(min, max, len, digits / len, base).ok_or(Day2Error::Overflow)?;
+                let side = match mobius(digits / len) {
+                    -1 => &mut added,
+                    1 => &mut subtracted,
+                    _ => continue,
+                };
+                *side = side.checked_add(sum).ok_or(Day2Error::Overflow)?;
             }
         }
+
+        total = total
+            .checked_add(added - subtracted)
+            .ok_or(Day2Error::Overflow)?;
     }
 
-    total
+    Ok(total)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::rng::TestRng;
 
     #[test]
     fn test_is_valid_10() {
@@ -114,14 +726,379 @@ mod tests {
         assert_eq!(is_valid_part_1("11"), false)
     }
 
+    #[test]
+    fn test_digits() {
+        let mut buffer = [0; MAX_DIGITS];
+        for id in [0, 7, 10, 1188511885, u64::MAX] {
+            assert_eq!(digits(id, 10, &mut buffer), id.to_string().as_bytes());
+            assert_eq!(
+                is_valid_digits_part_1(digits(id, 10, &mut buffer)),
+                is_valid_part_1(&id.to_string())
+            );
+            assert_eq!(
+                is_valid_digits_part_2(digits(id, 10, &mut buffer)),
+                is_valid_part_2(&id.to_string())
+            );
+        }
+        assert!(!is_valid_digits_part_1(b"1188511885"));
+        assert!(!is_valid_digits_part_2(b"824824824"));
+        assert!(is_valid_digits_part_2(b"824824821"));
+    }
+
+    #[test]
+    fn test_validity_rules() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(sum_invalid_ids(input, Part1), Ok(1227775554));
+        assert_eq!(sum_invalid_ids(input, Part2), Ok(4174379265));
+        assert_eq!(
+            sum_invalid_ids(input, |id: &[u8]| is_valid_part_2(
+                std::str::from_utf8(id).unwrap()
+            )),
+            Ok(4174379265)
+        );
+
+        // Only the IDs of exactly three equal blocks
+        let three_blocks =
+            |id: &[u8]| !(id.len().is_multiple_of(3) && parts_are_equal(id, id.len() / 3));
+        assert_eq!(
+            sum_invalid_ids("100-1000,123123123-123123123", three_blocks),
+            Ok(111 * (1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 9) + 123123123)
+        );
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        assert_eq!(
+            parse_ranges::<u64>("11-22,95-115\n,\n  998 -1012 ,,\n"),
+            Ok(vec![11..=22, 95..=115, 998..=1012])
+        );
+        assert_eq!(parse_ranges::<u64>(" \n"), Ok(vec![]));
+
+        let invalid = |token: &str, reason| Day2Error::InvalidRange {
+            token: token.to_string(),
+            reason,
+        };
+        assert_eq!(
+            parse_ranges::<u64>("11-22, 95 \n"),
+            Err(invalid("95", "missing '-'"))
+        );
+        assert_eq!(
+            parse_ranges::<u64>("1x-22"),
+            Err(invalid("1x-22", "invalid start"))
+        );
+        assert_eq!(
+            parse_ranges::<u64>("11--22"),
+            Err(invalid("11--22", "invalid end"))
+        );
+        assert_eq!(
+            parse_ranges::<u64>("22-11"),
+            Err(invalid("22-11", "start is after end"))
+        );
+        assert_eq!(
+            bruteforce_solution_part_1("11-22,-5"),
+            Err(invalid("-5", "invalid start"))
+        );
+        assert_eq!(
+            analytic_solution_part_2("11-22,5-"),
+            Err(invalid("5-", "invalid end"))
+        );
+    }
+
+    #[test]
+    fn test_overlapping_ranges() {
+        // 11, 22, 33 and 44 are in both ranges, 55 in the second only
+        let input = "10-45,11-55,55-55";
+        let keep = RangeOptions {
+            keep_overlaps: true,
+            ..RangeOptions::default()
+        };
+
+        assert_eq!(bruteforce_solution_part_1(input), Ok(165));
+        assert_eq!(analytic_solution_part_1(input), Ok(165));
+        assert_eq!(bruteforce_solution_part_2(input), Ok(165));
+        assert_eq!(analytic_solution_part_2(input), Ok(165));
+        assert_eq!(
+            bruteforce_solution_part_1_with_options(input, &keep),
+            Ok(110 + 165 + 55)
+        );
+        assert_eq!(
+            analytic_solution_part_1_with_options(input, &keep),
+            Ok(110 + 165 + 55)
+        );
+        assert_eq!(
+            bruteforce_solution_part_2_with_options(input, &keep),
+            Ok(110 + 165 + 55)
+        );
+        assert_eq!(
+            analytic_solution_part_2_with_options(input, &keep),
+            Ok(110 + 165 + 55)
+        );
+        assert_eq!(
+            sum_invalid_ids_with_options("11-11,11-11", Part1, &keep),
+            Ok(22)
+        );
+        assert_eq!(sum_invalid_ids("11-11,11-11", Part1), Ok(11));
+    }
+
+    #[test]
+    fn test_u128_ids() {
+        let mut buffer = [0; MAX_DIGITS];
+        assert_eq!(
+            digits(u128::MAX, 10, &mut buffer),
+            u128::MAX.to_string().as_bytes()
+        );
+
+        let input = include_str!("sample_input.txt");
+        assert_eq!(bruteforce_solution_part_1_u128(input), Ok(1227775554));
+        assert_eq!(analytic_solution_part_1_u128(input), Ok(1227775554));
+        assert_eq!(bruteforce_solution_part_2_u128(input), Ok(4174379265));
+        assert_eq!(analytic_solution_part_2_u128(input), Ok(4174379265));
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let digits = rng.below(38) + 1;
+            let mut min: u128 = 0;
+            for _ in 0..digits {
+                min = min * 10 + u128::from(rng.below(10));
+            }
+            let input = format!("{min}-{}", min + u128::from(rng.below(3000)));
+
+            assert_eq!(
+                analytic_solution_part_1_u128(&input),
+                bruteforce_solution_part_1_u128(&input),
+                "{input}"
+            );
+            assert_eq!(
+                analytic_solution_part_2_u128(&input),
+                bruteforce_solution_part_2_u128(&input),
+                "{input}"
+            );
+        }
+
+        // 1234567890123456789 twice and 13 times 123
+        let input = "12345678901234567891234567890123456788-12345678901234567891234567890123456790,\
+                     123123123123123123123123123123123123122-123123123123123123123123123123123123124";
+        let expected =
+            12345678901234567891234567890123456789 + 123123123123123123123123123123123123123;
+        assert_eq!(bruteforce_solution_part_2_u128(input), Ok(expected));
+        assert_eq!(analytic_solution_part_2_u128(input), Ok(expected));
+
+        let all = format!("0-{}", u128::MAX);
+        assert_eq!(
+            analytic_solution_part_1_u128(&all),
+            Err(Day2Error::Overflow)
+        );
+        assert_eq!(
+            analytic_solution_part_2_u128(&all),
+            Err(Day2Error::Overflow)
+        );
+        assert_eq!(
+            bruteforce_solution_part_1(&format!("{}-{}", u64::MAX - 1, u64::MAX)),
+            Ok(0)
+        );
+        assert!(parse_ranges::<u64>("1-18446744073709551616").is_err());
+    }
+
+    #[test]
+    fn test_bases() {
+        // The digits of `id` in `base`, formatted one by one
+        let format = |mut id: u64, base: u64| {
+            let mut digits = Vec::new();
+            loop {
+                digits.push(char::from_digit((id % base) as u32, base as u32).unwrap());
+                id /= base;
+                if id == 0 {
+                    return digits.iter().rev().collect::<String>();
+                }
+            }
+        };
+
+        let mut buffer = [0; MAX_DIGITS];
+        assert_eq!(digits(u128::MAX, 2, &mut buffer), [b'1'; 128]);
+        assert_eq!(digits(35u64, 36, &mut buffer), b"z");
+        assert_eq!(max_digits::<u64>(10), 20);
+        assert_eq!(max_digits::<u64>(2), 64);
+        assert_eq!(max_digits::<u128>(36), 25);
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let base = rng.below(35) as u32 + 2;
+            let digits = rng.below(10) as u32 + 1;
+            let min = rng.below(u64::from(base).pow(digits));
+            let max = min + rng.below(3000);
+            let input = format!("{min}-{max}");
+            let options = RangeOptions {
+                base,
+                ..RangeOptions::default()
+            };
+
+            let expected_1: u64 = (min..=max)
+                .filter(|&id| !is_valid_part_1(&format(id, u64::from(base))))
+                .sum();
+            let expected_2: u64 = (min..=max)
+                .filter(|&id| !is_valid_part_2(&format(id, u64::from(base))))
+                .sum();
+
+            assert_eq!(
+                bruteforce_solution_part_1_with_options(&input, &options),
+                Ok(expected_1),
+                "{input} in base {base}"
+            );
+            assert_eq!(
+                analytic_solution_part_1_with_options(&input, &options),
+                Ok(Answer::from(expected_1)),
+                "{input} in base {base}"
+            );
+            assert_eq!(
+                bruteforce_solution_part_2_with_options(&input, &options),
+                Ok(expected_2),
+                "{input} in base {base}"
+            );
+            assert_eq!(
+                analytic_solution_part_2_with_options(&input, &options),
+                Ok(Answer::from(expected_2)),
+                "{input} in base {base}"
+            );
+        }
+
+        // Every ID of a `u64` in binary: the blocks of 32 bits stop early
+        let binary = RangeOptions {
+            base: 2,
+            ..RangeOptions::default()
+        };
+        let input = format!("{}-{}", u64::MAX - 5000, u64::MAX);
+        assert_eq!(
+            analytic_solution_part_2_with_options(&input, &binary),
+            bruteforce_solution_part_2_with_options(&input, &binary).map(Answer::from)
+        );
+        assert!(analytic_solution_part_1_with_options(&format!("0-{}", u64::MAX), &binary).is_ok());
+
+        for base in [0, 1, 37] {
+            let options = RangeOptions {
+                base,
+                ..RangeOptions::default()
+            };
+            assert_eq!(
+                bruteforce_solution_part_1_with_options("11-22", &options),
+                Err(Day2Error::InvalidBase { base })
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid base 1")]
+    fn test_is_valid_in_base_1() {
+        is_valid_part_2_in_base(7u64, 1);
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        let ids: Vec<u64> = invalid_ids(998..=1012, Part1).collect();
+        assert_eq!(ids, [1010]);
+        let ids: Vec<u64> = invalid_ids(998..=1012, Part2).collect();
+        assert_eq!(ids, [999, 1010]);
+        assert_eq!(invalid_ids(1698522..=1698528u64, Part2).count(), 0);
+
+        let ids: Vec<u64> = invalid_ids(u64::MAX - 1..=u64::MAX, |_: &[u8]| false).collect();
+        assert_eq!(ids, [u64::MAX - 1, u64::MAX]);
+    }
+
     #[test]
     fn test_bruteforce_solution_part_1_sample_input() {
         assert_eq!(
             bruteforce_solution_part_1(include_str!("sample_input.txt")),
-            1227775554
+            Ok(1227775554)
         )
     }
 
+    #[test]
+    fn test_analytic_solution_part_1() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(analytic_solution_part_1(input), Ok(1227775554));
+        assert_eq!(analytic_solution_part_1("11-22\n"), Ok(33));
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let ranges: Vec<String> = (0..rng.below(4) + 1)
+                .map(|_| {
+                    let digits = rng.below(7) as u32 + 1;
+                    let min = rng.below(10u64.pow(digits));
+                    format!("{min}-{}", min + rng.below(3000))
+                })
+                .collect();
+            let input = ranges.join(",");
+
+            assert_eq!(
+                analytic_solution_part_1(&input),
+                bruteforce_solution_part_1(&input).map(Answer::from),
+                "{input}"
+            );
+        }
+
+        // Every ID of a `u64`, where the blocks of ten digits stop early
+        let sum_blocks = |len: u32, hi: Answer| {
+            let lo = Answer::pow(10, len - 1);
+            (Answer::pow(10, len) + 1) * (lo + hi) * (hi - lo + 1) / 2
+        };
+        let expected: Answer = (1..10)
+            .map(|len| sum_blocks(len, Answer::pow(10, len) - 1))
+            .sum::<Answer>()
+            + sum_blocks(10, Answer::from(u64::MAX) / (Answer::pow(10, 10) + 1));
+        assert_eq!(
+            analytic_solution_part_1(&format!("0-{}", u64::MAX)),
+            Ok(expected)
+        );
+    }
+
+    #[test]
+    fn test_mobius() {
+        let expected = [1, -1, -1, 0, -1, 1, -1, 0, 0, 1, -1, 0, -1, 1, 1, 0];
+        for (n, expected) in (1..).zip(expected) {
+            assert_eq!(mobius(n), expected, "{n}");
+        }
+    }
+
+    #[test]
+    fn test_analytic_solution_part_2() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(analytic_solution_part_2(input), Ok(4174379265));
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let ranges: Vec<String> = (0..rng.below(4) + 1)
+                .map(|_| {
+                    let digits = rng.below(8) as u32 + 1;
+                    let min = rng.below(10u64.pow(digits));
+                    format!("{min}-{}", min + rng.below(3000))
+                })
+                .collect();
+            let input = ranges.join(",");
+
+            assert_eq!(
+                analytic_solution_part_2(&input),
+                bruteforce_solution_part_2(&input).map(Answer::from),
+                "{input}"
+            );
+        }
+
+        // Every ID of up to six digits, checked one by one
+        assert_eq!(
+            analytic_solution_part_2("1-999999"),
+            bruteforce_solution_part_2("1-999999").map(Answer::from)
+        );
+
+        // The repeated blocks of the largest IDs never exceed a `u64`
+        let input = format!("{}-{}", u64::MAX - 10_000, u64::MAX);
+        assert_eq!(
+            analytic_solution_part_2(&input),
+            bruteforce_solution_part_2(&input).map(Answer::from)
+        );
+    }
+
     #[test]
     fn test_parts_are_equal_true_12341234() {
         assert_eq!(parts_are_equal("12341234", 4), true)
@@ -141,7 +1118,7 @@ mod tests {
     fn test_solution_part_2_sample_input() {
         assert_eq!(
             bruteforce_solution_part_2(include_str!("sample_input.txt")),
-            4174379265
+            Ok(4174379265)
         )
     }
 }
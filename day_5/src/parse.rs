@@ -10,7 +10,7 @@
 // This module parses the text into `MultipleRanges` and `Vec<u64>` for use by the
 // solution functions in `lib.rs`.
 
-use crate::{MultipleRanges, Range};
+use aoc_core::ranges::{MultipleRanges, Range};
 
 /// Possible parsing errors for the Day 5 input format.
 #[derive(Debug)]
@@ -26,7 +26,7 @@ pub fn parse_input(input: &str) -> Result<(MultipleRanges, Vec<u64>), ParseError
         .split_once("\n\n")
         .ok_or(ParseError::InvalidInputFormat)?;
 
-    let ranges = MultipleRanges::try_from(ranges)?;
+    let ranges = parse_ranges(ranges)?;
 
     let ids = ids
         .lines()
@@ -36,28 +36,19 @@ pub fn parse_input(input: &str) -> Result<(MultipleRanges, Vec<u64>), ParseError
     Ok((ranges, ids))
 }
 
-impl TryFrom<&str> for Range {
-    type Error = ParseError;
+/// Parse a single `<min>-<max>` line into a `Range`.
+fn parse_range(value: &str) -> Result<Range, ParseError> {
+    let (min, max) = value.split_once('-').ok_or(ParseError::InvalidRange)?;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let (min, max) = value.split_once('-').ok_or(ParseError::InvalidRange)?;
+    let min: u64 = min.parse().map_err(|_| ParseError::InvalidNumber)?;
+    let max: u64 = max.parse().map_err(|_| ParseError::InvalidNumber)?;
 
-        let min: u64 = min.parse().map_err(|_| ParseError::InvalidNumber)?;
-        let max: u64 = max.parse().map_err(|_| ParseError::InvalidNumber)?;
-
-        Ok(Self::new(min, max))
-    }
+    Ok(Range::new(min, max))
 }
 
-impl TryFrom<&str> for MultipleRanges {
-    type Error = ParseError;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let ranges = value
-            .lines()
-            .map(Range::try_from)
-            .collect::<Result<_, _>>()?;
+/// Parse the ranges section, one range per line.
+fn parse_ranges(value: &str) -> Result<MultipleRanges, ParseError> {
+    let ranges = value.lines().map(parse_range).collect::<Result<_, _>>()?;
 
-        Ok(MultipleRanges::new(ranges))
-    }
+    Ok(MultipleRanges::new(ranges))
 }
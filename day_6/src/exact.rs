@@ -0,0 +1,109 @@
+// Exact decimal number mode for Day 6: Trash Compactor
+//
+// Worksheets with fractional measurements (e.g. `12.5`) cannot be evaluated
+// with integers without losing precision. In this mode every number is parsed
+// into an exact rational, so sums and products are computed without rounding.
+// The result is always a reduced fraction, which can be displayed as such
+// (`Rational` implements `Display`, e.g. `85/4`) or via `format_decimal`.
+//
+// Layouts:
+// - part 1: numbers are whitespace-separated tokens such as `12.5` or `.25`
+// - part 2: a `.` inside a column marks the decimal point of that number
+
+use crate::grid::CharGrid;
+use crate::{Day6Error, Operator, WorksheetOptions, find_operator, parse_grid, read_columns};
+use num_rational::Ratio;
+use num_traits::CheckedMul;
+
+/// An exact, always reduced, rational number.
+pub type Rational = Ratio<i128>;
+
+/// Part 1 in exact mode: like [`crate::solution_part_1_with_options`], but
+/// numbers may contain a decimal point.
+pub fn solution_part_1(input: &str, options: &WorksheetOptions) -> Result<Rational, Day6Error> {
+    let (operators, columns) = read_columns(input, options, parse_decimal)?;
+
+    let results = operators
+        .iter()
+        .zip(columns)
+        .map(|(operator, column)| operator.apply_exact(column.into_iter()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Operator::Addition.apply_exact(results.into_iter())
+}
+
+/// Part 2 in exact mode: like [`crate::solution_part_2_with_options`], but a
+/// `.` in a column marks the decimal point of the number in that column.
+pub fn solution_part_2(input: &str, options: &WorksheetOptions) -> Result<Rational, Day6Error> {
+    let grid = parse_grid(input, options)?;
+
+    let results = grid
+        .blocks(options.min_gap.max(1))
+        .iter()
+        .map(|block| evaluate_block(&grid, block))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Operator::Addition.apply_exact(results.into_iter())
+}
+
+/// Evaluate one block, reading every column as a decimal number.
+fn evaluate_block(grid: &CharGrid, cols: &[usize]) -> Result<Rational, Day6Error> {
+    let operator = find_operator(grid, cols)?;
+    let operator_row = grid.height() - 1;
+
+    let numbers = cols
+        .iter()
+        .map(|&col| {
+            grid.column(col)
+                .take(operator_row)
+                .filter(|&c| c.is_ascii_digit() || c == '.')
+                .collect::<String>()
+        })
+        // Skip columns that do not hold a number at all
+        .filter(|digits| !digits.is_empty())
+        .map(|digits| parse_decimal(&digits))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    operator.apply_exact(numbers.into_iter())
+}
+
+/// Parse a decimal token such as `12.5`, `-3`, `7.` or `.25` into an exact
+/// rational.
+pub fn parse_decimal(token: &str) -> Result<Rational, Day6Error> {
+    let (whole, fraction) = token.split_once('.').unwrap_or((token, ""));
+
+    let numerator: i128 = format!("{whole}{fraction}")
+        .parse()
+        .map_err(|_| Day6Error::ParseIntError)?;
+    let denominator = u32::try_from(fraction.len())
+        .ok()
+        .and_then(|places| 10i128.checked_pow(places))
+        .ok_or(Day6Error::Overflow)?;
+
+    Ok(Rational::new(numerator, denominator))
+}
+
+/// Format `value` as a decimal number with exactly `places` digits after the
+/// decimal point, rounding half away from zero.
+pub fn format_decimal(value: &Rational, places: u32) -> Result<String, Day6Error> {
+    let scale = 10i128.checked_pow(places).ok_or(Day6Error::Overflow)?;
+    let scaled = value
+        .checked_mul(&Rational::from_integer(scale))
+        .ok_or(Day6Error::Overflow)?
+        .round()
+        .to_integer();
+
+    let sign = if scaled < 0 { "-" } else { "" };
+    let digits = format!(
+        "{:0>width$}",
+        scaled.unsigned_abs(),
+        width = places as usize + 1
+    );
+    let (whole, fraction) = digits.split_at(digits.len() - places as usize);
+
+    if places == 0 {
+        Ok(format!("{sign}{whole}"))
+    } else {
+        Ok(format!("{sign}{whole}.{fraction}"))
+    }
+}
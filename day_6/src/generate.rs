@@ -0,0 +1,139 @@
+// Random worksheet generator for Day 6: Trash Compactor
+//
+// Produces random, valid worksheets together with their expected answer. The
+// same problems are rendered in both layouts:
+// - horizontal (part 1): every problem is a column of whole numbers, one per row
+// - vertical (part 2): every number is a column of digits read top-to-bottom
+//
+// Since both layouts describe the same problems, part 1 on the horizontal
+// layout and part 2 on the vertical layout must produce the same answer. This
+// makes the generator useful for round-trip tests and large benchmarks.
+//
+// The worksheets are drawn from the seeded `TestRng` of `aoc_core`, so a seed
+// always gives the same worksheet.
+
+use crate::{Day6Error, Expression, Number, Operator};
+use aoc_core::rng::TestRng;
+
+/// A randomly generated worksheet in both layouts.
+#[derive(Debug)]
+pub struct GeneratedWorksheet {
+    /// The worksheet in the part 1 layout.
+    pub horizontal: String,
+    /// The worksheet in the part 2 layout.
+    pub vertical: String,
+    /// The grand total of all problems, valid for both layouts.
+    pub expected: i128,
+}
+
+/// Generate a worksheet of `problems` problems with `rows` numbers each.
+///
+/// Numbers are drawn from `0..1000`, so a problem is at most `999^rows` (or
+/// `999 · rows` for a sum) and the grand total at most `problems` times that.
+/// The total always fits in an `i128` if `problems · 999^rows <= i128::MAX`,
+/// e.g. for up to 172 problems of 12 rows or 172 024 problems of 11 rows.
+/// Otherwise it may not, and a total that does not fit is reported as
+/// [`Day6Error::Overflow`].
+pub fn generate_worksheet(
+    rng: &mut TestRng,
+    problems: usize,
+    rows: usize,
+) -> Result<GeneratedWorksheet, Day6Error> {
+    let expressions: Vec<Expression> = (0..problems)
+        .map(|_| random_expression(rng, rows))
+        .collect();
+
+    let mut expected: i128 = 0;
+    for expression in &expressions {
+        expected = expected
+            .checked_add(expression.evaluate()?)
+            .ok_or(Day6Error::Overflow)?;
+    }
+
+    Ok(GeneratedWorksheet {
+        horizontal: render_horizontal(rng, &expressions, rows),
+        vertical: render_vertical(rng, &expressions),
+        expected,
+    })
+}
+
+/// Pick a random operator and `rows` random numbers.
+fn random_expression(rng: &mut TestRng, rows: usize) -> Expression {
+    let operator = if rng.below(2) == 0 {
+        Operator::Addition
+    } else {
+        Operator::Multiplication
+    };
+    let numbers = (0..rows).map(|_| Number(rng.below(1000))).collect();
+
+    Expression::Apply(operator, numbers)
+}
+
+/// Render the problems side by side, with the `i`-th number of every problem
+/// on line `i`. Numbers are randomly left- or right-aligned within their
+/// problem column.
+fn render_horizontal(rng: &mut TestRng, expressions: &[Expression], rows: usize) -> String {
+    let mut lines = vec![Vec::new(); rows + 1];
+
+    for Expression::Apply(operator, numbers) in expressions {
+        let width = numbers
+            .iter()
+            .map(|n| n.0.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        for (line, number) in lines.iter_mut().zip(numbers) {
+            if rng.below(2) == 0 {
+                line.push(format!("{:<width$}", number.0));
+            } else {
+                line.push(format!("{:>width$}", number.0));
+            }
+        }
+        lines[rows].push(format!("{operator:<width$}"));
+    }
+
+    join_lines(lines)
+}
+
+/// Render every number as a column of digits, shifted by a random offset
+/// within its problem, with the operators on the bottom line.
+fn render_vertical(rng: &mut TestRng, expressions: &[Expression]) -> String {
+    let height = expressions
+        .iter()
+        .flat_map(|Expression::Apply(_, numbers)| numbers)
+        .map(|n| n.0.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines = vec![Vec::new(); height + 1];
+
+    for Expression::Apply(operator, numbers) in expressions {
+        let mut block = vec![String::new(); height];
+
+        for number in numbers {
+            let digits: Vec<char> = number.0.to_string().chars().collect();
+            let offset = rng.below((height - digits.len() + 1) as u64) as usize;
+
+            for (row, line) in block.iter_mut().enumerate() {
+                let digit = row.checked_sub(offset).and_then(|i| digits.get(i));
+                line.push(digit.copied().unwrap_or(' '));
+            }
+        }
+
+        for (line, block_line) in lines.iter_mut().zip(block) {
+            line.push(block_line);
+        }
+        lines[height].push(format!("{operator:<width$}", width = numbers.len().max(1)));
+    }
+
+    join_lines(lines)
+}
+
+/// Join the cells of every line with a single space, and the lines with `\n`.
+fn join_lines(lines: Vec<Vec<String>>) -> String {
+    lines
+        .into_iter()
+        .map(|cells| cells.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,116 @@
+// Character grid for Day 6: Trash Compactor
+//
+// The worksheet is read as a rectangular grid of chars. Lines in the input are
+// ragged (trailing spaces are often stripped), so every line is padded with
+// spaces to the width of the longest line at parse time. This lets the part 2
+// solver address any `(row, col)` without bounds juggling.
+
+/// A rectangular grid of characters, padded with spaces.
+#[derive(Debug, PartialEq)]
+pub struct CharGrid {
+    cells: Vec<char>,
+    width: usize,
+    height: usize,
+}
+
+impl CharGrid {
+    /// Parse `input` into a grid, expanding tabs to `tab_width` columns and
+    /// padding every line with spaces to the width of the longest line.
+    pub fn parse(input: &str, tab_width: usize) -> Self {
+        let lines: Vec<Vec<char>> = input
+            .lines()
+            .map(|line| expand_tabs(line, tab_width).chars().collect())
+            .collect();
+
+        let height = lines.len();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+
+        let mut cells = Vec::with_capacity(width * height);
+        for line in lines {
+            let padding = width - line.len();
+            cells.extend(line);
+            cells.extend(std::iter::repeat_n(' ', padding));
+        }
+
+        Self {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    /// Number of columns in the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows in the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The character at `(row, col)`, or `None` when out of bounds.
+    pub fn get(&self, row: usize, col: usize) -> Option<char> {
+        if row < self.height && col < self.width {
+            Some(self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// Iterate over the characters of column `col`, top to bottom.
+    pub fn column(&self, col: usize) -> impl Iterator<Item = char> + '_ {
+        (0..self.height).filter_map(move |row| self.get(row, col))
+    }
+
+    /// Whether column `col` contains only whitespace.
+    pub fn is_column_empty(&self, col: usize) -> bool {
+        self.column(col).all(char::is_whitespace)
+    }
+
+    /// Scan the columns left-to-right and group the non-empty ones into
+    /// blocks, starting a new block after `min_gap` consecutive empty columns.
+    pub fn blocks(&self, min_gap: usize) -> Vec<Vec<usize>> {
+        let mut blocks = Vec::new();
+        let mut current_block = Vec::new();
+        let mut gap = 0;
+
+        for col in 0..self.width() {
+            if !self.is_column_empty(col) {
+                gap = 0;
+                current_block.push(col);
+            } else {
+                gap += 1;
+                if gap >= min_gap && !current_block.is_empty() {
+                    blocks.push(std::mem::take(&mut current_block));
+                }
+            }
+        }
+
+        if !current_block.is_empty() {
+            blocks.push(current_block);
+        }
+
+        blocks
+    }
+}
+
+/// Expand every `\t` in `line` into spaces, advancing to the next tab stop.
+pub fn expand_tabs(line: &str, tab_width: usize) -> String {
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::with_capacity(line.len());
+    let mut column = 0;
+
+    for c in line.chars() {
+        if c == '\t' {
+            let spaces = tab_width - column % tab_width;
+            expanded.extend(std::iter::repeat_n(' ', spaces));
+            column += spaces;
+        } else {
+            expanded.push(c);
+            column += 1;
+        }
+    }
+
+    expanded
+}
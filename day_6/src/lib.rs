@@ -1,26 +1,92 @@
-///! Advent of Code 2025 — Day 6: Trash Compactor
+//! Advent of Code 2025 — Day 6: Trash Compactor
+
+use crate::exact::Rational;
+use crate::grid::CharGrid;
+use num_traits::{CheckedAdd, CheckedMul, One, Zero};
+use std::fmt;
+
+pub mod exact;
+pub mod generate;
+mod grid;
 
 /// Errors that can occur while parsing or evaluating the Day 6 worksheet.
 #[derive(Debug, PartialEq)]
-enum Day6Error {
+pub enum Day6Error {
     /// The provided input had no lines.
     EmptyInput,
     /// An operator other than `+` or `*` was encountered.
     UnknownOperator,
     /// A block did not contain any operator in its bottom row.
     OperatorNotFound,
-    /// A numeric token could not be parsed into an integer.
+    /// A numeric token could not be parsed into a number.
     ParseIntError,
+    /// A line in part 1 did not contain one number per operator.
+    ColumnMismatch {
+        /// The 1-based line number of the offending line.
+        line: usize,
+        /// The number of operators in the bottom line.
+        expected: usize,
+        /// The number of numbers found on the line.
+        found: usize,
+    },
+    /// An intermediate result did not fit in an `i128`.
+    Overflow,
 }
 
 /// The operator that applies to a block of numbers.
-enum Operator {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
     /// Sum all numbers in the block.
     Addition,
     /// Multiply all numbers in the block.
     Multiplication,
 }
 
+impl Operator {
+    /// The identity element of the operator: `0` for addition and `1` for
+    /// multiplication. Applying the operator to no values yields it.
+    pub fn identity(&self) -> i128 {
+        match self {
+            Operator::Addition => 0,
+            Operator::Multiplication => 1,
+        }
+    }
+
+    /// Fold `values` with the operator, starting from [`Operator::identity`].
+    ///
+    /// Returns [`Day6Error::Overflow`] if any intermediate result does not
+    /// fit in an `i128`.
+    pub fn apply(&self, values: impl Iterator<Item = i128>) -> Result<i128, Day6Error> {
+        self.fold(values)
+    }
+
+    /// Fold exact rational `values` with the operator, like [`Operator::apply`].
+    pub fn apply_exact(
+        &self,
+        values: impl Iterator<Item = Rational>,
+    ) -> Result<Rational, Day6Error> {
+        self.fold(values)
+    }
+
+    /// Checked fold shared by the integer and the exact number modes.
+    fn fold<T>(&self, mut values: impl Iterator<Item = T>) -> Result<T, Day6Error>
+    where
+        T: Zero + One + CheckedAdd + CheckedMul,
+    {
+        let identity = match self {
+            Operator::Addition => T::zero(),
+            Operator::Multiplication => T::one(),
+        };
+
+        values
+            .try_fold(identity, |acc, value| match self {
+                Operator::Addition => acc.checked_add(&value),
+                Operator::Multiplication => acc.checked_mul(&value),
+            })
+            .ok_or(Day6Error::Overflow)
+    }
+}
+
 impl TryFrom<&str> for Operator {
     type Error = Day6Error;
 
@@ -45,13 +111,57 @@ impl TryFrom<char> for Operator {
     }
 }
 
-/// Parse a whitespace-separated list of decimal numbers from a single line.
-fn parse_numbers(input: &str) -> Result<Vec<u64>, Day6Error> {
-    input
-        .split_whitespace()
-        .map(|str| str.parse::<u64>())
-        .collect::<Result<_, _>>()
-        .map_err(|_| Day6Error::ParseIntError)
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::Addition => f.pad("+"),
+            Operator::Multiplication => f.pad("*"),
+        }
+    }
+}
+
+/// A single number of a problem, as read from the worksheet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Number(u64);
+
+/// The parsed form of one problem block.
+#[derive(Debug, PartialEq)]
+enum Expression {
+    /// Apply the operator to all numbers of the block.
+    Apply(Operator, Vec<Number>),
+}
+
+impl Expression {
+    /// Compute the value of the expression.
+    fn evaluate(&self) -> Result<i128, Day6Error> {
+        let Expression::Apply(operator, numbers) = self;
+
+        operator.apply(numbers.iter().map(|n| i128::from(n.0)))
+    }
+}
+
+/// Render the expression back into the vertical (part 2) layout: one number
+/// per column with its digits read top-to-bottom, and the operator on the
+/// bottom row below the first column.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Expression::Apply(operator, numbers) = self;
+        let digits: Vec<Vec<char>> = numbers
+            .iter()
+            .map(|n| n.0.to_string().chars().collect())
+            .collect();
+        let height = digits.iter().map(|d| d.len()).max().unwrap_or(0);
+
+        for row in 0..height {
+            let line: String = digits
+                .iter()
+                .map(|d| d.get(row).copied().unwrap_or(' '))
+                .collect();
+            writeln!(f, "{line}")?;
+        }
+
+        write!(f, "{operator:<width$}", width = digits.len().max(1))
+    }
 }
 
 /// Read the first item from a reversed line iterator (which is the original
@@ -64,121 +174,236 @@ fn get_operators<'a>(mut iter: impl Iterator<Item = &'a str>) -> Result<Vec<Oper
         .collect::<Result<_, _>>()
 }
 
+/// Options controlling how a worksheet is read.
+///
+/// Worksheets copied from editors or spreadsheets often contain tabs, wider
+/// gaps between problems or stray numbers than the puzzle input does, so the
+/// handling of each is configurable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WorksheetOptions {
+    /// Width of a tab stop; every `\t` is expanded to spaces up to the next
+    /// multiple of this width. A width of `0` is treated as `1`.
+    pub tab_width: usize,
+    /// Minimum number of consecutive empty (all whitespace) columns that
+    /// separate two blocks. Shorter runs of empty columns are tolerated inside
+    /// a block and skipped, rather than read as a number. A gap of `0` is
+    /// treated as `1`.
+    pub min_gap: usize,
+    /// In part 1, ignore numbers beyond the last operator column instead of
+    /// reporting [`Day6Error::ColumnMismatch`], and accept lines with fewer
+    /// numbers than operators.
+    pub lenient: bool,
+}
+
+impl Default for WorksheetOptions {
+    fn default() -> Self {
+        Self {
+            tab_width: 8,
+            min_gap: 1,
+            lenient: false,
+        }
+    }
+}
+
 /// Part 1: Evaluate problems defined in vertical stacks, separated by
-/// columns of spaces, reading numbers top-to-bottom and operators from the
-/// last line (original bottom). For each column index:
-/// - If operator is `+`, add all numbers in that column to the running sum.
-/// - If operator is `*`, multiply numbers within that column together, then
-///   add the resulting product to the final answer at the end.
-fn solution_part_1(input: &str) -> Result<u64, Day6Error> {
+/// columns of spaces. Uses the default [`WorksheetOptions`].
+pub fn solution_part_1(input: &str) -> Result<i128, Day6Error> {
+    solution_part_1_with_options(input, &WorksheetOptions::default())
+}
+
+/// Part 1 with explicit options, reading numbers top-to-bottom and operators
+/// from the last line (original bottom). The numbers at the same index on
+/// every line form one column; each column is folded with its operator using
+/// [`Operator::apply`], and the column results are summed.
+///
+/// Every number line must contain exactly one number per operator, unless
+/// `options.lenient` is set, in which case extra numbers are skipped.
+pub fn solution_part_1_with_options(
+    input: &str,
+    options: &WorksheetOptions,
+) -> Result<i128, Day6Error> {
+    let (operators, columns) = read_columns(input, options, |token| {
+        token
+            .parse::<u64>()
+            .map(i128::from)
+            .map_err(|_| Day6Error::ParseIntError)
+    })?;
+
+    let results = operators
+        .iter()
+        .zip(columns)
+        .map(|(operator, column)| operator.apply(column.into_iter()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Operator::Addition.apply(results.into_iter())
+}
+
+/// Read the part 1 layout into its operators and, for every operator, the
+/// column of numbers above it. Every whitespace-separated token is converted
+/// with `parse_number`.
+fn read_columns<T>(
+    input: &str,
+    options: &WorksheetOptions,
+    parse_number: impl Fn(&str) -> Result<T, Day6Error>,
+) -> Result<(Vec<Operator>, Vec<Vec<T>>), Day6Error> {
     let mut reversed_lines = input.lines().rev();
     let operators = get_operators(&mut reversed_lines)?;
 
-    let mut sum = 0;
-    let mut multiplications: Vec<u64> = vec![1; operators.len()];
+    let mut columns: Vec<Vec<T>> = operators.iter().map(|_| Vec::new()).collect();
 
-    for line in reversed_lines {
-        let numbers: Vec<u64> = parse_numbers(line)?;
+    // Walk the remaining lines in their original order to report line numbers
+    for (index, line) in reversed_lines.rev().enumerate() {
+        let numbers: Vec<T> = line
+            .split_whitespace()
+            .map(&parse_number)
+            .collect::<Result<_, _>>()?;
 
-        for (index, &number) in numbers.iter().enumerate() {
-            match operators[index] {
-                Operator::Addition => sum += number,
-                Operator::Multiplication => {
-                    multiplications[index] = multiplications[index] * number
-                }
-            }
+        if !options.lenient && numbers.len() != operators.len() {
+            return Err(Day6Error::ColumnMismatch {
+                line: index + 1,
+                expected: operators.len(),
+                found: numbers.len(),
+            });
         }
-    }
 
-    // Sum the products only for columns that actually performed multiplication
-    let total_multiplications = multiplications.into_iter().filter(|&n| n != 1).sum::<u64>();
+        for (column, number) in columns.iter_mut().zip(numbers) {
+            column.push(number);
+        }
+    }
 
-    Ok(sum + total_multiplications)
+    Ok((operators, columns))
 }
 
 /// Part 2: Cephalopod math reads right-to-left, with each number given in its
-/// own column. We:
-/// - Convert the input to a ragged 2D grid of chars.
-/// - Scan columns left-to-right to find contiguous non-space "blocks" that
-///   represent one problem each.
+/// own column. Uses the default [`WorksheetOptions`].
+pub fn solution_part_2(input: &str) -> Result<i128, Day6Error> {
+    solution_part_2_with_options(input, &WorksheetOptions::default())
+}
+
+/// Part 2 with explicit layout options. We:
+/// - Expand tabs and convert the input to a ragged 2D grid of chars.
+/// - Scan columns left-to-right to find "blocks" of non-empty columns that
+///   represent one problem each. Blocks are separated by at least
+///   `options.min_gap` empty columns.
 /// - For a block, detect its operator from the bottom row, and parse every
 ///   column above into a number by reading digit chars from top to bottom.
 /// - Apply the operator over all parsed numbers and sum across all blocks.
-fn solution_part_2(input: &str) -> Result<u64, Day6Error> {
-    let grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+pub fn solution_part_2_with_options(
+    input: &str,
+    options: &WorksheetOptions,
+) -> Result<i128, Day6Error> {
+    let grid = parse_grid(input, options)?;
+    let blocks = grid.blocks(options.min_gap.max(1));
 
-    if grid.is_empty() {
-        return Err(Day6Error::EmptyInput);
-    }
+    evaluate_blocks(&grid, &blocks)
+}
+
+/// Explain how part 2 reads the worksheet: every block is parsed into an
+/// [`Expression`], re-rendered in the vertical layout and followed by its
+/// value. Blocks are separated by an empty line.
+///
+/// This is useful for debugging confusing inputs, since it shows exactly
+/// which numbers and operator the solver extracted from each block.
+pub fn explain_part_2(input: &str, options: &WorksheetOptions) -> Result<String, Day6Error> {
+    let grid = parse_grid(input, options)?;
 
-    let height = grid.len();
-    let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+    let explanations = grid
+        .blocks(options.min_gap.max(1))
+        .iter()
+        .map(|block| {
+            let expression = parse_block(&grid, block)?;
+            Ok(format!("{expression}\n= {}", expression.evaluate()?))
+        })
+        .collect::<Result<Vec<_>, Day6Error>>()?;
 
-    let mut total = 0;
-    let mut current_block = Vec::new();
+    Ok(explanations.join("\n\n"))
+}
 
-    // Iterate through columns
-    for col in 0..=width {
-        let is_empty = (0..height).all(|y| {
-            grid.get(y)
-                .and_then(|row| row.get(col))
-                .map_or(true, |&c| c == ' ')
-        });
+/// Expand tabs and convert the input into a padded grid of chars.
+fn parse_grid(input: &str, options: &WorksheetOptions) -> Result<CharGrid, Day6Error> {
+    let grid = CharGrid::parse(input, options.tab_width);
 
-        if !is_empty && col < width {
-            current_block.push(col);
-        } else if !current_block.is_empty() {
-            total += solve_block(&grid, &current_block)?;
-            current_block.clear();
-        }
+    if grid.height() == 0 {
+        return Err(Day6Error::EmptyInput);
     }
 
-    Ok(total)
+    Ok(grid)
 }
 
-/// Given a set of contiguous column indices (`cols`) that form one problem,
-/// determine its operator from the bottom row and compute the result by
-/// applying it to all per-column numbers above.
-fn solve_block(grid: &[Vec<char>], cols: &[usize]) -> Result<u64, Day6Error> {
-    let height = grid.len();
-    let operator_row = height - 1;
-
-    let operator = cols
+/// Solve every block and sum the results.
+#[cfg(not(feature = "parallel"))]
+fn evaluate_blocks(grid: &CharGrid, blocks: &[Vec<usize>]) -> Result<i128, Day6Error> {
+    let results = blocks
         .iter()
-        .find_map(|&x| {
-            grid.get(operator_row)
-                .and_then(|row| row.get(x))
-                .and_then(|&c| Operator::try_from(c).ok())
-        })
-        .ok_or(Day6Error::OperatorNotFound)?;
+        .map(|block| parse_block(grid, block)?.evaluate())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Operator::Addition.apply(results.into_iter())
+}
+
+/// Solve every block and sum the results. Blocks are independent of each
+/// other, so they are evaluated concurrently on the rayon thread pool.
+#[cfg(feature = "parallel")]
+fn evaluate_blocks(grid: &CharGrid, blocks: &[Vec<usize>]) -> Result<i128, Day6Error> {
+    use rayon::prelude::*;
+
+    blocks
+        .par_iter()
+        .map(|block| parse_block(grid, block)?.evaluate())
+        .try_reduce(
+            || Operator::Addition.identity(),
+            |a, b| Operator::Addition.apply([a, b].into_iter()),
+        )
+}
+
+/// Given the non-empty column indices (`cols`) that form one problem,
+/// determine its operator from the bottom row and build the expression that
+/// applies it to all per-column numbers above.
+fn parse_block(grid: &CharGrid, cols: &[usize]) -> Result<Expression, Day6Error> {
+    let operator_row = grid.height() - 1;
+    let operator = find_operator(grid, cols)?;
 
     let numbers = cols
         .iter()
-        .map(|&col| parse_number_in_column(&grid[..height - 1], col))
-        // Optional: filter out completely empty number columns if necessary
-        .filter(|&n| n > 0);
+        // Columns without any digit (e.g. only holding the operator) are not
+        // numbers; a column reading `0` is a legitimate zero.
+        .filter_map(|&col| parse_number_in_column(grid.column(col).take(operator_row)))
+        .map(Number)
+        .collect();
 
-    match operator {
-        Operator::Addition => Ok(numbers.sum()),
-        Operator::Multiplication => Ok(numbers.product()),
-    }
+    Ok(Expression::Apply(operator, numbers))
+}
+
+/// Find the operator of a block in the bottom row of the grid.
+fn find_operator(grid: &CharGrid, cols: &[usize]) -> Result<Operator, Day6Error> {
+    let operator_row = grid.height() - 1;
+
+    cols.iter()
+        .find_map(|&col| {
+            grid.get(operator_row, col)
+                .and_then(|c| Operator::try_from(c).ok())
+        })
+        .ok_or(Day6Error::OperatorNotFound)
 }
 
-/// Parse one number from a single column by reading digits top-to-bottom and
-/// treating them as most-significant to least-significant.
-fn parse_number_in_column(grid: &[Vec<char>], col: usize) -> u64 {
-    (0..grid.len())
-        .filter_map(|y| {
-            grid.get(y)
-                .and_then(|row| row.get(col))
-                .and_then(|c| c.to_digit(10))
+/// Parse one number from the characters of a single column by reading digits
+/// top-to-bottom and treating them as most-significant to least-significant.
+///
+/// Returns `None` if the column does not contain any digit.
+fn parse_number_in_column(column: impl Iterator<Item = char>) -> Option<u64> {
+    column
+        .filter_map(|c| c.to_digit(10))
+        .fold(None, |acc, digit| {
+            Some(acc.unwrap_or(0) * 10 + digit as u64)
         })
-        .fold(0, |acc, digit| acc * 10 + digit as u64)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::generate::generate_worksheet;
+    use crate::grid::expand_tabs;
+    use aoc_core::rng::TestRng;
 
     #[test]
     fn test_solution_part_1() {
@@ -188,6 +413,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_operator_apply() {
+        assert_eq!(Operator::Addition.apply([1, 2, 3].into_iter()), Ok(6));
+        assert_eq!(
+            Operator::Multiplication.apply([2, 3, 4].into_iter()),
+            Ok(24)
+        );
+    }
+
+    #[test]
+    fn test_operator_apply_empty_is_identity() {
+        assert_eq!(Operator::Addition.apply(std::iter::empty()), Ok(0));
+        assert_eq!(Operator::Multiplication.apply(std::iter::empty()), Ok(1));
+    }
+
+    #[test]
+    fn test_operator_apply_overflow() {
+        assert_eq!(
+            Operator::Multiplication.apply([i128::MAX, 2].into_iter()),
+            Err(Day6Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_solution_part_1_product_of_ones() {
+        // A product column that evaluates to 1 still contributes to the total
+        assert_eq!(solution_part_1("1 2\n1 3\n* +"), Ok(1 + 5));
+    }
+
+    #[test]
+    fn test_solution_part_1_column_mismatch() {
+        let input = "1 2\n3 4 5\n+ *";
+
+        assert_eq!(
+            solution_part_1(input),
+            Err(Day6Error::ColumnMismatch {
+                line: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_solution_part_1_lenient() {
+        let input = "1 2\n3 4 5\n6\n+ *";
+        let options = WorksheetOptions {
+            lenient: true,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options(input, &options), Ok(10 + 8));
+    }
+
     #[test]
     fn test_solution_part_2() {
         assert_eq!(
@@ -195,4 +474,199 @@ mod tests {
             Ok(3263827)
         );
     }
+
+    #[test]
+    fn test_parse_block() {
+        let grid = parse_grid("123\n 45\n  6\n*  ", &WorksheetOptions::default()).unwrap();
+        let expression = parse_block(&grid, &[0, 1, 2]).unwrap();
+
+        assert_eq!(
+            expression,
+            Expression::Apply(
+                Operator::Multiplication,
+                vec![Number(1), Number(24), Number(356)]
+            )
+        );
+        assert_eq!(expression.evaluate(), Ok(8544));
+    }
+
+    #[test]
+    fn test_expression_display() {
+        let expression = Expression::Apply(
+            Operator::Addition,
+            vec![Number(4), Number(431), Number(623)],
+        );
+
+        assert_eq!(expression.to_string(), "446\n 32\n 13\n+  ");
+    }
+
+    #[test]
+    fn test_expression_display_round_trip() {
+        let expression = Expression::Apply(
+            Operator::Multiplication,
+            vec![Number(75), Number(8), Number(1093)],
+        );
+        let grid = parse_grid(&expression.to_string(), &WorksheetOptions::default()).unwrap();
+
+        assert_eq!(parse_block(&grid, &[0, 1, 2]), Ok(expression));
+    }
+
+    #[test]
+    fn test_explain_part_2() {
+        let explanation = explain_part_2(
+            include_str!("sample_input.txt"),
+            &WorksheetOptions::default(),
+        )
+        .unwrap();
+
+        assert!(explanation.starts_with("123\n 45\n  6\n*  \n= 8544\n\n"));
+        assert!(explanation.ends_with("= 1058"));
+    }
+
+    #[test]
+    fn test_parse_block_keeps_zero_columns() {
+        let grid = parse_grid("10 \n05 \n* +", &WorksheetOptions::default()).unwrap();
+
+        assert_eq!(
+            parse_block(&grid, &[0, 1]),
+            Ok(Expression::Apply(
+                Operator::Multiplication,
+                vec![Number(10), Number(5)]
+            ))
+        );
+        assert_eq!(solution_part_2("12 3\n0  4\n*  +"), Ok(10 * 2 + 34));
+        assert_eq!(solution_part_2("3 0\n1 0\n+ *"), Ok(31));
+    }
+
+    #[test]
+    fn test_parse_block_skips_columns_without_digits() {
+        assert_eq!(parse_number_in_column("  0".chars()), Some(0));
+        assert_eq!(parse_number_in_column("   ".chars()), None);
+        // The second column only holds the operator and is not a zero factor
+        assert_eq!(solution_part_2("4 \n2 \n *"), Ok(42));
+    }
+
+    #[test]
+    fn test_char_grid_pads_ragged_lines() {
+        let grid = CharGrid::parse("12\n3\n*  +", 4);
+
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert_eq!(grid.get(1, 3), Some(' '));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.column(0).collect::<String>(), "13*");
+        assert_eq!(grid.blocks(1), vec![vec![0, 1], vec![3]]);
+    }
+
+    #[test]
+    fn test_expand_tabs() {
+        assert_eq!(expand_tabs("1\t23\t4", 4), "1   23  4");
+        assert_eq!(expand_tabs("\t\t", 2), "    ");
+    }
+
+    #[test]
+    fn test_solution_part_2_with_tabs() {
+        let input = "123\t328\t 51\t64\n 45\t64\t387\t23\n  6\t98\t215\t314\n*\t+\t*\t+";
+        let options = WorksheetOptions {
+            tab_width: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_2_with_options(input, &options), Ok(3263827));
+    }
+
+    #[test]
+    fn test_solution_part_2_min_gap() {
+        // With a minimum gap of two columns, the single empty column inside
+        // each problem no longer splits it in two.
+        let input = "1 2  3 4\n5 6  7 8\n*    +  ";
+        let options = WorksheetOptions {
+            min_gap: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            solution_part_2_with_options(input, &options),
+            Ok(15 * 26 + 37 + 48)
+        );
+        assert_eq!(solution_part_2(input), Err(Day6Error::OperatorNotFound));
+    }
+
+    #[test]
+    fn test_generated_worksheets_round_trip() {
+        let mut rng = TestRng::new(6);
+
+        for _ in 0..100 {
+            let problems = rng.below(19) as usize + 1;
+            let rows = rng.below(4) as usize + 1;
+            let worksheet = generate_worksheet(&mut rng, problems, rows).unwrap();
+
+            assert_eq!(
+                solution_part_1(&worksheet.horizontal),
+                Ok(worksheet.expected)
+            );
+            assert_eq!(solution_part_2(&worksheet.vertical), Ok(worksheet.expected));
+        }
+
+        // Within the documented bound the total always fits, beyond it the
+        // products soon do not
+        let worksheet = generate_worksheet(&mut rng, 172, 12).unwrap();
+        assert_eq!(solution_part_2(&worksheet.vertical), Ok(worksheet.expected));
+        assert!(matches!(
+            generate_worksheet(&mut rng, 10, 30),
+            Err(Day6Error::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(exact::parse_decimal("12.5"), Ok(Rational::new(25, 2)));
+        assert_eq!(exact::parse_decimal(".25"), Ok(Rational::new(1, 4)));
+        assert_eq!(exact::parse_decimal("-3"), Ok(Rational::from_integer(-3)));
+        assert_eq!(exact::parse_decimal("1.2.3"), Err(Day6Error::ParseIntError));
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        let value = Rational::new(2, 3);
+
+        assert_eq!(exact::format_decimal(&value, 3), Ok("0.667".to_string()));
+        assert_eq!(exact::format_decimal(&-value, 0), Ok("-1".to_string()));
+        assert_eq!(
+            exact::format_decimal(&Rational::new(1005, 100), 1),
+            Ok("10.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_solution_part_1_exact() {
+        let input = "0.5 1.25\n0.25 3\n+ *";
+        let result = exact::solution_part_1(input, &WorksheetOptions::default());
+
+        assert_eq!(result, Ok(Rational::new(3, 4) + Rational::new(15, 4)));
+        assert_eq!(result.unwrap().to_string(), "9/2");
+    }
+
+    #[test]
+    fn test_solution_part_2_exact() {
+        // Columns read `1.5` and `2.`, then `.2` and `5`
+        let input = "12 .5\n.. 2 \n5  \n*  + ";
+        let result = exact::solution_part_2(input, &WorksheetOptions::default());
+
+        assert_eq!(result, Ok(Rational::new(3, 1) + Rational::new(26, 5)));
+    }
+
+    #[test]
+    fn test_exact_mode_matches_integer_mode() {
+        let input = include_str!("sample_input.txt");
+        let options = WorksheetOptions::default();
+
+        assert_eq!(
+            exact::solution_part_1(input, &options),
+            Ok(Rational::from_integer(4277556))
+        );
+        assert_eq!(
+            exact::solution_part_2(input, &options),
+            Ok(Rational::from_integer(3263827))
+        );
+    }
 }
@@ -0,0 +1,67 @@
+//! Compare the row update algorithms on very wide diagrams.
+//!
+//! Run with `cargo bench -p day_7`, optionally with `--features parallel`.
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use day_7::{
+    Algorithm, ManifoldOptions, solution_part_1_with_options, solution_part_2_with_options,
+};
+
+/// A diagram `width` columns wide with `depth` splitter rows, a starter in
+/// every fifth column and splitters at pseudo-random positions.
+fn generate_diagram(width: usize, depth: usize) -> String {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut input: String = (0..width)
+        .map(|col| if col.is_multiple_of(5) { 'S' } else { '.' })
+        .collect();
+    input.push('\n');
+
+    for _ in 0..depth {
+        input.push_str(&".".repeat(width));
+        input.push('\n');
+        input.extend((0..width).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            if state.is_multiple_of(4) { '^' } else { '.' }
+        }));
+        input.push('\n');
+    }
+
+    input
+}
+
+fn row_update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("row_update");
+    group.sample_size(10);
+
+    for width in [10_000, 1_000_000, 4_000_000] {
+        let input = generate_diagram(width, 8);
+
+        for algorithm in [Algorithm::Sequential, Algorithm::TwoPass, Algorithm::Bitset] {
+            let options = ManifoldOptions {
+                algorithm,
+                ..Default::default()
+            };
+
+            group.bench_with_input(
+                BenchmarkId::new(format!("part_1/{algorithm:?}"), width),
+                &input,
+                |b, input| b.iter(|| solution_part_1_with_options(input, &options)),
+            );
+            // Part 2 has no bitset variant
+            if algorithm == Algorithm::Bitset {
+                continue;
+            }
+            group.bench_with_input(
+                BenchmarkId::new(format!("part_2/{algorithm:?}"), width),
+                &input,
+                |b, input| b.iter(|| solution_part_2_with_options(input, &options)),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, row_update);
+criterion_main!(benches);
@@ -0,0 +1,223 @@
+// Bitset beams for Day 7, part 1
+//
+// Part 1 only needs to know whether a column holds a beam, so a row of beams
+// fits in one bit per column. The row update then becomes a handful of
+// whole-word operations against masks of the splitter row:
+//
+// - beams on `.` (and `S`) tiles continue down: `beams & down`
+// - beams on `^` and `\` tiles move one column right: `(beams & right) << 1`
+// - beams on `^` and `/` tiles move one column left: `(beams & left) >> 1`
+//
+// Only the bits shifted out at the edges need the `EdgeMode`. Column `c` is
+// bit `c % 64` of word `c / 64`.
+
+use crate::rules::{Part1State, RowRule};
+use crate::{BeamSpace, Day7Error, EdgeMode, SplitterSpace, StarterSpace};
+
+/// One bit per column of a row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BitRow {
+    words: Vec<u64>,
+    width: usize,
+}
+
+impl BitRow {
+    /// A row of `width` columns with the bits set where `f` holds.
+    fn from_fn(width: usize, f: impl Fn(usize) -> bool) -> Self {
+        let mut row = BitRow {
+            words: vec![0; width.div_ceil(64)],
+            width,
+        };
+        for col in (0..width).filter(|&col| f(col)) {
+            row.set(col);
+        }
+        row
+    }
+
+    fn get(&self, col: usize) -> bool {
+        self.words[col / 64] & (1 << (col % 64)) != 0
+    }
+
+    fn set(&mut self, col: usize) {
+        self.words[col / 64] |= 1 << (col % 64);
+    }
+
+    fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn and(&self, other: &BitRow) -> BitRow {
+        BitRow {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+            width: self.width,
+        }
+    }
+
+    fn or_assign(&mut self, other: &BitRow) {
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, b)| *a |= b);
+    }
+
+    /// Every bit moved one column to the right; the last column is dropped.
+    fn shifted_right(&self) -> BitRow {
+        let mut carry = 0;
+        let mut words: Vec<u64> = self
+            .words
+            .iter()
+            .map(|&w| {
+                let shifted = (w << 1) | carry;
+                carry = w >> 63;
+                shifted
+            })
+            .collect();
+
+        if !self.width.is_multiple_of(64)
+            && let Some(last) = words.last_mut()
+        {
+            *last &= (1 << (self.width % 64)) - 1;
+        }
+
+        BitRow {
+            words,
+            width: self.width,
+        }
+    }
+
+    /// Every bit moved one column to the left; the first column is dropped.
+    fn shifted_left(&self) -> BitRow {
+        let mut carry = 0;
+        let mut words: Vec<u64> = self
+            .words
+            .iter()
+            .rev()
+            .map(|&w| {
+                let shifted = (w >> 1) | carry;
+                carry = w << 63;
+                shifted
+            })
+            .collect();
+        words.reverse();
+
+        BitRow {
+            words,
+            width: self.width,
+        }
+    }
+}
+
+/// The masks of a splitter row: which tiles send beams down, left or right,
+/// which tiles split, and where mid-grid starters are.
+struct RowMasks {
+    down: BitRow,
+    left: BitRow,
+    right: BitRow,
+    split: BitRow,
+    starters: BitRow,
+}
+
+impl RowMasks {
+    fn new(splitters: &[SplitterSpace]) -> Self {
+        let width = splitters.len();
+        let mask = |f: fn(&SplitterSpace) -> bool| BitRow::from_fn(width, |col| f(&splitters[col]));
+
+        RowMasks {
+            down: mask(|tile| tile.offsets().contains(&0)),
+            left: mask(|tile| tile.offsets().contains(&-1)),
+            right: mask(|tile| tile.offsets().contains(&1)),
+            split: mask(|tile| *tile == SplitterSpace::Splitter),
+            starters: mask(|tile| *tile == SplitterSpace::Starter),
+        }
+    }
+}
+
+/// Part 1 on bitset rows, see [`crate::rules::Part1`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BitsetPart1 {
+    pub edge_mode: EdgeMode,
+}
+
+/// The beams as a bitset, the number of splits so far, and the largest
+/// number of beams in any row so far.
+#[derive(Debug, Clone)]
+pub(crate) struct BitsetState {
+    beams: BitRow,
+    splits: u64,
+    max_beams: usize,
+}
+
+impl From<BitsetState> for Part1State {
+    fn from(state: BitsetState) -> Self {
+        let beams = (0..state.beams.width)
+            .map(|col| {
+                if state.beams.get(col) {
+                    BeamSpace::Beam
+                } else {
+                    BeamSpace::Empty
+                }
+            })
+            .collect();
+
+        Part1State {
+            beams,
+            splits: state.splits,
+            max_beams: state.max_beams,
+        }
+    }
+}
+
+impl RowRule for BitsetPart1 {
+    type State = BitsetState;
+
+    fn init(&self, start: Vec<StarterSpace>) -> BitsetState {
+        let beams = BitRow::from_fn(start.len(), |col| start[col] == StarterSpace::Starter);
+
+        BitsetState {
+            max_beams: beams.count_ones(),
+            beams,
+            splits: 0,
+        }
+    }
+
+    fn apply(&self, splitters: &[SplitterSpace], state: &mut BitsetState) -> Result<(), Day7Error> {
+        let width = splitters.len();
+        if width == 0 {
+            return Ok(());
+        }
+
+        let masks = RowMasks::new(splitters);
+        let beams = &state.beams;
+
+        state.splits += beams.and(&masks.split).count_ones() as u64;
+
+        let left = beams.and(&masks.left);
+        let right = beams.and(&masks.right);
+
+        let mut next = beams.and(&masks.down);
+        next.or_assign(&left.shifted_left());
+        next.or_assign(&right.shifted_right());
+        next.or_assign(&masks.starters);
+
+        if left.get(0)
+            && let Some(target) = self.edge_mode.left_exit(width)
+        {
+            next.set(target);
+        }
+        if right.get(width - 1)
+            && let Some(target) = self.edge_mode.right_exit(width)
+        {
+            next.set(target);
+        }
+
+        state.max_beams = state.max_beams.max(next.count_ones());
+        state.beams = next;
+
+        Ok(())
+    }
+}
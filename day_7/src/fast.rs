@@ -0,0 +1,184 @@
+// Fast path for repeating splitter rows in part 2
+//
+// A splitter row maps the timeline counts of one row linearly onto the next:
+// `next = T · count`, where `T[target][col]` is the number of branches from
+// column `col` landing in column `target`. A run of `k` identical rows is
+// then `T^k · count`, which takes O(width³ · log k) time with exponentiation
+// by squaring instead of O(width · k) time row by row. Generated stress
+// inputs often repeat the same row thousands of times, which makes this pay
+// off for narrow diagrams.
+//
+// Runs are only exponentiated when that is expected to be cheaper, and rows
+// with mid-grid starters (which add timelines rather than move them) are
+// always applied one by one. In strict mode a power of `T` can overflow while
+// the counts themselves do not; the run is then applied row by row, which
+// reports an overflow only if the counts overflow.
+
+use crate::parse::ParsedRow;
+use crate::rules::{Part2, RowRule};
+use crate::{Count, Day7Error, ManifoldOptions, SplitterSpace, StarterSpace};
+
+/// A square matrix of timeline counts, stored row by row.
+#[derive(Debug, Clone)]
+struct Matrix {
+    size: usize,
+    entries: Vec<Count>,
+}
+
+impl Matrix {
+    /// The transition matrix of a splitter row.
+    fn transition(splitters: &[SplitterSpace], rule: &Part2) -> Result<Self, Day7Error> {
+        let size = splitters.len();
+        let mut matrix = Matrix {
+            size,
+            entries: vec![Count::default(); size * size],
+        };
+
+        for (col, tile) in splitters.iter().enumerate() {
+            for &offset in tile.offsets() {
+                if let Some(target) = rule.edge_mode.landing_column(col, offset, size) {
+                    rule.add(&mut matrix.entries[target * size + col], &Count::from(1u8))?;
+                }
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// The product `self · other`.
+    fn mul(&self, other: &Matrix, rule: &Part2) -> Result<Matrix, Day7Error> {
+        let size = self.size;
+        let zero = Count::default();
+        let mut product = vec![Count::default(); size * size];
+
+        for i in 0..size {
+            for k in 0..size {
+                let a = &self.entries[i * size + k];
+                if *a == zero {
+                    continue;
+                }
+                for j in 0..size {
+                    let term = rule.mul(a, &other.entries[k * size + j])?;
+                    rule.add(&mut product[i * size + j], &term)?;
+                }
+            }
+        }
+
+        Ok(Matrix {
+            size,
+            entries: product,
+        })
+    }
+
+    /// The product `self · vector`.
+    fn apply(&self, vector: &[Count], rule: &Part2) -> Result<Vec<Count>, Day7Error> {
+        (0..self.size)
+            .map(|i| {
+                let mut sum = Count::default();
+                for (a, v) in self.entries[i * self.size..(i + 1) * self.size]
+                    .iter()
+                    .zip(vector)
+                {
+                    rule.add(&mut sum, &rule.mul(a, v)?)?;
+                }
+                Ok(sum)
+            })
+            .collect()
+    }
+}
+
+/// Whether raising the transition matrix of a `width` columns wide row to
+/// the power `run` is expected to be cheaper than applying the row `run`
+/// times.
+fn worth_exponentiating(width: usize, run: usize) -> bool {
+    let squarings = run.ilog2() as usize + 1;
+
+    width
+        .checked_mul(width)
+        .and_then(|w2| w2.checked_mul(squarings))
+        .is_some_and(|cost| cost < run)
+}
+
+/// Apply `run` repetitions of the splitter row `splitters` to `count`.
+fn apply_run(
+    splitters: &[SplitterSpace],
+    run: usize,
+    count: &mut Vec<Count>,
+    rule: &Part2,
+) -> Result<(), Day7Error> {
+    let has_starters = splitters.contains(&SplitterSpace::Starter);
+
+    if !has_starters && worth_exponentiating(splitters.len(), run) {
+        match power_apply(splitters, run, count, rule) {
+            Ok(next) => {
+                *count = next;
+                return Ok(());
+            }
+            // A power of the matrix overflowed; retry row by row below
+            Err(Day7Error::Overflow) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    for _ in 0..run {
+        rule.apply(splitters, count)?;
+    }
+
+    Ok(())
+}
+
+/// `T^run · count`, with `T` the transition matrix of `splitters`.
+fn power_apply(
+    splitters: &[SplitterSpace],
+    mut run: usize,
+    count: &[Count],
+    rule: &Part2,
+) -> Result<Vec<Count>, Day7Error> {
+    let mut power = Matrix::transition(splitters, rule)?;
+    let mut result = count.to_vec();
+
+    while run > 0 {
+        if run & 1 == 1 {
+            result = power.apply(&result, rule)?;
+        }
+        run >>= 1;
+        if run > 0 {
+            power = power.mul(&power, rule)?;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Simulate part 2 like [`crate::rules::simulate`] with [`Part2`], but
+/// exponentiating runs of identical splitter rows.
+pub(crate) fn simulate_part_2(
+    start: Vec<StarterSpace>,
+    splitters: impl Iterator<Item = ParsedRow>,
+    options: &ManifoldOptions,
+) -> Result<Vec<Count>, Day7Error> {
+    let rule = Part2::new(options);
+    let mut count = rule.init(start);
+
+    // The current run of identical rows and its length
+    let mut run: Option<(Vec<SplitterSpace>, usize)> = None;
+
+    for splitter in splitters {
+        let splitter = splitter?;
+
+        run = match run {
+            Some((row, length)) if row == splitter => Some((row, length + 1)),
+            Some((row, length)) => {
+                apply_run(&row, length, &mut count, &rule)?;
+                Some((splitter, 1))
+            }
+            None => Some((splitter, 1)),
+        };
+    }
+
+    if let Some((row, length)) = run {
+        apply_run(&row, length, &mut count, &rule)?;
+    }
+
+    Ok(count)
+}
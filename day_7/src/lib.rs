@@ -1,47 +1,253 @@
 //! Advent of Code Day 7 — Laboratories
-use std::cell::Cell;
+use crate::bitset::BitsetPart1;
+use crate::parse::{ParsedRow, parse_input_lazy, parse_reader};
+use crate::rules::{Part1, Part1State, Part2, simulate};
+use std::collections::BTreeSet;
+use std::io::{self, BufRead};
+
+pub use crate::parse::{Layout, RowLengthPolicy};
+pub use crate::simulator::Simulator;
+
+mod bitset;
+mod fast;
+mod parse;
+pub mod reference;
+pub mod reverse;
+mod rules;
+mod simulator;
+mod two_pass;
+pub mod visualize;
+
+/// Errors that can occur while parsing or simulating the Day 7 manifold diagram.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Day7Error {
+    /// The input did not contain a starter row.
+    MissingStartRow,
+    /// A character that is not valid at its position was encountered.
+    InvalidChar {
+        /// The 1-based line number in the input.
+        row: usize,
+        /// The 1-based column within the line.
+        col: usize,
+        /// The offending character.
+        char: char,
+    },
+    /// A splitter row does not have the same width as the starter row.
+    RowLengthMismatch {
+        /// The 1-based line number in the input.
+        row: usize,
+        /// The width of the starter row.
+        expected: usize,
+        /// The width of the offending row.
+        found: usize,
+    },
+    /// A timeline count overflowed in strict mode.
+    Overflow,
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// A queried column lies outside of the diagram.
+    ColumnOutOfRange {
+        /// The 0-based column that was queried.
+        column: usize,
+        /// The width of the diagram.
+        width: usize,
+    },
+    /// The reference simulator exceeded its limit on the number of timelines.
+    TooManyTimelines {
+        /// The maximum number of timelines that was allowed.
+        limit: usize,
+    },
+}
+
+/// The number of timelines in a column. Part 2 doubles the count at every
+/// splitter, so deep grids need wide integers: `u128` by default, and an
+/// arbitrary precision `BigUint` with the `wide` feature.
+#[cfg(not(feature = "wide"))]
+pub type Count = u128;
+
+/// The number of timelines in a column. Part 2 doubles the count at every
+/// splitter, so deep grids need wide integers: `u128` by default, and an
+/// arbitrary precision `BigUint` with the `wide` feature.
+#[cfg(feature = "wide")]
+pub type Count = num_bigint::BigUint;
+
+/// Options controlling how a manifold diagram is read and simulated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ManifoldOptions {
+    /// How the splitter rows are arranged in the input.
+    pub layout: Layout,
+    /// How splitter rows of a different width than the starter row are handled.
+    pub row_lengths: RowLengthPolicy,
+    /// Accept `S` markers in splitter rows, starting an additional beam (and
+    /// timeline) below the marker when the simulation reaches its row.
+    /// Without this option they are rejected with [`Day7Error::InvalidChar`].
+    pub mid_grid_starters: bool,
+    /// Report [`Day7Error::Overflow`] instead of saturating at the largest
+    /// [`Count`] when a part 2 timeline count does not fit in one.
+    pub strict: bool,
+    /// Compute the part 2 timeline counts modulo this number, e.g.
+    /// `1_000_000_007`, to check diagrams whose exact counts are impractically
+    /// large. `None` and `Some(0)` give the exact counts.
+    pub modulus: Option<u64>,
+    /// What happens to beams split off over the left or right edge.
+    pub edge_mode: EdgeMode,
+    /// How every splitter row is applied to the beams.
+    pub algorithm: Algorithm,
+}
+
+/// The row update algorithm. All algorithms give the same results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Walk the row once and move every beam to the columns it lands in.
+    #[default]
+    Sequential,
+    /// Compute the left, right and downward moves of all columns as separate
+    /// vectors, then combine them per column. Both passes are independent per
+    /// column, so they vectorize well, and run in parallel with the
+    /// `parallel` feature. Pays off for very wide grids.
+    TwoPass,
+    /// Store the part 1 beams as a bitset, one bit per column, and update
+    /// whole words of 64 columns at once. Part 2 and the step-wise
+    /// visualization fall back to [`Algorithm::Sequential`].
+    Bitset,
+    /// Skip runs of identical splitter rows in part 2 by raising the linear
+    /// map of the row to the power of the run length. Much faster for narrow
+    /// diagrams that repeat rows many times; part 1 falls back to
+    /// [`Algorithm::Sequential`].
+    Fast,
+}
+
+/// What happens to the branch of a split beam that would leave the grid at
+/// the left or right edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeMode {
+    /// The branch leaves the grid and is lost.
+    #[default]
+    Absorb,
+    /// The branch re-enters the grid in the column at the opposite edge.
+    Wrap,
+    /// The branch bounces off the edge and continues in the edge column.
+    Reflect,
+}
+
+impl EdgeMode {
+    /// The column a branch leaving a grid of `width` columns on the left
+    /// lands in, or `None` if it is lost.
+    fn left_exit(&self, width: usize) -> Option<usize> {
+        match self {
+            EdgeMode::Absorb => None,
+            EdgeMode::Wrap => Some(width - 1),
+            EdgeMode::Reflect => Some(0),
+        }
+    }
+
+    /// The column a branch leaving a grid of `width` columns on the right
+    /// lands in, or `None` if it is lost.
+    fn right_exit(&self, width: usize) -> Option<usize> {
+        match self {
+            EdgeMode::Absorb => None,
+            EdgeMode::Wrap => Some(0),
+            EdgeMode::Reflect => Some(width - 1),
+        }
+    }
+
+    /// The column a beam in `col` lands in after moving `offset` columns
+    /// (`-1`, `0` or `1`), or `None` if it left the grid and is lost.
+    fn landing_column(&self, col: usize, offset: isize, width: usize) -> Option<usize> {
+        match col.checked_add_signed(offset) {
+            None => self.left_exit(width),
+            Some(target) if target >= width => self.right_exit(width),
+            Some(target) => Some(target),
+        }
+    }
+}
 
 /// Per-position beam state for a single row.
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
 enum BeamSpace {
+    #[default]
     Empty,
     Beam,
 }
 
-/// Per-position splitter state for a single row.
+/// Per-position tile of a splitter row.
 #[derive(Debug, PartialEq)]
 enum SplitterSpace {
+    /// `.`: beams pass straight through.
     Empty,
+    /// `^`: beams split into a left and a right branch.
     Splitter,
+    /// `\`: beams are shifted one column to the right.
+    MirrorRight,
+    /// `/`: beams are shifted one column to the left.
+    MirrorLeft,
+    /// `X`: beams are terminated.
+    Absorber,
+    /// `S`: beams pass straight through, and a new beam starts below the
+    /// tile. Only accepted with [`ManifoldOptions::mid_grid_starters`].
+    Starter,
+}
+
+impl SplitterSpace {
+    /// The column offsets at which a beam entering this tile continues in the
+    /// next row.
+    fn offsets(&self) -> &'static [isize] {
+        match self {
+            SplitterSpace::Empty => &[0],
+            SplitterSpace::Splitter => &[-1, 1],
+            SplitterSpace::MirrorRight => &[1],
+            SplitterSpace::MirrorLeft => &[-1],
+            SplitterSpace::Absorber => &[],
+            SplitterSpace::Starter => &[0],
+        }
+    }
+
+    /// The character representing the tile in the diagram.
+    fn symbol(&self) -> char {
+        match self {
+            SplitterSpace::Empty => '.',
+            SplitterSpace::Splitter => '^',
+            SplitterSpace::MirrorRight => '\\',
+            SplitterSpace::MirrorLeft => '/',
+            SplitterSpace::Absorber => 'X',
+            SplitterSpace::Starter => 'S',
+        }
+    }
 }
 
 /// Per-position starter state for the top row.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 enum StarterSpace {
     Empty,
     Starter,
 }
 
+/// The offending character is returned as the error.
 impl TryFrom<char> for BeamSpace {
-    type Error = ();
+    type Error = char;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
             '|' => Ok(BeamSpace::Beam),
             '.' => Ok(BeamSpace::Empty),
-            _ => Err(()),
+            _ => Err(value),
         }
     }
 }
 
+/// The offending character is returned as the error.
 impl TryFrom<char> for SplitterSpace {
-    type Error = ();
+    type Error = char;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
             '^' => Ok(SplitterSpace::Splitter),
             '.' => Ok(SplitterSpace::Empty),
-            _ => Err(()),
+            '\\' => Ok(SplitterSpace::MirrorRight),
+            '/' => Ok(SplitterSpace::MirrorLeft),
+            'X' => Ok(SplitterSpace::Absorber),
+            'S' => Ok(SplitterSpace::Starter),
+            _ => Err(value),
         }
     }
 }
@@ -56,163 +262,346 @@ impl From<StarterSpace> for BeamSpace {
     }
 }
 
+/// The offending character is returned as the error.
 impl TryFrom<char> for StarterSpace {
-    type Error = ();
+    type Error = char;
 
     fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
             'S' => Ok(StarterSpace::Starter),
             '.' => Ok(StarterSpace::Empty),
-            _ => Err(()),
+            _ => Err(value),
         }
     }
 }
 
-/// Initialize the beam row from the starter row.
-fn initiate_beams(input: Vec<StarterSpace>) -> Vec<BeamSpace> {
-    input.into_iter().map(BeamSpace::from).collect()
-}
-
-/// Apply one splitter row to the current beam row in place and update the split count.
+/// Apply one splitter row to the values of a row, in place.
+///
+/// All values of the row move simultaneously: the value in column i interacts only
+/// with the tile in column i, and continues in the next row according to that tile
+/// (see [`SplitterSpace::offsets`]). Values landing in the same column are combined
+/// with `merge`, and values leaving the grid at the left or right edge follow
+/// `edge_mode`. Columns holding the default value (no beam) are skipped.
 ///
-/// For each adjacent pair (i, i+1), we examine whether a beam at i hits a splitter at i.
-/// If so, that incoming beam stops at i and produces a rightward beam at i+1.
-/// Symmetrically, if there is a beam at i+1 hitting a splitter at i+1, we produce
-/// a leftward beam at i and increment the split counter by one.
-fn process_beams_part_1(
+/// This is the sequential algorithm; see [`two_pass`] for the two-pass variant.
+fn scatter_row<T, M>(
     splitters: &[SplitterSpace],
-    beams: &mut [BeamSpace],
-    count_splits: &mut u64,
-) {
-    let slice_of_cells = Cell::from_mut(beams).as_slice_of_cells();
+    state: &mut [T],
+    edge_mode: EdgeMode,
+    merge: M,
+) -> Result<(), Day7Error>
+where
+    T: Clone + Default + PartialEq,
+    M: Fn(&mut T, &T) -> Result<(), Day7Error>,
+{
+    let empty = T::default();
+    let width = state.len();
+    let mut next = vec![T::default(); width];
 
-    for (beam_window, splitter) in slice_of_cells.windows(2).zip(splitters.windows(2)) {
-        if beam_window[0].get() == BeamSpace::Beam && splitter[0] == SplitterSpace::Splitter {
-            beam_window[0].set(BeamSpace::Empty);
-            beam_window[1].set(BeamSpace::Beam);
+    for (col, (value, tile)) in state.iter().zip(splitters).enumerate() {
+        if *value == empty {
+            continue;
         }
 
-        if beam_window[1].get() == BeamSpace::Beam && splitter[1] == SplitterSpace::Splitter {
-            beam_window[0].set(BeamSpace::Beam);
-            *count_splits += 1;
+        for &offset in tile.offsets() {
+            if let Some(target) = edge_mode.landing_column(col, offset, width) {
+                merge(&mut next[target], value)?;
+            }
         }
     }
+
+    state.clone_from_slice(&next);
+
+    Ok(())
 }
 
-/// Part 2 row update: propagate timeline counts through one splitter row.
-///
-/// Model:
-/// - `count[i]` holds the number of active timelines in column i for the current row.
-/// - For an empty cell, timelines continue downward unchanged.
-/// - For a splitter at i, all timelines from i transfer to i-1 and i+1 in the next row.
+/// Add `value` to the timeline count `target`.
 ///
-/// Implementation detail:
-/// - We perform neighbor transfers using a `Cell` view over the `count` slice, iterating
-///   windows of size 2 alongside the splitter windows. This mirrors the Part 1 approach but
-///   adds counts instead of toggling beam presence.
-/// - When a splitter exists at the left index of the window and `count[left] > 0`, we move
-///   those timelines to `right` and clear `left` (they do not pass straight through).
-/// - When a splitter exists at the right index and `count[right] > 0`, we add those timelines
-///   to `left` (the symmetric branch transfer).
-fn process_beams_part_2(splitters: &[SplitterSpace], count: &mut [u64]) {
-    let slice_of_cells = Cell::from_mut(count).as_slice_of_cells();
+/// In strict mode an overflowing addition returns [`Day7Error::Overflow`];
+/// otherwise it saturates at `u128::MAX`, so every count too large to fit
+/// reads as `u128::MAX`.
+#[cfg(not(feature = "wide"))]
+fn add_count(target: &mut Count, value: &Count, strict: bool) -> Result<(), Day7Error> {
+    *target = match target.checked_add(*value) {
+        Some(sum) => sum,
+        None if strict => return Err(Day7Error::Overflow),
+        None => Count::MAX,
+    };
 
-    for (count_window, splitter) in slice_of_cells.windows(2).zip(splitters.windows(2)) {
-        if count_window[0].get() > 0 && splitter[0] == SplitterSpace::Splitter {
-            count_window[1].set(count_window[1].get() + count_window[0].get());
-            count_window[0].set(0);
-        }
+    Ok(())
+}
 
-        if count_window[1].get() > 0 && splitter[1] == SplitterSpace::Splitter {
-            count_window[0].set(count_window[0].get() + count_window[1].get());
-        }
-    }
+/// Add `value` to the timeline count `target`. Arbitrary precision counts
+/// never overflow, so `strict` has no effect.
+#[cfg(feature = "wide")]
+fn add_count(target: &mut Count, value: &Count, _strict: bool) -> Result<(), Day7Error> {
+    *target += value;
+
+    Ok(())
 }
 
-fn parse_input(input: &str) -> Result<(Vec<StarterSpace>, Vec<Vec<SplitterSpace>>), ()> {
-    let mut iter = input.lines().step_by(2);
-    let start: Vec<StarterSpace> = iter
-        .next()
-        .ok_or(())?
-        .chars()
-        .map(StarterSpace::try_from)
-        .collect::<Result<Vec<_>, _>>()?;
+/// Multiply two timeline counts.
+///
+/// Like [`add_count`], an overflowing product returns [`Day7Error::Overflow`]
+/// in strict mode and saturates at `u128::MAX` otherwise.
+#[cfg(not(feature = "wide"))]
+fn mul_count(a: &Count, b: &Count, strict: bool) -> Result<Count, Day7Error> {
+    match a.checked_mul(*b) {
+        Some(product) => Ok(product),
+        None if strict => Err(Day7Error::Overflow),
+        None => Ok(Count::MAX),
+    }
+}
 
-    let splitters: Vec<Vec<SplitterSpace>> = iter
-        .map(|line| line.chars().map(SplitterSpace::try_from).collect())
-        .collect::<Result<Vec<_>, _>>()?;
+/// Multiply two arbitrary precision timeline counts.
+#[cfg(feature = "wide")]
+fn mul_count(a: &Count, b: &Count, _strict: bool) -> Result<Count, Day7Error> {
+    Ok(a * b)
+}
 
-    Ok((start, splitters))
+/// Solve part 1 with the default [`ManifoldOptions`].
+pub fn solution_part_1(input: &str) -> Result<u64, Day7Error> {
+    solution_part_1_with_options(input, &ManifoldOptions::default())
 }
 
 /// Solve part 1: parse input, simulate all rows, and return the total split count.
-fn solution_part_1(input: &str) -> Result<u64, ()> {
-    let (start, splitters) = parse_input(input)?;
+pub fn solution_part_1_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<u64, Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
+    let state = simulate_part_1(start, splitters, options)?;
 
-    let mut beams = initiate_beams(start);
-    let mut count_splits = 0;
+    Ok(state.splits)
+}
 
-    splitters
-        .iter()
-        .for_each(|splitter| process_beams_part_1(&splitter, &mut beams, &mut count_splits));
+/// Solve part 1 while reading the diagram row by row from `reader`.
+///
+/// Only the current row is kept in memory, so diagrams with millions of rows
+/// are solved in memory proportional to their width.
+pub fn solution_part_1_from_reader<R: BufRead>(
+    reader: R,
+    options: &ManifoldOptions,
+) -> Result<u64, Day7Error> {
+    let (start, splitters) = parse_reader(reader, options)?;
+    let state = simulate_part_1(start, splitters, options)?;
 
-    Ok(count_splits)
+    Ok(state.splits)
 }
 
-/// Solve part 2: parse input, simulate the many‑worlds row updates, and sum timelines.
-///
-/// Steps:
-/// - Convert the starter row into an initial timeline count per column (1 at each `S`).
-/// - For each splitter row, apply `process_beams_part_2` to propagate counts to neighbors.
-/// - At the end, sum the counts across the last row to obtain the total number of timelines.
-fn solution_part_2(input: &str) -> Result<u64, ()> {
-    let (start, splitters) = parse_input(input)?;
-
-    let mut count: Vec<u64> = initiate_beams(start)
+/// The beams leaving the bottom of the grid in part 1, with the default
+/// [`ManifoldOptions`].
+pub fn final_beams(input: &str) -> Result<Vec<bool>, Day7Error> {
+    final_beams_with_options(input, &ManifoldOptions::default())
+}
+
+/// The beams leaving the bottom of the grid in part 1: `true` for every
+/// column that holds a beam after the last splitter row.
+pub fn final_beams_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<Vec<bool>, Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
+    let state = simulate_part_1(start, splitters, options)?;
+
+    Ok(state.beams.iter().map(|&b| b == BeamSpace::Beam).collect())
+}
+
+/// The columns in which beams exit the bottom of the grid in part 1.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BottomExits {
+    /// The 0-based columns holding a beam after the last splitter row.
+    pub columns: BTreeSet<usize>,
+}
+
+impl BottomExits {
+    /// The number of beams exiting the bottom of the grid.
+    pub fn count(&self) -> usize {
+        self.columns.len()
+    }
+}
+
+/// The columns where part 1 beams exit the bottom of the grid, with the
+/// default [`ManifoldOptions`].
+pub fn bottom_exits(input: &str) -> Result<BottomExits, Day7Error> {
+    bottom_exits_with_options(input, &ManifoldOptions::default())
+}
+
+/// The columns where part 1 beams exit the bottom of the grid, i.e. the
+/// positions of the `true` entries of [`final_beams_with_options`].
+pub fn bottom_exits_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<BottomExits, Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
+    let state = simulate_part_1(start, splitters, options)?;
+
+    let columns = state
+        .beams
         .iter()
-        .map(|&b| if b == BeamSpace::Beam { 1 } else { 0 })
+        .enumerate()
+        .filter(|&(_, &b)| b == BeamSpace::Beam)
+        .map(|(col, _)| col)
         .collect();
 
-    splitters
-        .iter()
-        .for_each(|splitter| process_beams_part_2(&splitter, &mut count));
+    Ok(BottomExits { columns })
+}
+
+/// The largest number of beams active at the same time in part 1, with the
+/// default [`ManifoldOptions`].
+pub fn max_concurrent_beams(input: &str) -> Result<usize, Day7Error> {
+    max_concurrent_beams_with_options(input, &ManifoldOptions::default())
+}
+
+/// The largest number of columns holding a beam in any row of the part 1
+/// simulation, from the starter row down to the beams leaving the grid.
+pub fn max_concurrent_beams_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<usize, Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
+    let state = simulate_part_1(start, splitters, options)?;
+
+    Ok(state.max_beams)
+}
+
+/// Simulate all splitter rows of part 1 as they are parsed, on bitset rows
+/// with [`Algorithm::Bitset`] and on [`BeamSpace`] rows otherwise.
+fn simulate_part_1(
+    start: Vec<StarterSpace>,
+    splitters: impl Iterator<Item = ParsedRow>,
+    options: &ManifoldOptions,
+) -> Result<Part1State, Day7Error> {
+    match options.algorithm {
+        Algorithm::Bitset => {
+            let rule = BitsetPart1 {
+                edge_mode: options.edge_mode,
+            };
+            Ok(simulate(&rule, start, splitters)?.into())
+        }
+        _ => simulate(&Part1::new(options), start, splitters),
+    }
+}
+
+/// Solve part 2 with the default [`ManifoldOptions`].
+pub fn solution_part_2(input: &str) -> Result<Count, Day7Error> {
+    solution_part_2_with_options(input, &ManifoldOptions::default())
+}
+
+/// Solve part 2: simulate the many‑worlds row updates with
+/// [`timeline_counts_with_options`] and sum the timelines across the last row.
+pub fn solution_part_2_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<Count, Day7Error> {
+    let count = timeline_counts_with_options(input, options)?;
+
+    sum_counts(&count, &Part2::new(options))
+}
+
+/// Solve part 2 while reading the diagram row by row from `reader`, in
+/// memory proportional to the width of the diagram.
+pub fn solution_part_2_from_reader<R: BufRead>(
+    reader: R,
+    options: &ManifoldOptions,
+) -> Result<Count, Day7Error> {
+    let (start, splitters) = parse_reader(reader, options)?;
+    let count = simulate_part_2(start, splitters, options)?;
+
+    sum_counts(&count, &Part2::new(options))
+}
+
+/// Simulate all splitter rows of part 2 as they are parsed, skipping runs of
+/// identical rows with [`Algorithm::Fast`].
+fn simulate_part_2(
+    start: Vec<StarterSpace>,
+    splitters: impl Iterator<Item = ParsedRow>,
+    options: &ManifoldOptions,
+) -> Result<Vec<Count>, Day7Error> {
+    match options.algorithm {
+        Algorithm::Fast => fast::simulate_part_2(start, splitters, options),
+        _ => simulate(&Part2::new(options), start, splitters),
+    }
+}
+
+/// The total number of timelines across all columns, added up like `rule`
+/// adds timelines.
+fn sum_counts(count: &[Count], rule: &Part2) -> Result<Count, Day7Error> {
+    let mut total = Count::default();
+    for timelines in count {
+        rule.add(&mut total, timelines)?;
+    }
+
+    Ok(total)
+}
+
+/// The number of part 2 timelines per column after the last splitter row,
+/// with the default [`ManifoldOptions`].
+pub fn timeline_counts(input: &str) -> Result<Vec<Count>, Day7Error> {
+    timeline_counts_with_options(input, &ManifoldOptions::default())
+}
+
+/// The number of part 2 timelines per column after the last splitter row.
+///
+/// Every column starts with one timeline per `S`, and every splitter row
+/// propagates the counts to the neighboring columns, see [`rules::Part2`].
+pub fn timeline_counts_with_options(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<Vec<Count>, Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
 
-    Ok(count.iter().sum())
+    simulate_part_2(start, splitters, options)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parse::parse_input;
+    use crate::rules::RowRule;
+    use aoc_core::rng::TestRng;
 
     #[test]
     fn test_process_beams_split() {
-        let mut beams = vec![BeamSpace::Empty, BeamSpace::Beam, BeamSpace::Empty];
+        let beams = vec![BeamSpace::Empty, BeamSpace::Beam, BeamSpace::Empty];
         let splitters = vec![
             SplitterSpace::Empty,
             SplitterSpace::Splitter,
             SplitterSpace::Empty,
         ];
-        let mut total = 0;
-        process_beams_part_1(&splitters, &mut beams, &mut total);
-        assert_eq!(
+        let mut state = Part1State {
             beams,
+            splits: 0,
+            max_beams: 1,
+        };
+        Part1::new(&Default::default())
+            .apply(&splitters, &mut state)
+            .unwrap();
+        assert_eq!(
+            state.beams,
             vec![BeamSpace::Beam, BeamSpace::Empty, BeamSpace::Beam]
         );
     }
 
     #[test]
     fn test_process_beams_straight_beam() {
-        let mut beams = vec![BeamSpace::Empty, BeamSpace::Beam, BeamSpace::Empty];
+        let beams = vec![BeamSpace::Empty, BeamSpace::Beam, BeamSpace::Empty];
         let splitters = vec![
             SplitterSpace::Splitter,
             SplitterSpace::Empty,
             SplitterSpace::Empty,
         ];
 
-        let mut total = 0;
-        process_beams_part_1(&splitters, &mut beams, &mut total);
-        assert_eq!(
+        let mut state = Part1State {
             beams,
+            splits: 0,
+            max_beams: 1,
+        };
+        Part1::new(&Default::default())
+            .apply(&splitters, &mut state)
+            .unwrap();
+        assert_eq!(
+            state.beams,
             vec![BeamSpace::Empty, BeamSpace::Beam, BeamSpace::Empty]
         );
     }
@@ -224,6 +613,833 @@ mod tests {
 
     #[test]
     fn test_solution_part_2() {
-        assert_eq!(solution_part_2(include_str!("sample_input.txt")), Ok(40));
+        assert_eq!(
+            solution_part_2(include_str!("sample_input.txt")),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    #[test]
+    fn test_missing_start_row() {
+        assert_eq!(solution_part_1(""), Err(Day7Error::MissingStartRow));
+    }
+
+    #[test]
+    fn test_invalid_char() {
+        assert_eq!(
+            solution_part_2("..S..\n.....\n..^x.\n"),
+            Err(Day7Error::InvalidChar {
+                row: 3,
+                col: 4,
+                char: 'x'
+            })
+        );
+        assert_eq!(
+            solution_part_1(".^S"),
+            Err(Day7Error::InvalidChar {
+                row: 1,
+                col: 2,
+                char: '^'
+            })
+        );
+    }
+
+    #[test]
+    fn test_row_length_mismatch() {
+        assert_eq!(
+            solution_part_1("..S..\n.....\n..^.\n"),
+            Err(Day7Error::RowLengthMismatch {
+                row: 3,
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(
+            solution_part_1("..S..\n.....\n..^...\n"),
+            Err(Day7Error::RowLengthMismatch {
+                row: 3,
+                expected: 5,
+                found: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_row_length_pad_short() {
+        let options = ManifoldOptions {
+            row_lengths: RowLengthPolicy::PadShort,
+            ..Default::default()
+        };
+        let input = include_str!("sample_input.txt")
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                if row == 0 {
+                    line
+                } else {
+                    line.trim_end_matches('.')
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(solution_part_1_with_options(&input, &options), Ok(21));
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Ok(Count::from(40u8))
+        );
+        assert_eq!(
+            solution_part_1_with_options("..S..\n.....\n..^...\n", &options),
+            Err(Day7Error::RowLengthMismatch {
+                row: 3,
+                expected: 5,
+                found: 6
+            })
+        );
+    }
+
+    #[test]
+    fn test_layout_dense() {
+        // Only keep the splitter rows, so every row is a splitter row
+        let input: String = include_str!("sample_input.txt")
+            .lines()
+            .step_by(2)
+            .map(|line| format!("{line}\n"))
+            .collect();
+        let options = ManifoldOptions {
+            layout: Layout::Dense,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options(&input, &options), Ok(21));
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    #[test]
+    fn test_layout_stride() {
+        // Two beam rows between every splitter row
+        let input: String = include_str!("sample_input.txt")
+            .lines()
+            .step_by(2)
+            .map(|line| format!("{line}\n...............\n...............\n"))
+            .collect();
+        let options = ManifoldOptions {
+            layout: Layout::Stride(3),
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options(&input, &options), Ok(21));
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    #[test]
+    fn test_layout_blank_line_separated() {
+        let input = include_str!("sample_input.txt").replace("...............\n", "\n");
+        let options = ManifoldOptions {
+            layout: Layout::BlankLineSeparated,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options(&input, &options), Ok(21));
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    /// A diagram of `depth` splitter rows that doubles the number of
+    /// timelines on every row, for `2^depth` timelines in total.
+    fn doubling_diagram(depth: usize) -> String {
+        let width = 2 * depth + 1;
+        let mut input = format!("{:^width$}\n", "S").replace(' ', ".");
+
+        for row in 0..depth {
+            // Splitters at every other column around the center
+            let line: String = (0..width)
+                .map(|col| {
+                    let offset = col.abs_diff(depth);
+                    if offset <= row && offset % 2 == row % 2 {
+                        '^'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            input.push_str(&format!("{}\n{line}\n", ".".repeat(width)));
+        }
+
+        input
+    }
+
+    #[test]
+    fn test_doubling_diagram_beyond_u64() {
+        let input = doubling_diagram(70);
+
+        assert_eq!(solution_part_2(&input), Ok(Count::from(2u8).pow(70)));
+    }
+
+    #[cfg(not(feature = "wide"))]
+    #[test]
+    fn test_strict_overflow() {
+        let input = doubling_diagram(130);
+        let options = ManifoldOptions {
+            strict: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Err(Day7Error::Overflow)
+        );
+        assert_eq!(solution_part_2(&input), Ok(Count::MAX));
+    }
+
+    #[test]
+    fn test_final_beams() {
+        let beams = final_beams(include_str!("sample_input.txt")).unwrap();
+        let columns: Vec<usize> = (0..beams.len()).filter(|&i| beams[i]).collect();
+
+        assert_eq!(columns, vec![0, 2, 4, 6, 8, 10, 11, 12, 14]);
+    }
+
+    #[test]
+    fn test_timeline_counts() {
+        let counts = timeline_counts(include_str!("sample_input.txt")).unwrap();
+        let expected: Vec<Count> = [1u8, 0, 2, 0, 10, 0, 11, 0, 11, 0, 2, 1, 1, 0, 1]
+            .into_iter()
+            .map(Count::from)
+            .collect();
+
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn test_render_frames() {
+        let frames =
+            visualize::render_frames(include_str!("sample_input.txt"), &Default::default())
+                .unwrap();
+
+        assert_eq!(frames.len(), 8);
+        assert_eq!(
+            frames[1].lines().take(4).collect::<Vec<_>>(),
+            vec![
+                ".......S.......",
+                ".......|.......",
+                "......|^|......",
+                "......|.|......"
+            ]
+        );
+        assert_eq!(
+            frames[7],
+            include_str!("sample_input.txt")
+                .lines()
+                .take(1)
+                .chain([
+                    ".......|.......",
+                    "......|^|......",
+                    "......|.|......",
+                    ".....|^|^|.....",
+                    ".....|.|.|.....",
+                    "....|^|^|^|....",
+                    "....|.|.|.|....",
+                    "...|^|^|||^|...",
+                    "...|.|.|||.|...",
+                    "..|^|^|||^|^|..",
+                    "..|.|.|||.|.|..",
+                    ".|^|||^||.||^|.",
+                    ".|.|||.||.||.|.",
+                    "|^|^|^|^|^|||^|",
+                    "|.|.|.|.|.|||.|",
+                ])
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_edge_mode_left() {
+        let input = "S...\n....\n^...\n";
+        let expected = [
+            (EdgeMode::Absorb, vec![false, true, false, false], 1u8),
+            (EdgeMode::Wrap, vec![false, true, false, true], 2),
+            (EdgeMode::Reflect, vec![true, true, false, false], 2),
+        ];
+
+        for (edge_mode, beams, timelines) in expected {
+            let options = ManifoldOptions {
+                edge_mode,
+                ..Default::default()
+            };
+
+            assert_eq!(solution_part_1_with_options(input, &options), Ok(1));
+            assert_eq!(final_beams_with_options(input, &options), Ok(beams));
+            assert_eq!(
+                solution_part_2_with_options(input, &options),
+                Ok(Count::from(timelines))
+            );
+        }
+    }
+
+    #[test]
+    fn test_edge_mode_right() {
+        let input = "...S\n....\n...^\n";
+        let expected = [
+            (EdgeMode::Absorb, vec![false, false, true, false], 1u8),
+            (EdgeMode::Wrap, vec![true, false, true, false], 2),
+            (EdgeMode::Reflect, vec![false, false, true, true], 2),
+        ];
+
+        for (edge_mode, beams, timelines) in expected {
+            let options = ManifoldOptions {
+                edge_mode,
+                ..Default::default()
+            };
+
+            assert_eq!(solution_part_1_with_options(input, &options), Ok(1));
+            assert_eq!(final_beams_with_options(input, &options), Ok(beams));
+            assert_eq!(
+                solution_part_2_with_options(input, &options),
+                Ok(Count::from(timelines))
+            );
+        }
+    }
+
+    #[test]
+    fn test_edge_mode_single_column() {
+        let options = ManifoldOptions {
+            edge_mode: EdgeMode::Reflect,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options("S\n.\n^\n", &options), Ok(1));
+        assert_eq!(
+            solution_part_2_with_options("S\n.\n^\n", &options),
+            Ok(Count::from(2u8))
+        );
+    }
+
+    #[test]
+    fn test_parse_additional_tiles() {
+        assert_eq!(
+            parse_input("S....\n.....\n.^\\/X\n", &Default::default())
+                .map(|(_, splitters)| splitters),
+            Ok(vec![vec![
+                SplitterSpace::Empty,
+                SplitterSpace::Splitter,
+                SplitterSpace::MirrorRight,
+                SplitterSpace::MirrorLeft,
+                SplitterSpace::Absorber
+            ]])
+        );
+    }
+
+    #[test]
+    fn test_mirrors_shift_beams() {
+        // The beam is shifted right, right again, and then back left
+        let input = ".S...\n.....\n.\\...\n.....\n..\\..\n.....\n.../.\n";
+
+        assert_eq!(
+            final_beams(input),
+            Ok(vec![false, false, true, false, false])
+        );
+        assert_eq!(solution_part_1(input), Ok(0));
+        assert_eq!(solution_part_2(input), Ok(Count::from(1u8)));
+    }
+
+    #[test]
+    fn test_absorber_terminates_beams() {
+        let input = "..S..\n.....\n..^..\n.....\n.X...\n";
+
+        assert_eq!(
+            final_beams(input),
+            Ok(vec![false, false, false, true, false])
+        );
+        assert_eq!(solution_part_1(input), Ok(1));
+        assert_eq!(solution_part_2(input), Ok(Count::from(1u8)));
+    }
+
+    #[test]
+    fn test_mirror_over_edge() {
+        let input = "S..\n...\n/..\n";
+        let options = ManifoldOptions {
+            edge_mode: EdgeMode::Wrap,
+            ..Default::default()
+        };
+
+        assert_eq!(final_beams(input), Ok(vec![false, false, false]));
+        assert_eq!(
+            final_beams_with_options(input, &options),
+            Ok(vec![false, false, true])
+        );
+    }
+
+    /// A pseudo-random diagram of `depth` rows with every tile kind, seeded
+    /// with `seed`.
+    fn random_diagram(width: usize, depth: usize, seed: u64) -> String {
+        let mut rng = TestRng::new(seed);
+        let mut next_tile = || match rng.below(10) {
+            0..=2 => '^',
+            3 => '\\',
+            4 => '/',
+            5 => 'X',
+            _ => '.',
+        };
+
+        let mut input: String = (0..width)
+            .map(|col| if col % 7 == 3 { 'S' } else { '.' })
+            .collect();
+        input.push('\n');
+        for _ in 0..depth {
+            input.push_str(&".".repeat(width));
+            input.push('\n');
+            input.extend((0..width).map(|_| next_tile()));
+            input.push('\n');
+        }
+
+        input
+    }
+
+    #[test]
+    fn test_two_pass_matches_sequential() {
+        for (seed, width) in [(1, 1), (2, 2), (3, 17), (4, 64)] {
+            let input = random_diagram(width, 40, seed);
+            for edge_mode in [EdgeMode::Absorb, EdgeMode::Wrap, EdgeMode::Reflect] {
+                let sequential = ManifoldOptions {
+                    edge_mode,
+                    ..Default::default()
+                };
+                let two_pass = ManifoldOptions {
+                    algorithm: Algorithm::TwoPass,
+                    ..sequential
+                };
+
+                assert_eq!(
+                    solution_part_1_with_options(&input, &two_pass),
+                    solution_part_1_with_options(&input, &sequential)
+                );
+                assert_eq!(
+                    final_beams_with_options(&input, &two_pass),
+                    final_beams_with_options(&input, &sequential)
+                );
+                assert_eq!(
+                    timeline_counts_with_options(&input, &two_pass),
+                    timeline_counts_with_options(&input, &sequential)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_solve_from_reader() {
+        let input = include_str!("sample_input.txt");
+
+        assert_eq!(
+            solution_part_1_from_reader(input.as_bytes(), &Default::default()),
+            Ok(21)
+        );
+        assert_eq!(
+            solution_part_2_from_reader(input.as_bytes(), &Default::default()),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        let options = ManifoldOptions::default();
+
+        assert_eq!(
+            solution_part_1_from_reader(&b""[..], &options),
+            Err(Day7Error::MissingStartRow)
+        );
+        assert_eq!(
+            solution_part_1_from_reader(&b"S..\n...\n.^^^\n"[..], &options),
+            Err(Day7Error::RowLengthMismatch {
+                row: 3,
+                expected: 3,
+                found: 4
+            })
+        );
+        assert_eq!(
+            solution_part_2_from_reader(&b"S.\n..\n\xff\n"[..], &options),
+            Err(Day7Error::Io(io::ErrorKind::InvalidData))
+        );
+    }
+
+    #[test]
+    fn test_bottom_exits() {
+        let input = include_str!("sample_input.txt");
+        let exits = bottom_exits(input).unwrap();
+
+        assert_eq!(exits.count(), 9);
+        assert_eq!(
+            exits.columns.into_iter().collect::<Vec<_>>(),
+            vec![0, 2, 4, 6, 8, 10, 11, 12, 14]
+        );
+        assert_eq!(bottom_exits("S..\n...\n..X\n").unwrap().count(), 1);
+        assert_eq!(bottom_exits("S..\n...\nX..\n").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_max_concurrent_beams() {
+        assert_eq!(
+            max_concurrent_beams(include_str!("sample_input.txt")),
+            Ok(9)
+        );
+        assert_eq!(max_concurrent_beams("S.S\n...\nXXX\n"), Ok(2));
+        assert_eq!(max_concurrent_beams("...\n...\n.^.\n"), Ok(0));
+    }
+
+    #[test]
+    fn test_mid_grid_starters() {
+        let input = "S....\n.....\n.....\n.....\n...S.\n.....\n...^.\n";
+        let options = ManifoldOptions {
+            mid_grid_starters: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            solution_part_1(input),
+            Err(Day7Error::InvalidChar {
+                row: 5,
+                col: 4,
+                char: 'S'
+            })
+        );
+        assert_eq!(
+            final_beams_with_options(input, &options),
+            Ok(vec![true, false, true, false, true])
+        );
+        assert_eq!(solution_part_1_with_options(input, &options), Ok(1));
+        assert_eq!(
+            solution_part_2_with_options(input, &options),
+            Ok(Count::from(3u8))
+        );
+    }
+
+    #[test]
+    fn test_mid_grid_starter_on_beam() {
+        let input = "..S..\n.....\n..S..\n.....\n..^..\n";
+        let options = ManifoldOptions {
+            mid_grid_starters: true,
+            algorithm: Algorithm::TwoPass,
+            ..Default::default()
+        };
+
+        assert_eq!(solution_part_1_with_options(input, &options), Ok(1));
+        assert_eq!(
+            timeline_counts_with_options(input, &options),
+            Ok(vec![
+                Count::default(),
+                Count::from(2u8),
+                Count::default(),
+                Count::from(2u8),
+                Count::default()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_contributing_starters() {
+        use crate::reverse::{Contribution, contributing_starters};
+
+        let input = include_str!("sample_input.txt");
+        let counts = timeline_counts(input).unwrap();
+
+        for (column, count) in counts.iter().enumerate() {
+            let timelines: Vec<Count> = contributing_starters(input, column)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.timelines)
+                .collect();
+
+            assert_eq!(
+                sum_counts(&timelines, &Part2::new(&Default::default())).as_ref(),
+                Ok(count)
+            );
+        }
+
+        assert_eq!(
+            contributing_starters(".SS\n...\n.^.\n", 2),
+            Ok(vec![
+                Contribution {
+                    row: 0,
+                    column: 1,
+                    timelines: Count::from(1u8)
+                },
+                Contribution {
+                    row: 0,
+                    column: 2,
+                    timelines: Count::from(1u8)
+                }
+            ])
+        );
+        assert_eq!(
+            contributing_starters("S..\n...\n^..\n", 3),
+            Err(Day7Error::ColumnOutOfRange {
+                column: 3,
+                width: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_contributing_mid_grid_starters() {
+        use crate::reverse::contributing_starters_with_options;
+
+        let input = "S....\n.....\n.....\n.....\n...S.\n.....\n...^.\n";
+        let options = ManifoldOptions {
+            mid_grid_starters: true,
+            ..Default::default()
+        };
+        let contributions = contributing_starters_with_options(input, 4, &options).unwrap();
+
+        assert_eq!(contributions.len(), 1);
+        assert_eq!((contributions[0].row, contributions[0].column), (2, 3));
+    }
+
+    #[test]
+    fn test_bitset_matches_sequential() {
+        for (seed, width) in [(5, 1), (6, 63), (7, 64), (8, 65), (9, 130)] {
+            let input = random_diagram(width, 40, seed);
+            for edge_mode in [EdgeMode::Absorb, EdgeMode::Wrap, EdgeMode::Reflect] {
+                let sequential = ManifoldOptions {
+                    edge_mode,
+                    ..Default::default()
+                };
+                let bitset = ManifoldOptions {
+                    algorithm: Algorithm::Bitset,
+                    ..sequential
+                };
+
+                assert_eq!(
+                    solution_part_1_with_options(&input, &bitset),
+                    solution_part_1_with_options(&input, &sequential)
+                );
+                assert_eq!(
+                    final_beams_with_options(&input, &bitset),
+                    final_beams_with_options(&input, &sequential)
+                );
+                assert_eq!(
+                    max_concurrent_beams_with_options(&input, &bitset),
+                    max_concurrent_beams_with_options(&input, &sequential)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_modulus() {
+        let input = doubling_diagram(200);
+        let options = ManifoldOptions {
+            modulus: Some(1_000_000_007),
+            strict: true,
+            ..Default::default()
+        };
+
+        // 2^200 mod 1_000_000_007, one doubling at a time
+        let mut expected: u64 = 1;
+        for _ in 0..200 {
+            expected = expected * 2 % 1_000_000_007;
+        }
+
+        assert_eq!(
+            solution_part_2_with_options(&input, &options),
+            Ok(Count::from(expected))
+        );
+        assert_eq!(
+            solution_part_2_with_options(
+                include_str!("sample_input.txt"),
+                &ManifoldOptions {
+                    modulus: Some(7),
+                    ..Default::default()
+                }
+            ),
+            Ok(Count::from(40u8 % 7))
+        );
+        assert_eq!(
+            solution_part_2_with_options(
+                include_str!("sample_input.txt"),
+                &ManifoldOptions {
+                    modulus: Some(0),
+                    ..Default::default()
+                }
+            ),
+            Ok(Count::from(40u8))
+        );
+    }
+
+    #[test]
+    fn test_simulator() {
+        let input = include_str!("sample_input.txt");
+        let mut rows = input.lines().step_by(2);
+        let mut simulator = Simulator::new(rows.next().unwrap(), &Default::default()).unwrap();
+
+        assert_eq!(simulator.width(), 15);
+        assert_eq!(simulator.total_timelines(), Ok(Count::from(1u8)));
+
+        simulator.step(rows.next().unwrap()).unwrap();
+        assert_eq!(simulator.splits(), 1);
+        assert_eq!(
+            simulator.timelines()[6..9],
+            [Count::from(1u8), Count::default(), Count::from(1u8)]
+        );
+
+        for row in rows {
+            simulator.step(row).unwrap();
+        }
+
+        assert_eq!(simulator.steps(), 7);
+        assert_eq!(simulator.splits(), 21);
+        assert_eq!(simulator.max_concurrent_beams(), 9);
+        assert_eq!(Ok(simulator.beams()), final_beams(input));
+        assert_eq!(simulator.total_timelines(), Ok(Count::from(40u8)));
+    }
+
+    #[test]
+    fn test_simulator_errors() {
+        let mut simulator = Simulator::new("..S..", &Default::default()).unwrap();
+        simulator.step("..^..").unwrap();
+
+        assert_eq!(
+            simulator.step("..^."),
+            Err(Day7Error::RowLengthMismatch {
+                row: 3,
+                expected: 5,
+                found: 4
+            })
+        );
+        assert_eq!(
+            simulator.step("..#.."),
+            Err(Day7Error::InvalidChar {
+                row: 3,
+                col: 3,
+                char: '#'
+            })
+        );
+        assert_eq!(simulator.steps(), 1);
+        assert_eq!(simulator.beams(), vec![false, true, false, true, false]);
+    }
+
+    #[test]
+    fn test_reference_matches_counting() {
+        let sample = include_str!("sample_input.txt");
+        assert_eq!(
+            reference::timeline_counts(sample, &Default::default(), 1000),
+            timeline_counts(sample)
+        );
+
+        for (seed, width) in [(10, 1), (11, 2), (12, 9), (13, 16)] {
+            let input = random_diagram(width, 12, seed);
+            for edge_mode in [EdgeMode::Absorb, EdgeMode::Wrap, EdgeMode::Reflect] {
+                let options = ManifoldOptions {
+                    edge_mode,
+                    ..Default::default()
+                };
+
+                assert_eq!(
+                    reference::timeline_counts(&input, &options, 1 << 20),
+                    timeline_counts_with_options(&input, &options)
+                );
+            }
+        }
+
+        let input = "S....\n.....\n.....\n.....\n...S.\n.....\n...^.\n";
+        let options = ManifoldOptions {
+            mid_grid_starters: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            reference::timeline_counts(input, &options, 10),
+            timeline_counts_with_options(input, &options)
+        );
+    }
+
+    #[test]
+    fn test_reference_limit() {
+        assert_eq!(
+            reference::timeline_counts(&doubling_diagram(10), &Default::default(), 1000),
+            Err(Day7Error::TooManyTimelines { limit: 1000 })
+        );
+    }
+
+    /// A dense diagram repeating `row` `repeat` times below `start`.
+    fn repeated_diagram(start: &str, row: &str, repeat: usize) -> String {
+        format!("{start}\n{}", format!("{row}\n").repeat(repeat))
+    }
+
+    #[test]
+    fn test_fast_matches_sequential() {
+        let inputs = [
+            repeated_diagram("..S..", ".^.^.", 300),
+            repeated_diagram("S.S", "^^^", 150) + &repeated_diagram("", "\\.X", 100)[1..],
+            repeated_diagram("...S....", "..^.^/.\\", 1000),
+            include_str!("sample_input.txt").replace("...............\n", ""),
+        ];
+
+        for input in &inputs {
+            for edge_mode in [EdgeMode::Absorb, EdgeMode::Wrap, EdgeMode::Reflect] {
+                for modulus in [None, Some(1_000_000_007)] {
+                    let sequential = ManifoldOptions {
+                        layout: Layout::Dense,
+                        edge_mode,
+                        modulus,
+                        ..Default::default()
+                    };
+                    let fast = ManifoldOptions {
+                        algorithm: Algorithm::Fast,
+                        ..sequential
+                    };
+
+                    assert_eq!(
+                        timeline_counts_with_options(input, &fast),
+                        timeline_counts_with_options(input, &sequential)
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(not(feature = "wide"))]
+    #[test]
+    fn test_fast_strict_overflow() {
+        // The powers of the transition matrix overflow, the counts do not
+        let input = repeated_diagram("...", "^^^", 300);
+        let options = ManifoldOptions {
+            layout: Layout::Dense,
+            edge_mode: EdgeMode::Wrap,
+            strict: true,
+            algorithm: Algorithm::Fast,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            timeline_counts_with_options(&input, &options),
+            Ok(vec![0; 3])
+        );
+
+        let input = repeated_diagram(".S.", "^^^", 300);
+        assert_eq!(
+            timeline_counts_with_options(&input, &options),
+            Err(Day7Error::Overflow)
+        );
+
+        // Outside strict mode both algorithms saturate
+        for algorithm in [Algorithm::Fast, Algorithm::default()] {
+            let options = ManifoldOptions {
+                strict: false,
+                algorithm,
+                ..options
+            };
+            assert_eq!(
+                timeline_counts_with_options(&input, &options),
+                Ok(vec![Count::MAX; 3])
+            );
+        }
     }
 }
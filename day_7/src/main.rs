@@ -0,0 +1,60 @@
+//! Command line interface for Advent of Code Day 7 — Laboratories
+//!
+//! Usage: `day_7 [--visualize] <input file>`
+//!
+//! Prints the answers to both parts. With `--visualize`, the beam propagation
+//! is first animated in the terminal.
+
+use day_7::visualize::render_frames;
+use day_7::{ManifoldOptions, solution_part_1, solution_part_2};
+use std::time::Duration;
+use std::{env, fs, process, thread};
+
+/// Delay between two animation frames.
+const FRAME_DELAY: Duration = Duration::from_millis(150);
+
+fn main() {
+    let mut visualize = false;
+    let mut path = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--visualize" => visualize = true,
+            _ => path = Some(arg),
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("Usage: day_7 [--visualize] <input file>");
+        process::exit(2);
+    };
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {path}: {err}");
+        process::exit(1);
+    });
+
+    if visualize {
+        let frames = render_frames(&input, &ManifoldOptions::default()).unwrap_or_else(|err| {
+            eprintln!("Could not parse {path}: {err:?}");
+            process::exit(1);
+        });
+
+        for frame in frames {
+            // Clear the screen and move the cursor to the top left corner
+            println!("\x1b[2J\x1b[H{frame}");
+            thread::sleep(FRAME_DELAY);
+        }
+    }
+
+    match (solution_part_1(&input), solution_part_2(&input)) {
+        (Ok(part_1), Ok(part_2)) => {
+            println!("Part 1: {part_1}");
+            println!("Part 2: {part_2}");
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("Could not parse {path}: {err:?}");
+            process::exit(1);
+        }
+    }
+}
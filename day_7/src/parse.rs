@@ -0,0 +1,192 @@
+// Parsing helpers for Day 7: Laboratories
+//
+// Expected input format (puzzle layout):
+// .......S.......   (starter row, `S` marks where the beam enters)
+// ...............   (beam-only row, ignored)
+// .......^.......   (splitter row, `^` marks a splitter)
+// ...
+//
+// Other sources format the same diagram differently, see `Layout`.
+
+use crate::{Day7Error, ManifoldOptions, SplitterSpace, StarterSpace};
+use std::io::BufRead;
+
+/// How the splitter rows are arranged in the input, below the starter row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// Splitter rows alternate with beam-only rows, as in the puzzle input.
+    #[default]
+    Interleaved,
+    /// Every row after the starter row is a splitter row.
+    Dense,
+    /// Only every `n`-th line is a splitter row; the lines in between are
+    /// ignored. `Stride(2)` is [`Layout::Interleaved`] and `Stride(1)` is
+    /// [`Layout::Dense`]. A stride of `0` is treated as `1`.
+    Stride(usize),
+    /// Rows are separated by blank lines, and every non-blank line after the
+    /// starter row is a splitter row.
+    BlankLineSeparated,
+}
+
+/// What to do with splitter rows that are not as wide as the starter row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RowLengthPolicy {
+    /// Reject the diagram with [`Day7Error::RowLengthMismatch`].
+    #[default]
+    Strict,
+    /// Pad rows shorter than the starter row with empty tiles (`.`), e.g.
+    /// for diagrams whose trailing dots were stripped by an editor. Longer
+    /// rows are still rejected, as no beam could reach their extra tiles.
+    PadShort,
+}
+
+impl Layout {
+    /// Whether the line with the 0-based `index` is a row of the diagram,
+    /// i.e. the starter row or a splitter row.
+    fn is_row(&self, index: usize, line: &str) -> bool {
+        match *self {
+            Layout::Interleaved => index.is_multiple_of(2),
+            Layout::Dense => true,
+            Layout::Stride(n) => index.is_multiple_of(n.max(1)),
+            Layout::BlankLineSeparated => !line.trim().is_empty(),
+        }
+    }
+
+    /// Select the rows of the diagram, starting with the starter row, together
+    /// with their 1-based line numbers.
+    fn rows(self, input: &str) -> impl Iterator<Item = (usize, &str)> {
+        let layout = self;
+
+        input
+            .lines()
+            .enumerate()
+            .filter(move |&(index, line)| layout.is_row(index, line))
+            .map(|(index, line)| (index + 1, line))
+    }
+
+    /// Like [`Layout::rows`], but reading the lines one by one from `reader`.
+    fn read_rows<R: BufRead>(
+        self,
+        reader: R,
+    ) -> impl Iterator<Item = Result<(usize, String), Day7Error>> {
+        let layout = self;
+
+        reader
+            .lines()
+            .enumerate()
+            .filter_map(move |(index, line)| match line {
+                Ok(line) => layout.is_row(index, &line).then_some(Ok((index + 1, line))),
+                Err(err) => Some(Err(Day7Error::Io(err.kind()))),
+            })
+    }
+}
+
+/// A splitter row, or the error encountered while reading or parsing it.
+pub type ParsedRow = Result<Vec<SplitterSpace>, Day7Error>;
+
+/// Parse a single row of the diagram, reporting the position of any invalid
+/// character. `row` is the 1-based line number used in the error.
+fn parse_row<T: TryFrom<char, Error = char>>(line: &str, row: usize) -> Result<Vec<T>, Day7Error> {
+    line.chars()
+        .enumerate()
+        .map(|(index, c)| {
+            T::try_from(c).map_err(|char| Day7Error::InvalidChar {
+                row,
+                col: index + 1,
+                char,
+            })
+        })
+        .collect()
+}
+
+/// Parse a splitter row of the diagram, checking it against the width of the
+/// starter row. `row` is the 1-based line number used in errors.
+pub fn parse_splitter_row(
+    line: &str,
+    row: usize,
+    width: usize,
+    options: &ManifoldOptions,
+) -> ParsedRow {
+    let mut splitters: Vec<SplitterSpace> = parse_row(line, row)?;
+
+    if !options.mid_grid_starters
+        && let Some(index) = splitters.iter().position(|s| *s == SplitterSpace::Starter)
+    {
+        return Err(Day7Error::InvalidChar {
+            row,
+            col: index + 1,
+            char: 'S',
+        });
+    }
+
+    if options.row_lengths == RowLengthPolicy::PadShort && splitters.len() < width {
+        splitters.resize_with(width, || SplitterSpace::Empty);
+    }
+
+    if splitters.len() != width {
+        return Err(Day7Error::RowLengthMismatch {
+            row,
+            expected: width,
+            found: splitters.len(),
+        });
+    }
+
+    Ok(splitters)
+}
+
+/// Parse the starter row of the diagram. `row` is the 1-based line number
+/// used in errors.
+pub fn parse_starter_row(line: &str, row: usize) -> Result<Vec<StarterSpace>, Day7Error> {
+    parse_row(line, row)
+}
+
+/// Parse the starter row, and return it together with a lazy iterator over
+/// the parsed splitter rows, so only one row is held in memory at a time.
+fn parse_rows<L, I>(
+    mut rows: I,
+    options: ManifoldOptions,
+) -> Result<(Vec<StarterSpace>, impl Iterator<Item = ParsedRow>), Day7Error>
+where
+    L: AsRef<str>,
+    I: Iterator<Item = Result<(usize, L), Day7Error>>,
+{
+    let (start_row, start_line) = rows.next().ok_or(Day7Error::MissingStartRow)??;
+    let start = parse_starter_row(start_line.as_ref(), start_row)?;
+    let width = start.len();
+
+    let splitters = rows.map(move |row| {
+        let (row, line) = row?;
+        parse_splitter_row(line.as_ref(), row, width, &options)
+    });
+
+    Ok((start, splitters))
+}
+
+/// Parse the diagram into the starter row and a lazy iterator over the
+/// splitter rows, selecting the rows according to `options.layout`.
+pub fn parse_input_lazy<'a>(
+    input: &'a str,
+    options: &ManifoldOptions,
+) -> Result<(Vec<StarterSpace>, impl Iterator<Item = ParsedRow> + use<'a>), Day7Error> {
+    parse_rows(options.layout.rows(input).map(Ok), *options)
+}
+
+/// Like [`parse_input_lazy`], but reading the diagram line by line from
+/// `reader`.
+pub fn parse_reader<R: BufRead>(
+    reader: R,
+    options: &ManifoldOptions,
+) -> Result<(Vec<StarterSpace>, impl Iterator<Item = ParsedRow> + use<R>), Day7Error> {
+    parse_rows(options.layout.read_rows(reader), *options)
+}
+
+/// Parse the diagram into the starter row and the splitter rows, selecting
+/// the rows according to `options.layout`.
+pub fn parse_input(
+    input: &str,
+    options: &ManifoldOptions,
+) -> Result<(Vec<StarterSpace>, Vec<Vec<SplitterSpace>>), Day7Error> {
+    let (start, splitters) = parse_input_lazy(input, options)?;
+
+    Ok((start, splitters.collect::<Result<Vec<_>, _>>()?))
+}
@@ -0,0 +1,78 @@
+// Reference timeline simulator for Day 7: Laboratories
+//
+// The part 2 solution never looks at individual timelines: it only counts
+// them per column. This module follows every timeline on its own instead,
+// as a list of the columns the timelines are in. A timeline on a splitter is
+// replaced by one timeline per branch, all in the same row update, exactly
+// like the puzzle describes it. That is exponentially slow, but simple enough
+// to check the counting against on small diagrams.
+
+use crate::parse::parse_input;
+use crate::rules::{Part2, RowRule};
+use crate::{Count, Day7Error, ManifoldOptions, SplitterSpace, StarterSpace};
+
+/// The number of part 2 timelines per column after the last splitter row,
+/// found by following every timeline individually.
+///
+/// Gives the same results as [`crate::timeline_counts_with_options`], but
+/// takes time and memory proportional to the number of timelines. Fails with
+/// [`Day7Error::TooManyTimelines`] as soon as more than `max_timelines`
+/// timelines exist at the same time.
+pub fn timeline_counts(
+    input: &str,
+    options: &ManifoldOptions,
+    max_timelines: usize,
+) -> Result<Vec<Count>, Day7Error> {
+    let (start, splitters) = parse_input(input, options)?;
+    let width = start.len();
+
+    // The column of every timeline
+    let mut timelines: Vec<usize> = start
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s == StarterSpace::Starter)
+        .map(|(col, _)| col)
+        .collect();
+    check_limit(&timelines, max_timelines)?;
+
+    for row in &splitters {
+        let mut next = Vec::new();
+
+        for &col in &timelines {
+            let tile = &row[col];
+            for &offset in tile.offsets() {
+                if let Some(target) = options.edge_mode.landing_column(col, offset, width) {
+                    next.push(target);
+                }
+            }
+        }
+
+        for (col, tile) in row.iter().enumerate() {
+            if *tile == SplitterSpace::Starter {
+                next.push(col);
+            }
+        }
+
+        check_limit(&next, max_timelines)?;
+        timelines = next;
+    }
+
+    let rule = Part2::new(options);
+    let mut count = rule.init(vec![StarterSpace::Empty; width]);
+    for col in timelines {
+        rule.add(&mut count[col], &Count::from(1u8))?;
+    }
+
+    Ok(count)
+}
+
+/// Fail if there are more than `max_timelines` timelines.
+fn check_limit(timelines: &[usize], max_timelines: usize) -> Result<(), Day7Error> {
+    if timelines.len() > max_timelines {
+        return Err(Day7Error::TooManyTimelines {
+            limit: max_timelines,
+        });
+    }
+
+    Ok(())
+}
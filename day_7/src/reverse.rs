@@ -0,0 +1,113 @@
+// Reverse reachability for Day 7: Laboratories
+//
+// Part 2 pushes timeline counts down from the starters. Running the same
+// dynamic program backwards from a single bottom column instead gives, for
+// every position, the number of timelines leading from there to that
+// column. Evaluated at the starters, this tells how many of the timelines at
+// the bottom column each starter contributes.
+
+use crate::parse::parse_input;
+use crate::rules::Part2;
+use crate::{Count, Day7Error, ManifoldOptions, SplitterSpace, StarterSpace};
+
+/// The timelines a single starter contributes to a bottom column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contribution {
+    /// The row of the starter: `0` for the starter row, and `k` for a
+    /// mid-grid starter in the `k`-th splitter row.
+    pub row: usize,
+    /// The 0-based column of the starter.
+    pub column: usize,
+    /// The number of timelines from this starter ending in the bottom column.
+    pub timelines: Count,
+}
+
+/// The starters contributing timelines to the bottom `column`, with the
+/// default [`ManifoldOptions`].
+pub fn contributing_starters(input: &str, column: usize) -> Result<Vec<Contribution>, Day7Error> {
+    contributing_starters_with_options(input, column, &ManifoldOptions::default())
+}
+
+/// The starters contributing timelines to the 0-based bottom `column`, i.e.
+/// the column after the last splitter row, ordered from the top row down and
+/// left to right. Starters without timelines reaching the column are left
+/// out. The timelines of all contributions add up to the part 2 timeline
+/// count of the column.
+pub fn contributing_starters_with_options(
+    input: &str,
+    column: usize,
+    options: &ManifoldOptions,
+) -> Result<Vec<Contribution>, Day7Error> {
+    let (start, splitters) = parse_input(input, options)?;
+    let width = start.len();
+
+    if column >= width {
+        return Err(Day7Error::ColumnOutOfRange { column, width });
+    }
+
+    // paths[c]: the number of timelines from column c just below the rows
+    // processed so far to the bottom column.
+    let mut paths = vec![Count::default(); width];
+    paths[column] = Count::from(1u8);
+
+    let mut contributions = Vec::new();
+
+    for (index, row) in splitters.iter().enumerate().rev() {
+        // Mid-grid starters start their beams right below their own row.
+        contributions.extend(
+            row.iter()
+                .enumerate()
+                .filter(|(_, tile)| **tile == SplitterSpace::Starter)
+                .map(|(col, _)| Contribution {
+                    row: index + 1,
+                    column: col,
+                    timelines: Count::clone(&paths[col]),
+                }),
+        );
+
+        paths = step_back(row, &paths, options)?;
+    }
+
+    contributions.extend(
+        start
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| **s == StarterSpace::Starter)
+            .map(|(col, _)| Contribution {
+                row: 0,
+                column: col,
+                timelines: Count::clone(&paths[col]),
+            }),
+    );
+
+    let zero = Count::default();
+    contributions.retain(|c| c.timelines != zero);
+    contributions.sort_by_key(|c| (c.row, c.column));
+
+    Ok(contributions)
+}
+
+/// The number of timelines to the bottom column from every column above
+/// `splitters`, given the numbers `below` the row.
+fn step_back(
+    splitters: &[SplitterSpace],
+    below: &[Count],
+    options: &ManifoldOptions,
+) -> Result<Vec<Count>, Day7Error> {
+    let width = below.len();
+    let rule = Part2::new(options);
+
+    splitters
+        .iter()
+        .enumerate()
+        .map(|(col, tile)| {
+            let mut paths = Count::default();
+            for &offset in tile.offsets() {
+                if let Some(target) = options.edge_mode.landing_column(col, offset, width) {
+                    rule.add(&mut paths, &below[target])?;
+                }
+            }
+            Ok(paths)
+        })
+        .collect()
+}
@@ -0,0 +1,226 @@
+// Row update rules for Day 7: Laboratories
+//
+// Both parts push some per-column state through the splitter rows one row at
+// a time: part 1 tracks which columns hold a beam, part 2 how many timelines
+// each column holds. A `RowRule` describes one such state and how a single
+// splitter row changes it; `simulate` drives any rule over all rows.
+
+use crate::parse::ParsedRow;
+use crate::{
+    Algorithm, BeamSpace, Count, Day7Error, EdgeMode, ManifoldOptions, SplitterSpace, StarterSpace,
+    add_count, mul_count, scatter_row, two_pass,
+};
+
+/// How a per-column state evolves through the splitter rows.
+pub(crate) trait RowRule {
+    /// The state carried from one row to the next.
+    type State;
+
+    /// The state entering the first splitter row.
+    fn init(&self, start: Vec<StarterSpace>) -> Self::State;
+
+    /// Apply one splitter row to `state` in place.
+    fn apply(&self, splitters: &[SplitterSpace], state: &mut Self::State) -> Result<(), Day7Error>;
+}
+
+/// Apply `rule` to every splitter row as it is parsed, and return the state
+/// after the last row.
+pub(crate) fn simulate<R: RowRule>(
+    rule: &R,
+    start: Vec<StarterSpace>,
+    splitters: impl Iterator<Item = ParsedRow>,
+) -> Result<R::State, Day7Error> {
+    let mut state = rule.init(start);
+
+    for splitter in splitters {
+        rule.apply(&splitter?, &mut state)?;
+    }
+
+    Ok(state)
+}
+
+/// Move the values of a row to the columns they land in with the selected
+/// algorithm, combining values landing in the same column with `merge`.
+fn update_row<T, M>(
+    algorithm: Algorithm,
+    splitters: &[SplitterSpace],
+    state: &mut [T],
+    edge_mode: EdgeMode,
+    merge: M,
+) -> Result<(), Day7Error>
+where
+    T: Clone + Default + PartialEq + Send + Sync,
+    M: Fn(&mut T, &T) -> Result<(), Day7Error> + Sync + Send,
+{
+    match algorithm {
+        Algorithm::Sequential | Algorithm::Bitset | Algorithm::Fast => {
+            scatter_row(splitters, state, edge_mode, merge)
+        }
+        Algorithm::TwoPass => two_pass::update_row(splitters, state, edge_mode, merge),
+    }
+}
+
+/// The columns of the mid-grid starters in a splitter row, which start a new
+/// beam below themselves after the row update.
+fn starters(splitters: &[SplitterSpace]) -> impl Iterator<Item = usize> {
+    splitters
+        .iter()
+        .enumerate()
+        .filter(|(_, tile)| **tile == SplitterSpace::Starter)
+        .map(|(col, _)| col)
+}
+
+/// Part 1: beams merge when they land in the same column, and every beam
+/// hitting a splitter counts as one split.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Part1 {
+    pub edge_mode: EdgeMode,
+    pub algorithm: Algorithm,
+}
+
+/// The beams in every column, the number of splits so far, and the largest
+/// number of beams in any row so far.
+#[derive(Debug, Clone)]
+pub(crate) struct Part1State {
+    pub beams: Vec<BeamSpace>,
+    pub splits: u64,
+    pub max_beams: usize,
+}
+
+/// The number of columns holding a beam.
+fn count_beams(beams: &[BeamSpace]) -> usize {
+    beams.iter().filter(|&&b| b == BeamSpace::Beam).count()
+}
+
+impl Part1 {
+    pub fn new(options: &ManifoldOptions) -> Self {
+        Part1 {
+            edge_mode: options.edge_mode,
+            algorithm: options.algorithm,
+        }
+    }
+}
+
+impl RowRule for Part1 {
+    type State = Part1State;
+
+    fn init(&self, start: Vec<StarterSpace>) -> Part1State {
+        let beams: Vec<BeamSpace> = start.into_iter().map(BeamSpace::from).collect();
+
+        Part1State {
+            max_beams: count_beams(&beams),
+            beams,
+            splits: 0,
+        }
+    }
+
+    fn apply(&self, splitters: &[SplitterSpace], state: &mut Part1State) -> Result<(), Day7Error> {
+        state.splits += state
+            .beams
+            .iter()
+            .zip(splitters)
+            .filter(|&(&beam, tile)| beam == BeamSpace::Beam && *tile == SplitterSpace::Splitter)
+            .count() as u64;
+
+        update_row(
+            self.algorithm,
+            splitters,
+            &mut state.beams,
+            self.edge_mode,
+            |target, beam| {
+                if *beam == BeamSpace::Beam {
+                    *target = BeamSpace::Beam;
+                }
+                Ok(())
+            },
+        )?;
+
+        for col in starters(splitters) {
+            state.beams[col] = BeamSpace::Beam;
+        }
+
+        state.max_beams = state.max_beams.max(count_beams(&state.beams));
+
+        Ok(())
+    }
+}
+
+/// Part 2: the timelines of a column all follow the tile of that column, and
+/// timelines landing in the same column add up. Additions are checked when
+/// `strict` is set (see [`add_count`]), and reduced modulo `modulus` if set.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Part2 {
+    pub edge_mode: EdgeMode,
+    pub algorithm: Algorithm,
+    pub strict: bool,
+    pub modulus: Option<u64>,
+}
+
+impl Part2 {
+    pub fn new(options: &ManifoldOptions) -> Self {
+        Part2 {
+            edge_mode: options.edge_mode,
+            algorithm: options.algorithm,
+            strict: options.strict,
+            modulus: options.modulus.filter(|&m| m != 0),
+        }
+    }
+
+    /// Add `value` to the timeline count `target`, modulo the modulus if
+    /// one is set.
+    pub fn add(&self, target: &mut Count, value: &Count) -> Result<(), Day7Error> {
+        add_count(target, value, self.strict)?;
+
+        if let Some(modulus) = self.modulus {
+            *target %= Count::from(modulus);
+        }
+
+        Ok(())
+    }
+
+    /// The product of two timeline counts, checked like [`Part2::add`] and
+    /// modulo the modulus if one is set.
+    pub fn mul(&self, a: &Count, b: &Count) -> Result<Count, Day7Error> {
+        let mut product = mul_count(a, b, self.strict)?;
+
+        if let Some(modulus) = self.modulus {
+            product %= Count::from(modulus);
+        }
+
+        Ok(product)
+    }
+}
+
+impl RowRule for Part2 {
+    type State = Vec<Count>;
+
+    fn init(&self, start: Vec<StarterSpace>) -> Vec<Count> {
+        start
+            .into_iter()
+            .map(|s| {
+                let mut count = Count::default();
+                if s == StarterSpace::Starter {
+                    // Cannot fail: 0 + 1 neither overflows nor exceeds a modulus
+                    let _ = self.add(&mut count, &Count::from(1u8));
+                }
+                count
+            })
+            .collect()
+    }
+
+    fn apply(&self, splitters: &[SplitterSpace], count: &mut Vec<Count>) -> Result<(), Day7Error> {
+        update_row(
+            self.algorithm,
+            splitters,
+            count,
+            self.edge_mode,
+            |target, timelines| self.add(target, timelines),
+        )?;
+
+        for col in starters(splitters) {
+            self.add(&mut count[col], &Count::from(1u8))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+// Step-wise simulation for Day 7: Laboratories
+//
+// The batch solutions parse a whole diagram and only report the final
+// answers. `Simulator` instead holds the state between two rows, so callers
+// can feed the splitter rows one at a time and inspect the beams and timeline
+// counts after every step.
+
+use crate::parse::{parse_splitter_row, parse_starter_row};
+use crate::rules::{Part1, Part1State, Part2, RowRule};
+use crate::{BeamSpace, Count, Day7Error, ManifoldOptions, sum_counts};
+
+/// Simulates both parts of a manifold diagram one splitter row at a time.
+///
+/// Rows are given without the layout of the input: every row passed to
+/// [`Simulator::step`] is a splitter row. Errors report the 1-based number of
+/// the offending row among all rows given, the starter row being row 1.
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    options: ManifoldOptions,
+    part_1: Part1,
+    part_2: Part2,
+    beams: Part1State,
+    timelines: Vec<Count>,
+    steps: usize,
+}
+
+impl Simulator {
+    /// Start a simulation from the starter row.
+    pub fn new(starter_row: &str, options: &ManifoldOptions) -> Result<Self, Day7Error> {
+        let start = parse_starter_row(starter_row, 1)?;
+        let part_1 = Part1::new(options);
+        let part_2 = Part2::new(options);
+
+        Ok(Simulator {
+            options: *options,
+            beams: part_1.init(start.clone()),
+            timelines: part_2.init(start),
+            part_1,
+            part_2,
+            steps: 0,
+        })
+    }
+
+    /// Move the beams and timelines through one splitter row. On error the
+    /// state is left unchanged.
+    pub fn step(&mut self, splitter_row: &str) -> Result<(), Day7Error> {
+        let splitters =
+            parse_splitter_row(splitter_row, self.steps + 2, self.width(), &self.options)?;
+
+        let mut timelines = self.timelines.clone();
+        self.part_2.apply(&splitters, &mut timelines)?;
+        self.part_1.apply(&splitters, &mut self.beams)?;
+        self.timelines = timelines;
+        self.steps += 1;
+
+        Ok(())
+    }
+
+    /// The number of columns of the diagram.
+    pub fn width(&self) -> usize {
+        self.beams.beams.len()
+    }
+
+    /// The number of splitter rows simulated so far.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The part 1 beams below the last simulated row: `true` for every column
+    /// holding a beam.
+    pub fn beams(&self) -> Vec<bool> {
+        self.beams
+            .beams
+            .iter()
+            .map(|&b| b == BeamSpace::Beam)
+            .collect()
+    }
+
+    /// The number of part 1 splits so far.
+    pub fn splits(&self) -> u64 {
+        self.beams.splits
+    }
+
+    /// The largest number of part 1 beams in any row so far.
+    pub fn max_concurrent_beams(&self) -> usize {
+        self.beams.max_beams
+    }
+
+    /// The number of part 2 timelines per column below the last simulated row.
+    pub fn timelines(&self) -> &[Count] {
+        &self.timelines
+    }
+
+    /// The total number of part 2 timelines below the last simulated row.
+    pub fn total_timelines(&self) -> Result<Count, Day7Error> {
+        sum_counts(&self.timelines, &self.part_2)
+    }
+}
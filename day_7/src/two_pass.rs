@@ -0,0 +1,91 @@
+// Two-pass row update
+//
+// The sequential row update walks the row once and scatters every beam into
+// the columns it lands in. Here the same update is split in two passes that
+// are independent per column:
+//
+// 1. Split every column into what moves down, what moves to the left and
+//    what moves to the right, as three separate vectors.
+// 2. Gather the next row: column i receives the down move of i, the right
+//    move of i - 1 and the left move of i + 1.
+//
+// Only the two edge columns need extra care, depending on the `EdgeMode`.
+// Without data dependencies between columns both passes vectorize, and with
+// the `parallel` feature they are spread over the rayon thread pool.
+use crate::{Day7Error, EdgeMode, SplitterSpace};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// Build a vector of `width` values from a per-column function.
+#[cfg(not(feature = "parallel"))]
+fn map_columns<T, F>(width: usize, f: F) -> Result<Vec<T>, Day7Error>
+where
+    T: Send,
+    F: Fn(usize) -> Result<T, Day7Error> + Sync + Send,
+{
+    (0..width).map(f).collect()
+}
+
+/// Build a vector of `width` values from a per-column function, in parallel.
+#[cfg(feature = "parallel")]
+fn map_columns<T, F>(width: usize, f: F) -> Result<Vec<T>, Day7Error>
+where
+    T: Send,
+    F: Fn(usize) -> Result<T, Day7Error> + Sync + Send,
+{
+    (0..width).into_par_iter().map(f).collect()
+}
+
+/// Two-pass equivalent of [`crate::scatter_row`]: apply one splitter row to
+/// `state`, combining values that land in the same column with `merge`.
+pub(crate) fn update_row<T, M>(
+    splitters: &[SplitterSpace],
+    state: &mut [T],
+    edge_mode: EdgeMode,
+    merge: M,
+) -> Result<(), Day7Error>
+where
+    T: Clone + Default + Send + Sync,
+    M: Fn(&mut T, &T) -> Result<(), Day7Error> + Sync + Send,
+{
+    let width = state.len();
+    if width == 0 {
+        return Ok(());
+    }
+
+    let moves = |offset: isize| {
+        map_columns(width, |col| {
+            Ok(if splitters[col].offsets().contains(&offset) {
+                state[col].clone()
+            } else {
+                T::default()
+            })
+        })
+    };
+    let left = moves(-1)?;
+    let down = moves(0)?;
+    let right = moves(1)?;
+
+    let mut next = map_columns(width, |col| {
+        let mut value = down[col].clone();
+        if col > 0 {
+            merge(&mut value, &right[col - 1])?;
+        }
+        if col + 1 < width {
+            merge(&mut value, &left[col + 1])?;
+        }
+        Ok(value)
+    })?;
+
+    if let Some(target) = edge_mode.left_exit(width) {
+        merge(&mut next[target], &left[0])?;
+    }
+    if let Some(target) = edge_mode.right_exit(width) {
+        merge(&mut next[target], &right[width - 1])?;
+    }
+
+    state.clone_from_slice(&next);
+
+    Ok(())
+}
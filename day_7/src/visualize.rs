@@ -0,0 +1,87 @@
+// Beam propagation visualization for Day 7: Laboratories
+//
+// Renders the part 1 simulation as a sequence of text frames in the style of
+// the puzzle description: `S` for the starter, `^` for splitters and `|` for
+// beams. Frame `k` shows the beams after they passed the first `k` splitter
+// rows, so playing the frames in order animates the beams moving down the
+// manifold. Mirrors and absorbers are drawn with their own symbols.
+//
+// The diagram is always drawn in the interleaved layout (a beam row above
+// every splitter row), regardless of the layout of the input.
+
+use crate::parse::parse_input;
+use crate::rules::{Part1, RowRule};
+use crate::{BeamSpace, Day7Error, ManifoldOptions, SplitterSpace};
+
+/// Render one frame per simulation step, from the initial state (frame `0`)
+/// until the beams left the last splitter row.
+pub fn render_frames(input: &str, options: &ManifoldOptions) -> Result<Vec<String>, Day7Error> {
+    let (start, splitters) = parse_input(input, options)?;
+
+    // history[r] holds the beams entering splitter row `r`; the last entry
+    // holds the beams leaving the grid.
+    let rule = Part1::new(options);
+    let mut state = rule.init(start);
+    let mut history = vec![state.beams.clone()];
+
+    for splitter in &splitters {
+        rule.apply(splitter, &mut state)?;
+        history.push(state.beams.clone());
+    }
+
+    Ok((0..=splitters.len())
+        .map(|step| render_frame(&splitters, &history, step))
+        .collect())
+}
+
+/// Render the frame in which the beams passed `step` splitter rows.
+fn render_frame(
+    splitters: &[Vec<SplitterSpace>],
+    history: &[Vec<BeamSpace>],
+    step: usize,
+) -> String {
+    let width = history[0].len();
+    let no_beams = vec![BeamSpace::Empty; width];
+    let beams_at = |row: usize| {
+        if row <= step {
+            &history[row]
+        } else {
+            &no_beams
+        }
+    };
+
+    let mut lines = Vec::with_capacity(2 * splitters.len() + 2);
+
+    lines.push(
+        history[0]
+            .iter()
+            .map(|&b| if b == BeamSpace::Beam { 'S' } else { '.' })
+            .collect::<String>(),
+    );
+
+    for (row, splitter) in splitters.iter().enumerate() {
+        lines.push(render_beams(beams_at(row)));
+        lines.push(
+            splitter
+                .iter()
+                .zip(beams_at(row + 1))
+                .map(|(s, &b)| match (s, b) {
+                    (SplitterSpace::Empty, BeamSpace::Beam) => '|',
+                    (tile, _) => tile.symbol(),
+                })
+                .collect(),
+        );
+    }
+
+    lines.push(render_beams(beams_at(splitters.len())));
+
+    lines.join("\n")
+}
+
+/// Render a beam-only row.
+fn render_beams(beams: &[BeamSpace]) -> String {
+    beams
+        .iter()
+        .map(|&b| if b == BeamSpace::Beam { '|' } else { '.' })
+        .collect()
+}
@@ -0,0 +1,97 @@
+//! Compare the k closest pairs algorithms on generated point clouds.
+//!
+//! Run with `cargo bench -p day_8`, optionally with `--features parallel`.
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use day_8::{
+    AutoAlgorithm, BruteForceAlgorithm, GridHashAlgorithm, HeapAlgorithm, KClosestNeighbor,
+    KdTreeAlgorithm, Position, VpTreeAlgorithm,
+};
+
+/// `n` positions in a cube of side 100 000, spread evenly or, when
+/// `clustered`, around 10 random centers.
+fn generate_cloud(n: usize, clustered: bool) -> Vec<Position> {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |range: u64| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % range
+    };
+
+    let centers: Vec<[u64; 3]> = (0..10)
+        .map(|_| [next(90_000), next(90_000), next(90_000)])
+        .collect();
+
+    (0..n)
+        .map(|_| {
+            if clustered {
+                let center = centers[next(10) as usize];
+                Position(center.map(|c| c + next(2_000)))
+            } else {
+                Position([next(100_000), next(100_000), next(100_000)])
+            }
+        })
+        .collect()
+}
+
+fn bench<A: KClosestNeighbor>(
+    group: &mut criterion::BenchmarkGroup<criterion::measurement::WallTime>,
+    name: &str,
+    algorithm: A,
+    cloud: &str,
+    positions: &[Position],
+    k: usize,
+) {
+    group.bench_with_input(
+        BenchmarkId::new(
+            format!("{cloud}/{name}"),
+            format!("n={}/k={k}", positions.len()),
+        ),
+        positions,
+        |b, positions| b.iter(|| algorithm.closest_neighbors(positions, k)),
+    );
+}
+
+fn closest_pairs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("closest_pairs");
+    group.sample_size(10);
+
+    for clustered in [false, true] {
+        let cloud = if clustered { "clustered" } else { "uniform" };
+
+        for n in [100, 1_000, 10_000, 100_000] {
+            let positions = generate_cloud(n, clustered);
+
+            for k in [n / 10, n, 10 * n] {
+                // The quadratic algorithms take minutes beyond this
+                if n <= 1_000 {
+                    bench(
+                        &mut group,
+                        "brute_force",
+                        BruteForceAlgorithm,
+                        cloud,
+                        &positions,
+                        k,
+                    );
+                    bench(&mut group, "heap", HeapAlgorithm, cloud, &positions, k);
+                }
+                bench(
+                    &mut group,
+                    "grid_hash",
+                    GridHashAlgorithm,
+                    cloud,
+                    &positions,
+                    k,
+                );
+                bench(&mut group, "kd_tree", KdTreeAlgorithm, cloud, &positions, k);
+                bench(&mut group, "vp_tree", VpTreeAlgorithm, cloud, &positions, k);
+                bench(&mut group, "auto", AutoAlgorithm, cloud, &positions, k);
+            }
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, closest_pairs);
+criterion_main!(benches);
@@ -0,0 +1,189 @@
+// Approximate k closest pairs by randomly shifted space-filling curves
+//
+// The Z-order curve visits the cells of a grid by interleaving the bits of
+// their coordinates, so points that are close on the curve are close in
+// space. The converse mostly holds too: a close pair is split only where it
+// straddles the boundary of a large cell, and a random shift of the grid
+// makes that unlikely, which makes the curve a locality-sensitive ordering.
+//
+// Every round sorts the points along the curve of a newly shifted grid and
+// measures the exact distance of each point to the `window` points following
+// it. The pairs found over all rounds are merged in a bounded heap, which
+// keeps the memory at O(n + k) however large the cloud.
+
+use crate::{Distance, KClosestNeighbor, Metric, Position};
+use std::collections::{BinaryHeap, HashSet};
+
+/// Finds approximately the k closest pairs along randomly shifted Z-order
+/// curves, in O(rounds · n (log n + window)) time, for clouds too large for
+/// the exact algorithms.
+///
+/// Recall is the fraction of the exact k closest pairs in the result. Every
+/// returned pair is a real pair with its exact distance, ordered as by
+/// [`crate::BruteForceAlgorithm`], so only missed pairs are in error, and the
+/// result is exact once `window` is at least `n - 1`.
+///
+/// Otherwise the recall is heuristic: there is no bound on it in terms of
+/// `rounds` and `window`. A random shift keeps a close pair inside one large
+/// cell with high probability, but how far apart along the curve that puts
+/// its points depends on how many other points share the cell, that is on
+/// the density of the cloud. Check the recall on a given cloud with
+/// [`estimate_recall`], which compares a sample of the boxes against exact
+/// distances; it is the only check of the result.
+#[derive(Debug, Clone, Copy)]
+pub struct ApproxAlgorithm {
+    /// The number of randomly shifted curves.
+    pub rounds: usize,
+    /// How many following points along the curve every point is compared
+    /// with.
+    pub window: usize,
+    /// The seed of the random shifts.
+    pub seed: u64,
+}
+
+impl Default for ApproxAlgorithm {
+    fn default() -> Self {
+        ApproxAlgorithm {
+            rounds: 8,
+            window: 16,
+            seed: 0x9e37_79b9_7f4a_7c15,
+        }
+    }
+}
+
+impl KClosestNeighbor for ApproxAlgorithm {
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        let min: [u64; D] = std::array::from_fn(|axis| {
+            positions
+                .iter()
+                .map(|p| p.coordinate(axis))
+                .min()
+                .unwrap_or(0)
+        });
+        let extent: [u128; D] = std::array::from_fn(|axis| {
+            positions
+                .iter()
+                .map(|p| u128::from(p.coordinate(axis) - min[axis]))
+                .max()
+                .unwrap_or(0)
+        });
+        // Shifted coordinates are below twice the extent, and are cut to
+        // their highest bits for all of them to fit in one key
+        let largest = 2 * extent.iter().copied().max().unwrap_or(0);
+        let drop = (128 - largest.leading_zeros()).saturating_sub(128 / D.max(1) as u32);
+
+        let mut state = self.seed | 1;
+        let mut best = BinaryHeap::new();
+        // The pairs in `best`, as the same pair is found in several rounds
+        let mut found = HashSet::new();
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+
+        for _ in 0..self.rounds {
+            let shift: [u128; D] =
+                std::array::from_fn(|axis| random(&mut state) % (extent[axis] + 1));
+            order.sort_by_cached_key(|&i| z_order(&positions[i], &min, &shift, drop));
+
+            for (place, &a) in order.iter().enumerate() {
+                for &b in order.iter().skip(place + 1).take(self.window) {
+                    let pair = (
+                        metric.distance(&positions[a], &positions[b]),
+                        a.min(b),
+                        a.max(b),
+                    );
+                    if found.contains(&(pair.1, pair.2)) {
+                        continue;
+                    }
+
+                    if best.len() < k {
+                        best.push(pair);
+                        found.insert((pair.1, pair.2));
+                    } else if best.peek().is_some_and(|worst| pair < *worst) {
+                        if let Some((_, i, j)) = best.pop() {
+                            found.remove(&(i, j));
+                        }
+                        best.push(pair);
+                        found.insert((pair.1, pair.2));
+                    }
+                }
+            }
+        }
+
+        best.into_sorted_vec()
+    }
+}
+
+/// The next value of the xorshift generator `state`.
+fn random(state: &mut u64) -> u128 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+
+    u128::from(*state)
+}
+
+/// The position of `position` on the Z-order curve after shifting it by
+/// `shift`: the bits of its coordinates interleaved, without the lowest `drop`
+/// bits of each.
+fn z_order<const D: usize>(
+    position: &Position<D>,
+    min: &[u64; D],
+    shift: &[u128; D],
+    drop: u32,
+) -> u128 {
+    let coordinates: [u128; D] = std::array::from_fn(|axis| {
+        (u128::from(position.coordinate(axis) - min[axis]) + shift[axis]) >> drop
+    });
+
+    let mut key = 0;
+    for bit in (0..128 / D.max(1) as u32).rev() {
+        for coordinate in coordinates {
+            key = key << 1 | (coordinate >> bit) & 1;
+        }
+    }
+    key
+}
+
+/// Estimate the recall of the (approximate) k closest `pairs` of `positions`
+/// by checking `sample` evenly spread points against exact distances.
+///
+/// An exact result holds every pair closer than its last pair, so a pair
+/// closer than the last of `pairs` that is not in `pairs` was missed. For
+/// every sampled point all pairs closer than that are computed, and the
+/// fraction of them in `pairs` is returned: `1.0` for an exact result. Takes
+/// O(sample · n) time.
+pub fn estimate_recall<const D: usize>(
+    positions: &[Position<D>],
+    pairs: &[(Distance, usize, usize)],
+    sample: usize,
+    metric: impl Metric,
+) -> f64 {
+    let Some(&(threshold, _, _)) = pairs.last() else {
+        return 1.0;
+    };
+    let returned: HashSet<(usize, usize)> = pairs.iter().map(|&(_, i, j)| (i, j)).collect();
+
+    let step = (positions.len() / sample.max(1)).max(1);
+    let mut expected = 0;
+    let mut found = 0;
+    for i in (0..positions.len()).step_by(step).take(sample) {
+        for (j, other) in positions.iter().enumerate() {
+            if j != i && metric.distance(&positions[i], other) < threshold {
+                expected += 1;
+                if returned.contains(&(i.min(j), i.max(j))) {
+                    found += 1;
+                }
+            }
+        }
+    }
+
+    if expected == 0 {
+        1.0
+    } else {
+        found as f64 / expected as f64
+    }
+}
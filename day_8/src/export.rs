@@ -0,0 +1,108 @@
+// JSON export of the connected edges and circuits
+//
+// External tools (3D viewers, notebooks) only need plain data: every box with
+// its coordinates and the id of its circuit, and every connected edge. Circuit
+// ids follow the order of `circuits`, so circuit 0 is the largest one.
+
+use crate::{
+    Day8Error, Distance, DuplicatePolicy, KClosestNeighbor, Metric, Position, SquaredEuclidean,
+    UnionFind, apply_duplicate_policy, parse_input,
+};
+use serde::Serialize;
+
+/// The junction boxes and connected edges of a set of circuits, ready to be
+/// serialized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CircuitExport {
+    pub boxes: Vec<ExportedBox>,
+    pub edges: Vec<ExportedEdge>,
+}
+
+/// A junction box and the circuit it belongs to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedBox {
+    /// The index of the box in the input.
+    pub index: usize,
+    pub coordinates: Vec<u64>,
+    /// The circuit of the box, `0` for the largest circuit.
+    pub component: usize,
+}
+
+/// A connected edge between the boxes `i < j`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ExportedEdge {
+    pub distance: Distance,
+    pub i: usize,
+    pub j: usize,
+}
+
+impl CircuitExport {
+    /// Connect the `edges` between `positions` and collect the resulting
+    /// circuits.
+    pub fn new<const D: usize>(
+        positions: &[Position<D>],
+        edges: &[(Distance, usize, usize)],
+    ) -> Self {
+        let mut uf = UnionFind::new(positions.len());
+        for &(_, i, j) in edges {
+            uf.union(i, j);
+        }
+
+        let mut circuits = uf.components();
+        circuits.sort_by_key(|circuit| std::cmp::Reverse(circuit.len()));
+
+        let mut component = vec![0; positions.len()];
+        for (id, circuit) in circuits.iter().enumerate() {
+            for &i in circuit {
+                component[i] = id;
+            }
+        }
+
+        CircuitExport {
+            boxes: positions
+                .iter()
+                .enumerate()
+                .map(|(index, position)| ExportedBox {
+                    index,
+                    coordinates: position.0.to_vec(),
+                    component: component[index],
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|&(distance, i, j)| ExportedEdge { distance, i, j })
+                .collect(),
+        }
+    }
+
+    /// The export as a JSON object with the fields `boxes` and `edges`.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// The circuits after connecting the `n` shortest edges, as in part 1, for
+/// export.
+pub fn export_circuits(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+) -> Result<CircuitExport, Day8Error> {
+    export_circuits_nd::<3>(input, algorithm, n, SquaredEuclidean, DuplicatePolicy::Keep)
+}
+
+/// [`export_circuits`] for positions with `D` coordinates, with distances
+/// measured by `metric` and repeated positions handled by `duplicates`.
+pub fn export_circuits_nd<const D: usize>(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+    metric: impl Metric,
+    duplicates: DuplicatePolicy,
+) -> Result<CircuitExport, Day8Error> {
+    let mut numbers = parse_input::<D>(input)?;
+    apply_duplicate_policy(&mut numbers, duplicates)?;
+    let edges = algorithm.closest_neighbors_by(&numbers, n, metric);
+
+    Ok(CircuitExport::new(&numbers, &edges))
+}
@@ -0,0 +1,159 @@
+// Spatial hash grid over the junction box positions
+//
+// The points are bucketed into hypercubic cells of side `s`. Two points at a
+// distance of at most `s` lie in the same or in adjacent cells, so comparing
+// only the points of adjacent cells finds every pair at distance <= s. If at
+// least k of those pairs are found, they contain the k closest pairs;
+// otherwise the cell size is doubled and the search repeated. Once the cells
+// are as wide as the bounding box of the points, every pair lies in the same
+// or in adjacent cells, and all of them are kept whatever their distance.
+//
+// The initial cell size is chosen from the point density so that about k
+// pairs are expected within one cell side for uniformly spread points.
+
+use crate::{Distance, KClosestNeighbor, Metric, Position};
+use std::collections::HashMap;
+
+/// Finds the k closest pairs by comparing only points in adjacent cells of a
+/// uniform grid. Simpler than the KD-tree, and fast when the points are
+/// spread evenly or in a few dense clusters.
+pub struct GridHashAlgorithm;
+
+/// A cell of the grid.
+type Cell<const D: usize> = [u64; D];
+
+impl KClosestNeighbor for GridHashAlgorithm {
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        if positions.len() < 2 || k == 0 {
+            return Vec::new();
+        }
+
+        let extent = extent(positions);
+        let mut side = initial_cell_side(positions.len(), k, extent, D);
+
+        loop {
+            // The points differ by at most `extent` along every axis, so with
+            // cells at least that wide every pair is found. That also ends
+            // the search when `side` reaches `u64::MAX`.
+            let all_found = side >= extent;
+            let max_distance = if all_found {
+                Distance::MAX
+            } else {
+                metric.axis_distance(side)
+            };
+
+            let mut pairs = pairs_within(positions, side, max_distance, metric);
+            pairs.sort_unstable();
+
+            if pairs.len() >= k || all_found {
+                pairs.truncate(k);
+                return pairs;
+            }
+
+            side = side.saturating_mul(2);
+        }
+    }
+}
+
+/// The largest extent of the bounding box of `positions` along any axis.
+fn extent<const D: usize>(positions: &[Position<D>]) -> u64 {
+    (0..D)
+        .map(|axis| {
+            let coordinates = positions.iter().map(|p| p.coordinate(axis));
+            let min = coordinates.clone().min().unwrap_or(0);
+            let max = coordinates.max().unwrap_or(0);
+            max - min
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// The cell side for which about `k` of the pairs of `n` points spread evenly
+/// over a hypercube of side `extent` in `dimensions` dimensions lie within one
+/// cell side of each other.
+///
+/// The expected number of pairs within distance `s` is about
+/// `n² / 2 · V · s^D / extent^D`, with `V` the volume of the unit ball.
+fn initial_cell_side(n: usize, k: usize, extent: u64, dimensions: usize) -> u64 {
+    let d = dimensions as f64;
+    let volume = (extent.max(1) as f64).powf(d);
+    let side = (2.0 * k as f64 * volume / (unit_ball_volume(dimensions) * (n as f64).powi(2)))
+        .powf(1.0 / d);
+
+    (side.ceil() as u64).max(1)
+}
+
+/// All pairs `(distance, i, j)` with `i < j` in the same or in adjacent cells
+/// of side `side` at a distance of at most `max_distance`, unordered. With a
+/// `max_distance` of at most the axis distance of `side`, that is every pair
+/// at such a distance: they differ by at most `side` along every axis.
+fn pairs_within<const D: usize>(
+    positions: &[Position<D>],
+    side: u64,
+    max_distance: Distance,
+    metric: impl Metric,
+) -> Vec<(Distance, usize, usize)> {
+    let cell_of = |p: &Position<D>| p.0.map(|c| c / side);
+
+    let mut cells: HashMap<Cell<D>, Vec<usize>> = HashMap::new();
+    for (index, position) in positions.iter().enumerate() {
+        cells.entry(cell_of(position)).or_default().push(index);
+    }
+
+    let mut pairs = Vec::new();
+    let mut push = |i: usize, j: usize| {
+        let distance = metric.distance(&positions[i], &positions[j]);
+        if distance <= max_distance {
+            pairs.push((distance, i.min(j), i.max(j)));
+        }
+    };
+
+    for (&cell, members) in &cells {
+        for (a, &i) in members.iter().enumerate() {
+            for &j in &members[a + 1..] {
+                push(i, j);
+            }
+        }
+
+        // Every pair of distinct cells once: only look at larger neighbors
+        for neighbor in neighbors(cell).filter(|&neighbor| neighbor > cell) {
+            if let Some(others) = cells.get(&neighbor) {
+                for &i in members {
+                    for &j in others {
+                        push(i, j);
+                    }
+                }
+            }
+        }
+    }
+
+    pairs
+}
+
+/// The up to `3^D - 1` cells adjacent to `cell`.
+fn neighbors<const D: usize>(cell: Cell<D>) -> impl Iterator<Item = Cell<D>> {
+    // Every offset vector in {-1, 0, 1}^D, encoded in base 3
+    (0..3usize.pow(D as u32)).filter_map(move |mut code| {
+        let mut neighbor = cell;
+        for coordinate in &mut neighbor {
+            *coordinate = coordinate.checked_add_signed((code % 3) as i64 - 1)?;
+            code /= 3;
+        }
+
+        (neighbor != cell).then_some(neighbor)
+    })
+}
+
+/// The volume of the unit ball in `dimensions` dimensions.
+fn unit_ball_volume(dimensions: usize) -> f64 {
+    match dimensions {
+        0 => 1.0,
+        1 => 2.0,
+        d => unit_ball_volume(d - 2) * 2.0 * std::f64::consts::PI / d as f64,
+    }
+}
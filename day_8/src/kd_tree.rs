@@ -0,0 +1,250 @@
+// KD-tree over the junction box positions
+//
+// The tree is stored implicitly in a permutation of the point indices: the
+// median point of a range (split on axis `depth % D`) sits in the middle of
+// the range, with the points left of it on that axis before it and the
+// points right of it after it. Both halves are again laid out the same way.
+//
+// `edge_stream` yields all pairs in ascending order from a cursor over the
+// nearest neighbors of every point, fetched with tree queries of doubling
+// size, merged by a heap. Neighbors at equal distance are ordered by index,
+// which makes the result match the pair order of the brute-force algorithm.
+// `KdTreeAlgorithm` takes the first k pairs of the stream, so it only queries
+// about as many neighbors per point as the k closest pairs need.
+
+use crate::{Distance, KClosestNeighbor, Metric, Position, SquaredEuclidean};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// A static KD-tree over a slice of positions, answering nearest neighbor
+/// queries by the distance `M`.
+#[derive(Debug)]
+pub struct KdTree<'a, const D: usize = 3, M = SquaredEuclidean> {
+    positions: &'a [Position<D>],
+    order: Vec<usize>,
+    metric: M,
+}
+
+impl<'a, const D: usize> KdTree<'a, D> {
+    /// Build the tree for squared Euclidean distances in O(n log n) time.
+    pub fn new(positions: &'a [Position<D>]) -> Self {
+        KdTree::with_metric(positions, SquaredEuclidean)
+    }
+}
+
+impl<'a, const D: usize, M: Metric> KdTree<'a, D, M> {
+    /// Build the tree for distances by `metric` in O(n log n) time.
+    pub fn with_metric(positions: &'a [Position<D>], metric: M) -> Self {
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        build(positions, &mut order, 0);
+
+        KdTree {
+            positions,
+            order,
+            metric,
+        }
+    }
+
+    /// The `k` points nearest to `query` as `(distance, index)` pairs, nearest
+    /// first, with ties ordered by index. The point with index `exclude`, if
+    /// any, is skipped.
+    pub fn nearest(
+        &self,
+        query: &Position<D>,
+        k: usize,
+        exclude: Option<usize>,
+    ) -> Vec<(Distance, usize)> {
+        let mut best = BinaryHeap::with_capacity(k.min(self.order.len()));
+        if k > 0 {
+            self.search(query, k, exclude, 0, self.order.len(), 0, &mut best);
+        }
+
+        best.into_sorted_vec()
+    }
+
+    /// Search the subtree `order[lo..hi]`, split on axis `depth % D`, keeping
+    /// the `k` best candidates in the max-heap `best`.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        query: &Position<D>,
+        k: usize,
+        exclude: Option<usize>,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut BinaryHeap<(Distance, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let index = self.order[mid];
+        let point = &self.positions[index];
+
+        if exclude != Some(index) {
+            best.push((self.metric.distance(query, point), index));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let axis = depth % D;
+        let (near, far) = if query.coordinate(axis) < point.coordinate(axis) {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.search(query, k, exclude, near.0, near.1, depth + 1, best);
+
+        // The far side can only hold better points if the splitting plane is
+        // no farther away than the worst candidate so far.
+        let plane = query.coordinate(axis).abs_diff(point.coordinate(axis));
+        if best.len() < k
+            || best
+                .peek()
+                .is_some_and(|&(worst, _)| self.metric.axis_distance(plane) <= worst)
+        {
+            self.search(query, k, exclude, far.0, far.1, depth + 1, best);
+        }
+    }
+}
+
+/// Lay out `order` as a KD-tree split on axis `depth % D`.
+fn build<const D: usize>(positions: &[Position<D>], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+
+    let axis = depth % D;
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by_key(mid, |&i| positions[i].coordinate(axis));
+
+    let (left, right) = order.split_at_mut(mid);
+    build(positions, left, depth + 1);
+    build(positions, &mut right[1..], depth + 1);
+}
+
+/// Finds the k closest pairs as the first k pairs of [`edge_stream_by`],
+/// querying a KD-tree for the nearest neighbors of every point instead of
+/// enumerating all O(n²) pairs.
+pub struct KdTreeAlgorithm;
+
+impl KClosestNeighbor for KdTreeAlgorithm {
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        edge_stream_by(positions, metric).take(k).collect()
+    }
+}
+
+/// The `k` boxes of `positions` closest to `query` as `(distance, index)`
+/// pairs, nearest first, with ties ordered by index.
+///
+/// Builds a KD-tree for the single query; keep a [`KdTree`] around to answer
+/// many queries on the same positions.
+pub fn k_nearest<const D: usize>(
+    positions: &[Position<D>],
+    query: &Position<D>,
+    k: usize,
+) -> Vec<(Distance, usize)> {
+    k_nearest_by(positions, query, k, SquaredEuclidean)
+}
+
+/// [`k_nearest`] with distances measured by `metric`.
+pub fn k_nearest_by<const D: usize>(
+    positions: &[Position<D>],
+    query: &Position<D>,
+    k: usize,
+    metric: impl Metric,
+) -> Vec<(Distance, usize)> {
+    KdTree::with_metric(positions, metric).nearest(query, k, None)
+}
+
+/// All pairs `(distance, i, j)` with `i < j` of `positions` in ascending
+/// order, computed lazily.
+///
+/// Every point keeps a cursor over its neighbors, found with KD-tree queries
+/// of doubling size, and a heap merges the cursors. Taking the first `m`
+/// pairs only computes neighbors up to about the distance of the `m`-th
+/// pair, so callers can stop early without ever looking at distant pairs.
+/// Pairs at equal distance come in index order, as with
+/// [`crate::BruteForceAlgorithm`].
+pub fn edge_stream<const D: usize>(
+    positions: &[Position<D>],
+) -> impl Iterator<Item = (Distance, usize, usize)> + '_ {
+    edge_stream_by(positions, SquaredEuclidean)
+}
+
+/// [`edge_stream`] with distances measured by `metric`.
+pub fn edge_stream_by<const D: usize, M: Metric>(
+    positions: &[Position<D>],
+    metric: M,
+) -> impl Iterator<Item = (Distance, usize, usize)> + use<'_, D, M> {
+    let mut stream = EdgeStream {
+        tree: KdTree::with_metric(positions, metric),
+        cursors: (0..positions.len()).map(|_| Cursor::default()).collect(),
+        heads: BinaryHeap::new(),
+    };
+
+    for i in 0..positions.len() {
+        stream.advance(i);
+    }
+
+    stream
+}
+
+/// The neighbors of one point fetched so far, and how many were used.
+#[derive(Debug, Default)]
+struct Cursor {
+    fetched: Vec<(Distance, usize)>,
+    used: usize,
+}
+
+/// The lazy iterator behind [`edge_stream`].
+struct EdgeStream<'a, const D: usize, M> {
+    tree: KdTree<'a, D, M>,
+    cursors: Vec<Cursor>,
+    /// The next pair `(distance, i, j)` of every point `i` that has one left.
+    heads: BinaryHeap<Reverse<(Distance, usize, usize)>>,
+}
+
+impl<const D: usize, M: Metric> EdgeStream<'_, D, M> {
+    /// Push the next pair `(distance, i, j)` with `j > i` of point `i` onto
+    /// the heads, if any. Pairs with `j < i` belong to the cursor of `j`.
+    fn advance(&mut self, i: usize) {
+        let others = self.cursors.len() - 1;
+        let cursor = &mut self.cursors[i];
+
+        loop {
+            if let Some(&(distance, j)) = cursor.fetched.get(cursor.used) {
+                cursor.used += 1;
+                if j > i {
+                    self.heads.push(Reverse((distance, i, j)));
+                    return;
+                }
+            } else if cursor.fetched.len() == others {
+                return;
+            } else {
+                let count = (2 * cursor.fetched.len()).max(4).min(others);
+                cursor.fetched = self.tree.nearest(&self.tree.positions[i], count, Some(i));
+            }
+        }
+    }
+}
+
+impl<const D: usize, M: Metric> Iterator for EdgeStream<'_, D, M> {
+    type Item = (Distance, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(pair) = self.heads.pop()?;
+        self.advance(pair.1);
+
+        Some(pair)
+    }
+}
@@ -1,215 +1,1214 @@
-///! Advent of Code Day 8 – Playground
+//! Advent of Code Day 8 – Playground
+use aoc_core::{index_pairs, parse_coordinates};
+use std::collections::{BinaryHeap, HashSet};
+use std::io::{self, BufRead};
 use std::str::FromStr;
+use std::{error, fmt};
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub use crate::approx::{ApproxAlgorithm, estimate_recall};
+#[cfg(feature = "serde")]
+pub use crate::export::{
+    CircuitExport, ExportedBox, ExportedEdge, export_circuits, export_circuits_nd,
+};
+pub use crate::grid_hash::GridHashAlgorithm;
+pub use crate::kd_tree::{
+    KdTree, KdTreeAlgorithm, edge_stream, edge_stream_by, k_nearest, k_nearest_by,
+};
+pub use crate::metric::{Chebyshev, Distance, Manhattan, Metric, SquaredEuclidean};
+pub use crate::vp_tree::VpTreeAlgorithm;
+pub use aoc_core::union_find::UnionFind;
+
+mod approx;
+#[cfg(feature = "serde")]
+mod export;
+mod grid_hash;
+mod kd_tree;
+mod metric;
+mod vp_tree;
+
+/// Errors that can occur while parsing or solving the Day 8 puzzle.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Day8Error {
+    /// A line is not a valid position.
+    InvalidLine {
+        /// The 1-based line number in the input.
+        line: usize,
+        /// The offending line.
+        text: String,
+        /// Why the line is invalid.
+        reason: &'static str,
+    },
+    /// Part 1 needs at least three circuits to multiply.
+    TooFewCircuits {
+        /// The number of circuits after connecting the edges.
+        found: usize,
+    },
+    /// All edges were connected without joining all points in one circuit,
+    /// which happens for fewer than two points.
+    NotConnected,
+    /// The part 2 product of two X-coordinates does not fit in a `u64`.
+    Overflow,
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+    /// The input holds the same position more than once, which is rejected
+    /// with [`DuplicatePolicy::Error`].
+    DuplicatePositions {
+        /// The number of positions that repeat an earlier one.
+        count: usize,
+        /// The 1-based line number of the first repeated position.
+        line: usize,
+    },
+}
+
+impl fmt::Display for Day8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Day8Error::InvalidLine { line, text, reason } => {
+                write!(f, "line {line}: invalid position {text:?}: {reason}")
+            }
+            Day8Error::TooFewCircuits { found } => {
+                write!(f, "part 1 needs three circuits, found {found}")
+            }
+            Day8Error::NotConnected => write!(f, "the junction boxes never form one circuit"),
+            Day8Error::Overflow => write!(f, "the product of the X-coordinates overflows"),
+            Day8Error::Io(kind) => write!(f, "could not read the input: {kind}"),
+            Day8Error::DuplicatePositions { count, line } => {
+                write!(f, "line {line}: repeated position, {count} in total")
+            }
+        }
+    }
+}
+
+impl error::Error for Day8Error {}
+
+/// The position of a junction box in `D` dimensions, `X,Y,Z` by default.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-struct Position(u64, u64, u64);
+pub struct Position<const D: usize = 3>(pub [u64; D]);
 
-impl Position {
-    /// Squared Euclidean distance between two 3D points.
+impl<const D: usize> Position<D> {
+    /// The coordinate along `axis`: `0` for X, `1` for Y, `2` for Z and so on.
+    pub fn coordinate(&self, axis: usize) -> u64 {
+        self.0[axis]
+    }
+
+    /// Squared Euclidean distance between two points.
     ///
     /// We deliberately avoid `sqrt` so we can stay in integer space and keep
-    /// comparisons exact and fast.
-    fn distance(&self, other: &Position) -> u64 {
-        let distance_x = self.0.abs_diff(other.0);
-        let distance_y = self.1.abs_diff(other.1);
-        let distance_z = self.2.abs_diff(other.2);
-
-        distance_x * distance_x + distance_y * distance_y + distance_z * distance_z
+    /// comparisons exact and fast. The squares are summed as [`Distance`],
+    /// which is exact for coordinates below `2^63` in three dimensions, and
+    /// saturates beyond.
+    pub fn distance(&self, other: &Position<D>) -> Distance {
+        self.0.iter().zip(&other.0).fold(0, |sum, (a, b)| {
+            let distance = Distance::from(a.abs_diff(*b));
+            sum.saturating_add(distance * distance)
+        })
     }
 }
 
-impl FromStr for Position {
+impl<const D: usize> FromStr for Position<D> {
     type Err = &'static str;
 
+    /// Parse `D` comma-separated coordinates.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(',');
-        let x = parts
-            .next()
-            .ok_or("Missing X")?
-            .parse()
-            .map_err(|_| "Invalid X")?;
-        let y = parts
-            .next()
-            .ok_or("Missing Y")?
-            .parse()
-            .map_err(|_| "Invalid Y")?;
-        let z = parts
-            .next()
-            .ok_or("Missing Z")?
-            .parse()
-            .map_err(|_| "Invalid Z")?;
-        Ok(Position(x, y, z))
+        parse_coordinates(s).map(Position)
     }
 }
 
+/// What happens to positions that occur more than once in the input.
+///
+/// Identical positions are at distance zero, so every copy takes one of the
+/// `k` closest edges without connecting anything new.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keep every copy, like the puzzle does.
+    #[default]
+    Keep,
+    /// Keep only the first copy. Later positions move up in the indices.
+    Dedup,
+    /// Fail with [`Day8Error::DuplicatePositions`].
+    Error,
+}
+
+/// Apply `policy` to the parsed `positions`, and return how many of them
+/// repeat an earlier position.
+pub fn apply_duplicate_policy<const D: usize>(
+    positions: &mut Vec<Position<D>>,
+    policy: DuplicatePolicy,
+) -> Result<usize, Day8Error> {
+    let mut seen = HashSet::with_capacity(positions.len());
+    let first: Vec<bool> = positions.iter().map(|p| seen.insert(p.0)).collect();
+
+    let count = first.iter().filter(|&&first| !first).count();
+    match policy {
+        DuplicatePolicy::Keep => {}
+        DuplicatePolicy::Dedup => {
+            let mut first = first.into_iter();
+            positions.retain(|_| first.next().unwrap_or(true));
+        }
+        DuplicatePolicy::Error => {
+            if let Some(index) = first.iter().position(|&first| !first) {
+                return Err(Day8Error::DuplicatePositions {
+                    count,
+                    line: index + 1,
+                });
+            }
+        }
+    }
+
+    Ok(count)
+}
+
 /// Strategy trait for producing the `k` closest edges between points.
 ///
 /// Returns a vector of triples: `(distance, i, j)` where `i < j` are indices
-/// into `positions`, and `distance` is the squared Euclidean distance.
-trait KClosestNeighbor {
-    fn closest_neighbors(&self, positions: &[Position], k: usize) -> Vec<(u64, usize, usize)>;
+/// into `positions`, and `distance` is the distance by the [`Metric`],
+/// squared Euclidean unless given otherwise.
+pub trait KClosestNeighbor {
+    fn closest_neighbors<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+    ) -> Vec<(Distance, usize, usize)> {
+        self.closest_neighbors_by(positions, k, SquaredEuclidean)
+    }
+
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)>;
 }
 
 /// Simple brute-force algorithm that enumerates all O(n^2) pairs, sorts them,
 /// and returns the first `k` pairs.
-struct BruteForceAlgorithm;
+pub struct BruteForceAlgorithm;
 
 impl KClosestNeighbor for BruteForceAlgorithm {
-    fn closest_neighbors(&self, positions: &[Position], k: usize) -> Vec<(u64, usize, usize)> {
-        let mut distances = Vec::new();
-
-        for (first_index, point) in positions.iter().enumerate() {
-            for (other_index, other_point) in positions.iter().enumerate().skip(first_index + 1) {
-                distances.push((point.distance(other_point), first_index, other_index));
-            }
-        }
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        let mut distances: Vec<(Distance, usize, usize)> = index_pairs(positions.len())
+            .map(|(i, j)| (metric.distance(&positions[i], &positions[j]), i, j))
+            .collect();
 
-        distances.sort_by(|a, b| a.0.cmp(&b.0));
+        distances.sort_by_key(|&(distance, _, _)| distance);
 
         distances.into_iter().take(k).collect()
     }
 }
 
-/// Parse the input where each line is `X,Y,Z`.
-fn parse_input(input: &str) -> Vec<Position> {
-    input
-        .lines()
-        .map(|line| Position::from_str(line).unwrap())
-        .collect()
-}
-
-/// Union-Find (Disjoint Set Union) to maintain circuits (connected components).
-struct UnionFind {
-    parent: Vec<usize>,
-    size: Vec<usize>,
-}
+/// Brute-force enumeration of all O(n^2) pairs like [`BruteForceAlgorithm`],
+/// but keeping only the `k` best pairs in a bounded max-heap. Needs O(k)
+/// memory instead of O(n^2), and O(n^2 log k) time instead of sorting all
+/// pairs. With the `parallel` feature the rows of pairs are spread over the
+/// rayon thread pool, each worker keeping its own heap.
+pub struct HeapAlgorithm;
 
-impl UnionFind {
-    fn new(n: usize) -> Self {
-        UnionFind {
-            parent: (0..n).collect(),
-            size: vec![1; n],
+impl KClosestNeighbor for HeapAlgorithm {
+    #[cfg(not(feature = "parallel"))]
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        let mut best = BinaryHeap::new();
+        for i in 0..positions.len() {
+            push_pairs(&mut best, positions, i, k, metric);
         }
+
+        best.into_sorted_vec()
     }
 
-    /// Find the representative (root) of the set containing 'i'
-    fn find(&mut self, i: usize) -> usize {
-        if self.parent[i] == i {
-            return i;
-        }
-        // Recursively find the root and update the parent pointer
-        // to point directly to the root (flattening the tree).
-        let root = self.find(self.parent[i]);
-        self.parent[i] = root;
-        root
+    #[cfg(feature = "parallel")]
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        (0..positions.len())
+            .into_par_iter()
+            .fold(BinaryHeap::new, |mut best, i| {
+                push_pairs(&mut best, positions, i, k, metric);
+                best
+            })
+            .reduce(BinaryHeap::new, |mut best, other| {
+                for pair in other {
+                    push_pair(&mut best, pair, k);
+                }
+                best
+            })
+            .into_sorted_vec()
     }
+}
 
-    /// Unite the sets containing 'i' and 'j'
-    fn union(&mut self, i: usize, j: usize) {
-        let root_i = self.find(i);
-        let root_j = self.find(j);
+/// Picks an algorithm from the number of points `n` and pairs `k`, following
+/// the crossover points measured with `cargo bench -p day_8`:
+///
+/// - [`HeapAlgorithm`] for small clouds, where building a tree or grid does not
+///   pay off,
+/// - [`GridHashAlgorithm`] when `k` is large compared to `n`, where the grid
+///   finds many pairs per cell,
+/// - [`KdTreeAlgorithm`] otherwise.
+pub struct AutoAlgorithm;
 
-        // If they are already in the same circuit, do nothing
-        if root_i == root_j {
-            return;
-        }
+impl AutoAlgorithm {
+    /// Below this many points the heap algorithm is fastest.
+    const SMALL_CLOUD: usize = 200;
+    /// From this many pairs per point on the grid is faster than the tree.
+    const PAIRS_PER_POINT: usize = 8;
+}
 
-        // Merge smaller set into larger set
-        if self.size[root_i] < self.size[root_j] {
-            self.parent[root_i] = root_j;
-            self.size[root_j] += self.size[root_i];
+impl KClosestNeighbor for AutoAlgorithm {
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        let n = positions.len();
+
+        if n < Self::SMALL_CLOUD {
+            HeapAlgorithm.closest_neighbors_by(positions, k, metric)
+        } else if k >= Self::PAIRS_PER_POINT.saturating_mul(n) {
+            GridHashAlgorithm.closest_neighbors_by(positions, k, metric)
         } else {
-            self.parent[root_j] = root_i;
-            self.size[root_i] += self.size[root_j];
+            KdTreeAlgorithm.closest_neighbors_by(positions, k, metric)
         }
     }
+}
 
-    /// Helper to get all circuit sizes for the final answer
-    fn get_all_circuit_sizes(&mut self) -> Vec<usize> {
-        let mut sizes = Vec::new();
-        for i in 0..self.parent.len() {
-            // Only collect sizes from the *roots* of the circuits
-            // We use self.parent[i] here directly to check if it's a root
-            if self.parent[i] == i {
-                sizes.push(self.size[i]);
-            }
-        }
-        sizes
+/// Add the pairs `(distance, i, j)` with `j > i` to the max-heap `best` of
+/// the `k` best pairs so far.
+fn push_pairs<const D: usize>(
+    best: &mut BinaryHeap<(Distance, usize, usize)>,
+    positions: &[Position<D>],
+    i: usize,
+    k: usize,
+    metric: impl Metric,
+) {
+    for j in i + 1..positions.len() {
+        push_pair(
+            best,
+            (metric.distance(&positions[i], &positions[j]), i, j),
+            k,
+        );
     }
+}
 
-    /// Helper to check if all points are connected
-    fn all_connected(&mut self) -> bool {
-        let root = self.find(0);
-        self.size[root] == self.parent.len()
+/// Add `pair` to the max-heap `best` if it is among the `k` best pairs so
+/// far. Pairs at equal distance are ordered by index, like the stable sort of
+/// [`BruteForceAlgorithm`].
+fn push_pair(
+    best: &mut BinaryHeap<(Distance, usize, usize)>,
+    pair: (Distance, usize, usize),
+    k: usize,
+) {
+    if best.len() < k {
+        best.push(pair);
+    } else if best.peek().is_some_and(|worst| pair < *worst) {
+        best.pop();
+        best.push(pair);
     }
 }
 
+/// Parse the input where each line holds the `D` coordinates of a position,
+/// like `X,Y,Z`.
+pub fn parse_input<const D: usize>(input: &str) -> Result<Vec<Position<D>>, Day8Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_line(index + 1, line))
+        .collect()
+}
+
+/// Like [`parse_input`], but reading the positions line by line from
+/// `reader`. Every position is yielded as soon as its line is read, so only
+/// the current line is kept in memory.
+pub fn parse_reader<const D: usize, R: BufRead>(
+    reader: R,
+) -> impl Iterator<Item = Result<Position<D>, Day8Error>> {
+    reader.lines().enumerate().map(|(index, line)| {
+        let line = line.map_err(|err| Day8Error::Io(err.kind()))?;
+        parse_line(index + 1, &line)
+    })
+}
+
+/// Parse the line with 1-based number `number`.
+fn parse_line<const D: usize>(number: usize, line: &str) -> Result<Position<D>, Day8Error> {
+    Position::from_str(line).map_err(|reason| Day8Error::InvalidLine {
+        line: number,
+        text: line.to_string(),
+        reason,
+    })
+}
+
 /// Part 1: connect the `n` shortest edges and return the product of the sizes
 /// of the three largest resulting components.
-fn solution_part_1(input: &str, algorithm: impl KClosestNeighbor, n: usize) -> usize {
-    let numbers = parse_input(input);
-    let closest_neighbors = algorithm.closest_neighbors(&numbers, n);
-    let mut uf = UnionFind::new(numbers.len());
+pub fn solution_part_1(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+) -> Result<usize, Day8Error> {
+    solution_part_1_nd::<3>(input, algorithm, n, SquaredEuclidean, DuplicatePolicy::Keep)
+}
 
-    for (_, i, j) in closest_neighbors {
-        uf.union(i, j);
-    }
+/// Part 1 for positions with `D` coordinates, with distances measured by
+/// `metric` and repeated positions handled by `duplicates`.
+pub fn solution_part_1_nd<const D: usize>(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+    metric: impl Metric,
+    duplicates: DuplicatePolicy,
+) -> Result<usize, Day8Error> {
+    let mut numbers = parse_input::<D>(input)?;
+    apply_duplicate_policy(&mut numbers, duplicates)?;
+
+    part_1(&numbers, algorithm, n, metric)
+}
+
+/// Solve part 1 while reading the positions line by line from `reader`,
+/// without keeping the text of the input in memory.
+pub fn solution_part_1_from_reader<R: BufRead>(
+    reader: R,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+) -> Result<usize, Day8Error> {
+    let numbers = parse_reader::<3, R>(reader).collect::<Result<Vec<_>, _>>()?;
+
+    part_1(&numbers, algorithm, n, SquaredEuclidean)
+}
+
+/// The product of the sizes of the three largest circuits after connecting
+/// the `n` shortest edges between `numbers`.
+fn part_1<const D: usize>(
+    numbers: &[Position<D>],
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+    metric: impl Metric,
+) -> Result<usize, Day8Error> {
+    let pairs = algorithm.closest_neighbors_by(numbers, n, metric);
+
+    solution_part_1_with_pairs(numbers, &pairs)
+}
 
-    let mut circuit_sizes = uf.get_all_circuit_sizes();
+/// Part 1 with the pairs to connect already computed, e.g. by
+/// [`KClosestNeighbor::closest_neighbors`], so that the caller can inspect
+/// them without running the algorithm twice. The pair indices must be
+/// indices of `numbers`.
+pub fn solution_part_1_with_pairs<const D: usize>(
+    numbers: &[Position<D>],
+    pairs: &[(Distance, usize, usize)],
+) -> Result<usize, Day8Error> {
+    let mut circuit_sizes = connect_pairs(numbers.len(), pairs).circuit_sizes();
     circuit_sizes.sort();
     circuit_sizes.reverse();
 
-    circuit_sizes[0] * circuit_sizes[1] * circuit_sizes[2]
+    match circuit_sizes[..] {
+        [first, second, third, ..] => Ok(first * second * third),
+        _ => Err(Day8Error::TooFewCircuits {
+            found: circuit_sizes.len(),
+        }),
+    }
 }
 
-/// Part 2: iterate edges in ascending order of distance and return as soon as
-/// the graph becomes fully connected. The return value is the product of the
-/// X-coordinates of the two points that were connected last.
-///
-/// The parameter `n` should be large enough to exceed the number of edges
-/// needed to achieve full connectivity.
-fn solution_part_2(
+/// The circuits after connecting the `n` shortest edges, as in part 1: the
+/// indices of the junction boxes of every circuit in ascending order, largest
+/// circuits first and circuits of equal size ordered by their first box.
+pub fn circuits(
     input: &str,
     algorithm: impl KClosestNeighbor,
     n: usize,
-) -> Result<u64, &'static str> {
-    let numbers = parse_input(input);
-    let closest_neighbors = algorithm.closest_neighbors(&numbers, n);
+) -> Result<Vec<Vec<usize>>, Day8Error> {
+    circuits_nd::<3>(input, algorithm, n, SquaredEuclidean, DuplicatePolicy::Keep)
+}
+
+/// [`circuits`] for positions with `D` coordinates, with distances measured
+/// by `metric` and repeated positions handled by `duplicates`. With
+/// [`DuplicatePolicy::Dedup`] the indices refer to the remaining positions.
+pub fn circuits_nd<const D: usize>(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+    metric: impl Metric,
+    duplicates: DuplicatePolicy,
+) -> Result<Vec<Vec<usize>>, Day8Error> {
+    let mut numbers = parse_input::<D>(input)?;
+    apply_duplicate_policy(&mut numbers, duplicates)?;
+    let mut circuits = connect_closest(&numbers, algorithm, n, metric).components();
+    circuits.sort_by_key(|circuit| std::cmp::Reverse(circuit.len()));
+
+    Ok(circuits)
+}
+
+/// Connect the `n` shortest edges between `numbers`.
+fn connect_closest<const D: usize>(
+    numbers: &[Position<D>],
+    algorithm: impl KClosestNeighbor,
+    n: usize,
+    metric: impl Metric,
+) -> UnionFind {
+    let closest_neighbors = algorithm.closest_neighbors_by(numbers, n, metric);
+
+    connect_pairs(numbers.len(), &closest_neighbors)
+}
+
+/// Connect `pairs` between `len` junction boxes.
+fn connect_pairs(len: usize, pairs: &[(Distance, usize, usize)]) -> UnionFind {
+    let mut uf = UnionFind::new(len);
+
+    for &(_, i, j) in pairs {
+        uf.union(i, j);
+    }
+
+    uf
+}
+
+/// Part 2: iterate edges in ascending order of distance (Kruskal's
+/// algorithm) and return as soon as the graph becomes fully connected. The
+/// return value is the product of the X-coordinates of the two points that
+/// were connected last.
+///
+/// The edges are requested from `algorithm` in batches of doubling size, so
+/// only about as many edges are computed as the connection needs.
+pub fn solution_part_2(input: &str, algorithm: impl KClosestNeighbor) -> Result<u64, Day8Error> {
+    solution_part_2_nd::<3>(input, algorithm, SquaredEuclidean, DuplicatePolicy::Keep)
+}
+
+/// Part 2 for positions with `D` coordinates, with distances measured by
+/// `metric` and repeated positions handled by `duplicates`.
+pub fn solution_part_2_nd<const D: usize>(
+    input: &str,
+    algorithm: impl KClosestNeighbor,
+    metric: impl Metric,
+    duplicates: DuplicatePolicy,
+) -> Result<u64, Day8Error> {
+    let mut numbers = parse_input::<D>(input)?;
+    apply_duplicate_policy(&mut numbers, duplicates)?;
+
+    part_2(&numbers, algorithm, metric)
+}
+
+/// Solve part 2 while reading the positions line by line from `reader`,
+/// without keeping the text of the input in memory.
+pub fn solution_part_2_from_reader<R: BufRead>(
+    reader: R,
+    algorithm: impl KClosestNeighbor,
+) -> Result<u64, Day8Error> {
+    let numbers = parse_reader::<3, R>(reader).collect::<Result<Vec<_>, _>>()?;
+
+    part_2(&numbers, algorithm, SquaredEuclidean)
+}
+
+/// Connect the edges between `numbers` in batches from `algorithm` until all
+/// are connected.
+fn part_2<const D: usize>(
+    numbers: &[Position<D>],
+    algorithm: impl KClosestNeighbor,
+    metric: impl Metric,
+) -> Result<u64, Day8Error> {
+    let total_edges = numbers.len() * numbers.len().saturating_sub(1) / 2;
+
+    let mut processed = 0;
+    let mut batch = numbers.len();
+    let edges = std::iter::from_fn(|| {
+        if processed >= total_edges {
+            return None;
+        }
+
+        // The closest edges of a larger batch start with the smaller batch
+        batch = batch.saturating_mul(2).min(total_edges);
+        let closest_neighbors = algorithm.closest_neighbors_by(numbers, batch, metric);
+        let new_edges = closest_neighbors[processed..].to_vec();
+        processed = batch;
+
+        Some(new_edges)
+    })
+    .flatten();
+
+    connect_all(numbers, edges)
+}
+
+/// Part 2 on the lazily computed edges of [`edge_stream`], which stops
+/// computing edges as soon as all points are connected.
+pub fn solution_part_2_streaming(input: &str) -> Result<u64, Day8Error> {
+    let numbers = parse_input::<3>(input)?;
+
+    connect_all(&numbers, edge_stream(&numbers))
+}
+
+/// The circuits right after connecting one more edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitStep {
+    /// The distance between the two junction boxes of the edge.
+    pub distance: Distance,
+    /// The junction boxes of the edge, with `i < j`.
+    pub i: usize,
+    pub j: usize,
+    /// The number of circuits after connecting the edge.
+    pub circuits: usize,
+    /// The size of the largest circuit after connecting the edge.
+    pub largest: usize,
+}
+
+/// Connect the edges between `positions` in ascending order of distance and
+/// yield the circuits after every edge, which is the merge history of
+/// single-linkage clustering. Edges within one circuit leave the counts
+/// unchanged. Ends as soon as all junction boxes are in one circuit.
+pub fn circuit_evolution<const D: usize>(
+    positions: &[Position<D>],
+) -> impl Iterator<Item = CircuitStep> + '_ {
+    circuit_evolution_by(positions, SquaredEuclidean)
+}
+
+/// [`circuit_evolution`] with distances measured by `metric`.
+pub fn circuit_evolution_by<const D: usize, M: Metric>(
+    positions: &[Position<D>],
+    metric: M,
+) -> impl Iterator<Item = CircuitStep> + use<'_, D, M> {
+    let mut edges = edge_stream_by(positions, metric);
+    let mut uf = UnionFind::new(positions.len());
+    let mut largest = positions.len().min(1);
+
+    std::iter::from_fn(move || {
+        if uf.component_count() <= 1 {
+            return None;
+        }
+
+        let (distance, i, j) = edges.next()?;
+        uf.union(i, j);
+        largest = largest.max(uf.size_of(i));
+
+        Some(CircuitStep {
+            distance,
+            i,
+            j,
+            circuits: uf.component_count(),
+            largest,
+        })
+    })
+}
+
+/// Union the `edges` in order until all points are connected, and return the
+/// product of the X-coordinates of the last two points connected.
+fn connect_all<const D: usize>(
+    numbers: &[Position<D>],
+    edges: impl Iterator<Item = (Distance, usize, usize)>,
+) -> Result<u64, Day8Error> {
     let mut uf = UnionFind::new(numbers.len());
 
-    for (_, i, j) in closest_neighbors {
+    for (_, i, j) in edges {
         uf.union(i, j);
         if uf.all_connected() {
-            return Ok(numbers[i].0 * numbers[j].0);
+            return numbers[i]
+                .coordinate(0)
+                .checked_mul(numbers[j].coordinate(0))
+                .ok_or(Day8Error::Overflow);
         }
     }
 
-    Err("Could not connect all points")
+    Err(Day8Error::NotConnected)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::rng::TestRng;
 
     #[test]
     fn test_solution_part_1() {
         assert_eq!(
             solution_part_1(include_str!("sample_input.txt"), BruteForceAlgorithm, 10),
-            40
+            Ok(40)
+        );
+
+        let positions = parse_input::<3>(include_str!("sample_input.txt")).unwrap();
+        let pairs = BruteForceAlgorithm.closest_neighbors(&positions, 10);
+        assert_eq!(solution_part_1_with_pairs(&positions, &pairs), Ok(40));
+        assert_eq!(solution_part_1_with_pairs(&positions, &[]), Ok(1));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(
+            solution_part_1("1,2,3\n4,x,6\n", BruteForceAlgorithm, 1),
+            Err(Day8Error::InvalidLine {
+                line: 2,
+                text: "4,x,6".to_string(),
+                reason: "Invalid coordinate",
+            })
+        );
+        assert_eq!(
+            solution_part_1("1,2,3\n4,x,6\n", BruteForceAlgorithm, 1)
+                .unwrap_err()
+                .to_string(),
+            r#"line 2: invalid position "4,x,6": Invalid coordinate"#
+        );
+        assert_eq!(
+            solution_part_2("1,2,3\n\n", BruteForceAlgorithm),
+            Err(Day8Error::InvalidLine {
+                line: 2,
+                text: String::new(),
+                reason: "Invalid coordinate",
+            })
+        );
+        assert_eq!(
+            solution_part_1("1,2,3\n4,5,6\n7,8,9\n", BruteForceAlgorithm, 1),
+            Err(Day8Error::TooFewCircuits { found: 2 })
         );
     }
 
     #[test]
     fn test_solution_part_2() {
+        let input = include_str!("sample_input.txt");
+
+        assert_eq!(solution_part_2(input, BruteForceAlgorithm), Ok(25272));
+        assert_eq!(solution_part_2(input, HeapAlgorithm), Ok(25272));
+        assert_eq!(solution_part_2(input, GridHashAlgorithm), Ok(25272));
         assert_eq!(
-            // note that we use a magic number here to reuse part1, which
-            // could be considered as a dirty hack
-            solution_part_2(
-                include_str!("sample_input.txt"),
-                BruteForceAlgorithm,
-                10_000
+            solution_part_2("1,2,3\n", BruteForceAlgorithm),
+            Err(Day8Error::NotConnected)
+        );
+        assert_eq!(
+            solution_part_2("1,2,3\n4,5,6\n", BruteForceAlgorithm),
+            Ok(4)
+        );
+    }
+
+    /// `n` pseudo-random positions in a cube of side `size`, seeded with
+    /// `seed`.
+    fn random_positions(n: usize, size: u64, seed: u64) -> Vec<Position> {
+        random_positions_nd(n, size, seed)
+    }
+
+    /// Like [`random_positions`], in `D` dimensions.
+    fn random_positions_nd<const D: usize>(n: usize, size: u64, seed: u64) -> Vec<Position<D>> {
+        let mut rng = TestRng::new(seed);
+
+        (0..n)
+            .map(|_| Position(std::array::from_fn(|_| rng.below(size))))
+            .collect()
+    }
+
+    #[test]
+    fn test_kd_tree_sample() {
+        let input = include_str!("sample_input.txt");
+
+        assert_eq!(solution_part_1(input, KdTreeAlgorithm, 10), Ok(40));
+        assert_eq!(solution_part_2(input, KdTreeAlgorithm), Ok(25272));
+    }
+
+    #[test]
+    fn test_kd_tree_matches_brute_force() {
+        // Small cubes produce many ties and duplicate points
+        for (n, size, seed) in [
+            (0, 10, 1),
+            (1, 10, 2),
+            (50, 4, 3),
+            (200, 1000, 4),
+            (300, 20, 5),
+        ] {
+            let positions = random_positions(n, size, seed);
+            for k in [0, 1, 10, 100, 1000] {
+                assert_eq!(
+                    KdTreeAlgorithm.closest_neighbors(&positions, k),
+                    BruteForceAlgorithm.closest_neighbors(&positions, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_hash_matches_brute_force() {
+        let sample = parse_input::<3>(include_str!("sample_input.txt")).unwrap();
+        assert_eq!(
+            GridHashAlgorithm.closest_neighbors(&sample, 10),
+            BruteForceAlgorithm.closest_neighbors(&sample, 10)
+        );
+
+        for (n, size, seed) in [
+            (0, 10, 1),
+            (1, 10, 2),
+            (50, 4, 3),
+            (200, 1000, 4),
+            (300, 20, 5),
+        ] {
+            let positions = random_positions(n, size, seed);
+            for k in [0, 1, 10, 100, 1000, 100_000] {
+                assert_eq!(
+                    GridHashAlgorithm.closest_neighbors(&positions, k),
+                    BruteForceAlgorithm.closest_neighbors(&positions, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_grid_hash_clustered() {
+        // Two tight clusters far apart
+        let mut positions = random_positions(100, 10, 7);
+        positions.extend(
+            random_positions(100, 10, 8)
+                .into_iter()
+                .map(|Position([x, y, z])| Position([x + 1_000_000, y, z + 5_000])),
+        );
+
+        for k in [50, 5000, 15_000] {
+            assert_eq!(
+                GridHashAlgorithm.closest_neighbors(&positions, k),
+                BruteForceAlgorithm.closest_neighbors(&positions, k)
+            );
+        }
+    }
+
+    #[test]
+    fn test_heap_matches_brute_force() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(solution_part_1(input, HeapAlgorithm, 10), Ok(40));
+
+        for (n, size, seed) in [(0, 10, 1), (1, 10, 2), (50, 4, 3), (200, 1000, 4)] {
+            let positions = random_positions(n, size, seed);
+            for k in [0, 1, 10, 100, 100_000] {
+                assert_eq!(
+                    HeapAlgorithm.closest_neighbors(&positions, k),
+                    BruteForceAlgorithm.closest_neighbors(&positions, k)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_stream() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(solution_part_2_streaming(input), Ok(25272));
+
+        for (n, size, seed) in [
+            (0, 10, 1),
+            (1, 10, 2),
+            (2, 10, 3),
+            (50, 4, 4),
+            (150, 1000, 5),
+        ] {
+            let positions = random_positions(n, size, seed);
+
+            assert_eq!(
+                edge_stream(&positions).collect::<Vec<_>>(),
+                BruteForceAlgorithm.closest_neighbors(&positions, usize::MAX)
+            );
+        }
+    }
+
+    #[test]
+    fn test_other_dimensions() {
+        assert_eq!("1,2".parse(), Ok(Position([1, 2])));
+        assert_eq!("1,2,3,4".parse(), Ok(Position([1, 2, 3, 4])));
+        assert_eq!("1,2".parse::<Position>(), Err("Missing coordinate"));
+        assert_eq!("1,2,3".parse::<Position<2>>(), Err("Too many coordinates"));
+        assert_eq!(
+            solution_part_2_nd::<2>(
+                "1,2\n7,2\n3,2\n",
+                KdTreeAlgorithm,
+                SquaredEuclidean,
+                DuplicatePolicy::Keep
             ),
+            Ok(21)
+        );
+
+        let planar = random_positions_nd::<2>(200, 100, 1);
+        let spatial = random_positions_nd::<4>(200, 100, 2);
+        for k in [1, 100, 1000] {
+            let expected = BruteForceAlgorithm.closest_neighbors(&planar, k);
+            assert_eq!(KdTreeAlgorithm.closest_neighbors(&planar, k), expected);
+            assert_eq!(GridHashAlgorithm.closest_neighbors(&planar, k), expected);
+
+            let expected = BruteForceAlgorithm.closest_neighbors(&spatial, k);
+            assert_eq!(KdTreeAlgorithm.closest_neighbors(&spatial, k), expected);
+            assert_eq!(GridHashAlgorithm.closest_neighbors(&spatial, k), expected);
+        }
+    }
+
+    #[test]
+    fn test_metrics() {
+        let a = Position([1, 5, 2]);
+        let b = Position([4, 1, 2]);
+        assert_eq!(SquaredEuclidean.distance(&a, &b), 25);
+        assert_eq!(Manhattan.distance(&a, &b), 7);
+        assert_eq!(Chebyshev.distance(&a, &b), 4);
+
+        // Small cubes give many ties, in particular for Chebyshev
+        for (n, size, seed) in [(2, 10, 1), (60, 5, 2), (200, 1000, 3)] {
+            let positions = random_positions(n, size, seed);
+            for k in [1, 50, 1000] {
+                let expected = BruteForceAlgorithm.closest_neighbors_by(&positions, k, Manhattan);
+                assert_eq!(
+                    KdTreeAlgorithm.closest_neighbors_by(&positions, k, Manhattan),
+                    expected
+                );
+                assert_eq!(
+                    GridHashAlgorithm.closest_neighbors_by(&positions, k, Manhattan),
+                    expected
+                );
+                assert_eq!(
+                    HeapAlgorithm.closest_neighbors_by(&positions, k, Manhattan),
+                    expected
+                );
+
+                let expected = BruteForceAlgorithm.closest_neighbors_by(&positions, k, Chebyshev);
+                assert_eq!(
+                    KdTreeAlgorithm.closest_neighbors_by(&positions, k, Chebyshev),
+                    expected
+                );
+                assert_eq!(
+                    GridHashAlgorithm.closest_neighbors_by(&positions, k, Chebyshev),
+                    expected
+                );
+                assert_eq!(
+                    HeapAlgorithm.closest_neighbors_by(&positions, k, Chebyshev),
+                    expected
+                );
+            }
+
+            assert_eq!(
+                edge_stream_by(&positions, Chebyshev).collect::<Vec<_>>(),
+                BruteForceAlgorithm.closest_neighbors_by(&positions, usize::MAX, Chebyshev)
+            );
+        }
+
+        let input = include_str!("sample_input.txt");
+        assert_eq!(
+            solution_part_2_nd::<3>(input, KdTreeAlgorithm, Manhattan, DuplicatePolicy::Keep),
+            solution_part_2_nd::<3>(input, BruteForceAlgorithm, Manhattan, DuplicatePolicy::Keep)
+        );
+    }
+
+    #[test]
+    fn test_large_coordinates() {
+        let far = u64::MAX / 2;
+        let positions = [
+            Position([0, 0, 0]),
+            Position([far, far, far]),
+            Position([far, far, far - 3]),
+        ];
+        assert_eq!(
+            positions[0].distance(&positions[1]),
+            3 * Distance::from(far).pow(2)
+        );
+        assert_eq!(
+            Manhattan.distance(&positions[0], &positions[1]),
+            3 * Distance::from(far)
+        );
+        assert_eq!(
+            KdTreeAlgorithm.closest_neighbors(&positions, 3),
+            BruteForceAlgorithm.closest_neighbors(&positions, 3)
+        );
+        assert_eq!(
+            GridHashAlgorithm.closest_neighbors(&positions, 3),
+            BruteForceAlgorithm.closest_neighbors(&positions, 3)
+        );
+
+        assert_eq!(
+            Position([0, 0, 0]).distance(&Position([u64::MAX; 3])),
+            Distance::MAX
+        );
+
+        // Full-range coordinates: the cells grow up to `u64::MAX` wide
+        let corners = [Position([0; 3]), Position([u64::MAX; 3])];
+        assert_eq!(
+            GridHashAlgorithm.closest_neighbors(&corners, 1),
+            vec![(Distance::MAX, 0, 1)]
+        );
+        let corners = [
+            Position([u64::MAX; 3]),
+            Position([0; 3]),
+            Position([u64::MAX, 0, u64::MAX]),
+        ];
+        assert_eq!(
+            GridHashAlgorithm.closest_neighbors(&corners, 3),
+            BruteForceAlgorithm.closest_neighbors(&corners, 3)
+        );
+
+        let input = format!("3,0,0\n{far},{far},{far}\n5,1,1\n");
+        assert_eq!(
+            solution_part_2(&input, KdTreeAlgorithm),
+            Err(Day8Error::Overflow)
+        );
+        let input = "3000000000,0,0\n4000000000,0,0\n";
+        assert_eq!(
+            solution_part_2(input, KdTreeAlgorithm),
+            Ok(12_000_000_000_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_solve_from_reader() {
+        let input = include_str!("sample_input.txt");
+
+        assert_eq!(
+            solution_part_1_from_reader(input.as_bytes(), KdTreeAlgorithm, 10),
+            Ok(40)
+        );
+        assert_eq!(
+            solution_part_2_from_reader(input.as_bytes(), GridHashAlgorithm),
             Ok(25272)
         );
+        assert_eq!(
+            parse_reader::<3, _>("1,2,3\n4,5\n".as_bytes()).collect::<Vec<_>>(),
+            vec![
+                Ok(Position([1, 2, 3])),
+                Err(Day8Error::InvalidLine {
+                    line: 2,
+                    text: "4,5".to_string(),
+                    reason: "Missing coordinate",
+                })
+            ]
+        );
+        assert_eq!(
+            solution_part_2_from_reader(&[0xff, b'\n'][..], KdTreeAlgorithm),
+            Err(Day8Error::Io(io::ErrorKind::InvalidData))
+        );
+    }
+
+    #[test]
+    fn test_duplicate_policy() {
+        let input = "1,1,1\n5,5,5\n1,1,1\n9,9,9\n1,1,1\n20,20,20\n";
+        let parsed = parse_input::<3>(input).unwrap();
+
+        let mut kept = parse_input::<3>(input).unwrap();
+        assert_eq!(
+            apply_duplicate_policy(&mut kept, DuplicatePolicy::Keep),
+            Ok(2)
+        );
+        assert_eq!(kept, parsed);
+
+        let mut deduplicated = parse_input::<3>(input).unwrap();
+        assert_eq!(
+            apply_duplicate_policy(&mut deduplicated, DuplicatePolicy::Dedup),
+            Ok(2)
+        );
+        assert_eq!(
+            deduplicated,
+            [parsed[0].0, parsed[1].0, parsed[3].0, parsed[5].0].map(Position)
+        );
+
+        assert_eq!(
+            solution_part_1_nd::<3>(
+                input,
+                BruteForceAlgorithm,
+                2,
+                SquaredEuclidean,
+                DuplicatePolicy::Error
+            ),
+            Err(Day8Error::DuplicatePositions { count: 2, line: 3 })
+        );
+
+        // Kept, the two zero-length edges only join the copies, deduplicated
+        // they join three different boxes
+        let keep = solution_part_1_nd::<3>(
+            input,
+            BruteForceAlgorithm,
+            2,
+            SquaredEuclidean,
+            DuplicatePolicy::Keep,
+        );
+        let dedup = solution_part_1_nd::<3>(
+            input,
+            BruteForceAlgorithm,
+            2,
+            SquaredEuclidean,
+            DuplicatePolicy::Dedup,
+        );
+        assert_eq!(keep, Ok(3));
+        assert_eq!(dedup, Err(Day8Error::TooFewCircuits { found: 2 }));
+        assert_eq!(
+            circuits_nd::<3>(
+                input,
+                BruteForceAlgorithm,
+                2,
+                SquaredEuclidean,
+                DuplicatePolicy::Dedup
+            ),
+            Ok(vec![vec![0, 1, 2], vec![3]])
+        );
+    }
+
+    #[test]
+    fn test_circuits() {
+        let input = include_str!("sample_input.txt");
+        let circuits = circuits(input, KdTreeAlgorithm, 10).unwrap();
+
+        let sizes: Vec<usize> = circuits.iter().map(Vec::len).collect();
+        assert_eq!(sizes[..4], [5, 4, 2, 2]);
+        assert_eq!(sizes.iter().sum::<usize>(), 20);
+        assert_eq!(circuits[0], vec![2, 8, 13, 17, 18]);
+        assert_eq!(
+            Ok(circuits),
+            super::circuits(input, BruteForceAlgorithm, 10)
+        );
+
+        let mut uf = UnionFind::new(4);
+        uf.union(3, 1);
+        assert_eq!(uf.components(), vec![vec![0], vec![1, 3], vec![2]]);
+    }
+
+    #[test]
+    fn test_kd_tree_nearest() {
+        let positions = random_positions(100, 50, 6);
+        let tree = KdTree::new(&positions);
+        let query = Position([25, 25, 25]);
+
+        let mut expected: Vec<(Distance, usize)> = positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (query.distance(p), i))
+            .collect();
+        expected.sort();
+        expected.truncate(5);
+
+        assert_eq!(tree.nearest(&query, 5, None), expected);
+        assert_eq!(k_nearest(&positions, &query, 5), expected);
+        assert_eq!(k_nearest(&positions, &query, 0), vec![]);
+        assert_eq!(k_nearest(&positions, &query, 500).len(), 100);
+        assert_eq!(
+            k_nearest_by(&positions, &positions[3], 1, Manhattan),
+            vec![(0, 3)]
+        );
+    }
+
+    #[test]
+    fn test_auto_algorithm() {
+        // One cloud per branch: small, many pairs per point, few pairs per point
+        for (n, k) in [(50, 100), (300, 3000), (300, 30)] {
+            let positions = random_positions(n, 1000, 7);
+            assert_eq!(
+                AutoAlgorithm.closest_neighbors(&positions, k),
+                BruteForceAlgorithm.closest_neighbors(&positions, k)
+            );
+        }
+    }
+
+    #[test]
+    fn test_circuit_evolution() {
+        let positions = parse_input::<3>(include_str!("sample_input.txt")).unwrap();
+        let steps: Vec<CircuitStep> = circuit_evolution(&positions).collect();
+
+        assert_eq!(steps[0].circuits, 19);
+        assert_eq!((steps[9].circuits, steps[9].largest), (11, 5));
+
+        let last = steps.last().unwrap();
+        assert_eq!((last.circuits, last.largest), (1, 20));
+        assert_eq!(
+            positions[last.i].coordinate(0) * positions[last.j].coordinate(0),
+            25272
+        );
+        assert!(steps.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+        assert_eq!(circuit_evolution::<3>(&[]).count(), 0);
+        assert_eq!(circuit_evolution(&positions[..1]).count(), 0);
+        assert_eq!(
+            circuit_evolution_by(&positions, Manhattan)
+                .last()
+                .unwrap()
+                .circuits,
+            1
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_export_circuits() {
+        let input = include_str!("sample_input.txt");
+        let export = export_circuits(input, KdTreeAlgorithm, 10).unwrap();
+
+        assert_eq!(export.edges.len(), 10);
+        assert_eq!(export.boxes.len(), 20);
+        assert_eq!(export.boxes[0].coordinates, vec![162, 817, 812]);
+        let circuits = circuits(input, KdTreeAlgorithm, 10).unwrap();
+        for (id, circuit) in circuits.iter().enumerate() {
+            assert!(circuit.iter().all(|&i| export.boxes[i].component == id));
+        }
+
+        let json = CircuitExport::new(&[Position([1, 2]), Position([4, 6])], &[(25, 0, 1)])
+            .to_json()
+            .unwrap();
+        assert_eq!(
+            json,
+            r#"{"boxes":[{"index":0,"coordinates":[1,2],"component":0},{"index":1,"coordinates":[4,6],"component":0}],"edges":[{"distance":25,"i":0,"j":1}]}"#
+        );
+    }
+
+    #[test]
+    fn test_vp_tree() {
+        assert_eq!(SquaredEuclidean.triangle_bounds(25), (5, 5));
+        assert_eq!(SquaredEuclidean.triangle_bounds(26), (5, 6));
+        assert_eq!(Manhattan.triangle_bounds(26), (26, 26));
+
+        let input = include_str!("sample_input.txt");
+        assert_eq!(solution_part_1(input, VpTreeAlgorithm, 10), Ok(40));
+        assert_eq!(solution_part_2(input, VpTreeAlgorithm), Ok(25272));
+
+        // Small cubes give many ties and repeated positions
+        for (n, size, seed) in [(2, 10, 1), (60, 5, 2), (200, 1000, 3)] {
+            let positions = random_positions(n, size, seed);
+            for k in [0, 1, 50, 1000] {
+                assert_eq!(
+                    VpTreeAlgorithm.closest_neighbors(&positions, k),
+                    BruteForceAlgorithm.closest_neighbors(&positions, k)
+                );
+                assert_eq!(
+                    VpTreeAlgorithm.closest_neighbors_by(&positions, k, Manhattan),
+                    BruteForceAlgorithm.closest_neighbors_by(&positions, k, Manhattan)
+                );
+                assert_eq!(
+                    VpTreeAlgorithm.closest_neighbors_by(&positions, k, Chebyshev),
+                    BruteForceAlgorithm.closest_neighbors_by(&positions, k, Chebyshev)
+                );
+            }
+        }
+
+        let spatial = random_positions_nd::<4>(200, 100, 4);
+        assert_eq!(
+            VpTreeAlgorithm.closest_neighbors(&spatial, 300),
+            BruteForceAlgorithm.closest_neighbors(&spatial, 300)
+        );
+    }
+
+    #[test]
+    fn test_approx_algorithm() {
+        let positions = random_positions(300, 1000, 8);
+        let exact = ApproxAlgorithm {
+            window: positions.len(),
+            ..Default::default()
+        };
+        for k in [0, 1, 300, 100_000] {
+            assert_eq!(
+                exact.closest_neighbors(&positions, k),
+                BruteForceAlgorithm.closest_neighbors(&positions, k)
+            );
+        }
+
+        let expected = KdTreeAlgorithm.closest_neighbors(&positions, 300);
+        assert_eq!(
+            estimate_recall(&positions, &expected, 50, SquaredEuclidean),
+            1.0
+        );
+
+        let positions = random_positions(5000, 100_000, 9);
+        let pairs = ApproxAlgorithm::default().closest_neighbors(&positions, 5000);
+        let expected: HashSet<_> = KdTreeAlgorithm
+            .closest_neighbors(&positions, 5000)
+            .into_iter()
+            .collect();
+        let recall = pairs.iter().filter(|pair| expected.contains(pair)).count();
+        assert!(recall >= 4950);
+        assert!(estimate_recall(&positions, &pairs, 100, SquaredEuclidean) >= 0.99);
     }
 }
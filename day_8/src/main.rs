@@ -0,0 +1,65 @@
+//! Command line interface for Advent of Code Day 8 — Playground
+//!
+//! Usage: `day_8 [--approx] <input file>`
+//!
+//! Prints the answers to both parts. With `--approx`, part 1 connects the
+//! approximate closest pairs of [`ApproxAlgorithm`] for inputs too large for
+//! the exact algorithms, and prints their recall estimated on a sample of the
+//! boxes. Part 2 always streams the exact edges.
+
+use day_8::{
+    ApproxAlgorithm, AutoAlgorithm, KClosestNeighbor, SquaredEuclidean, estimate_recall,
+    parse_input, solution_part_1, solution_part_1_with_pairs, solution_part_2_streaming,
+};
+use std::{env, fs, process};
+
+/// The number of closest pairs connected in part 1 of the puzzle.
+const PART_1_PAIRS: usize = 1000;
+
+/// The number of boxes checked against exact distances with `--approx`.
+const RECALL_SAMPLE: usize = 100;
+
+fn main() {
+    let mut approx = false;
+    let mut path = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--approx" => approx = true,
+            _ => path = Some(arg),
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("Usage: day_8 [--approx] <input file>");
+        process::exit(2);
+    };
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {path}: {err}");
+        process::exit(1);
+    });
+
+    let part_1 = if approx {
+        // The recall is estimated on the very pairs part 1 connects
+        parse_input::<3>(&input).and_then(|positions| {
+            let pairs = ApproxAlgorithm::default().closest_neighbors(&positions, PART_1_PAIRS);
+            let recall = estimate_recall(&positions, &pairs, RECALL_SAMPLE, SquaredEuclidean);
+            println!("Estimated recall: {:.1}%", 100.0 * recall);
+            solution_part_1_with_pairs(&positions, &pairs)
+        })
+    } else {
+        solution_part_1(&input, AutoAlgorithm, PART_1_PAIRS)
+    };
+
+    match (part_1, solution_part_2_streaming(&input)) {
+        (Ok(part_1), Ok(part_2)) => {
+            println!("Part 1: {part_1}");
+            println!("Part 2: {part_2}");
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("Could not solve {path}: {err}");
+            process::exit(1);
+        }
+    }
+}
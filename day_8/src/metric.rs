@@ -0,0 +1,95 @@
+// Distance metrics between junction boxes
+//
+// The puzzle measures straight-line distance, compared as squared Euclidean
+// distance to stay in integers. The search algorithms only rely on two
+// properties of a metric: the distance between two points is at least the
+// `axis_distance` of their difference along any single axis, and it grows
+// with every coordinate difference. Both hold for all metrics below.
+//
+// The VP-tree instead prunes by the triangle inequality, which squared
+// Euclidean distances violate. `triangle_bounds` maps a distance to a scale
+// on which it holds, the square root for squared Euclidean, as integer lower
+// and upper bounds so the pruning never skips a point it should visit.
+//
+// Distances are `u128`: a squared difference of two `u64` coordinates needs
+// up to 128 bits, and summing a few of them only saturates at the very top.
+
+use crate::Position;
+
+/// A distance between two positions, wide enough for any `u64` coordinates.
+pub type Distance = u128;
+
+/// A distance between two positions.
+pub trait Metric: Copy + Send + Sync {
+    /// The distance between `a` and `b`.
+    fn distance<const D: usize>(&self, a: &Position<D>, b: &Position<D>) -> Distance;
+
+    /// The distance between two points that differ by `delta` along a single
+    /// axis. A lower bound for the distance of points at least `delta` apart
+    /// along any axis.
+    fn axis_distance(&self, delta: u64) -> Distance;
+
+    /// Lower and upper bounds of `distance` on a scale that is monotone in it
+    /// and satisfies the triangle inequality. Exact for true metrics.
+    fn triangle_bounds(&self, distance: Distance) -> (Distance, Distance) {
+        (distance, distance)
+    }
+}
+
+/// The squared Euclidean distance of the puzzle, see [`Position::distance`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SquaredEuclidean;
+
+impl Metric for SquaredEuclidean {
+    fn distance<const D: usize>(&self, a: &Position<D>, b: &Position<D>) -> Distance {
+        a.distance(b)
+    }
+
+    fn axis_distance(&self, delta: u64) -> Distance {
+        Distance::from(delta) * Distance::from(delta)
+    }
+
+    fn triangle_bounds(&self, distance: Distance) -> (Distance, Distance) {
+        let root = distance.isqrt();
+        if root * root == distance {
+            (root, root)
+        } else {
+            (root, root + 1)
+        }
+    }
+}
+
+/// The Manhattan (taxicab) distance: the sum of the coordinate differences.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn distance<const D: usize>(&self, a: &Position<D>, b: &Position<D>) -> Distance {
+        a.0.iter()
+            .zip(&b.0)
+            .map(|(a, b)| Distance::from(a.abs_diff(*b)))
+            .sum()
+    }
+
+    fn axis_distance(&self, delta: u64) -> Distance {
+        Distance::from(delta)
+    }
+}
+
+/// The Chebyshev (chessboard) distance: the largest coordinate difference.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Chebyshev;
+
+impl Metric for Chebyshev {
+    fn distance<const D: usize>(&self, a: &Position<D>, b: &Position<D>) -> Distance {
+        a.0.iter()
+            .zip(&b.0)
+            .map(|(a, b)| Distance::from(a.abs_diff(*b)))
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn axis_distance(&self, delta: u64) -> Distance {
+        Distance::from(delta)
+    }
+}
@@ -0,0 +1,189 @@
+// Vantage-point tree over the junction box positions
+//
+// Like the KD-tree, the tree is stored implicitly in a permutation of the
+// point indices: the first point of a range is its vantage point, and the
+// rest of the range is split at the median distance to it, the closer half
+// first. A search prunes a half by the triangle inequality alone, so unlike
+// the KD-tree it needs no bounds along the axes and works for any metric.
+//
+// `VpTreeAlgorithm` finds the k globally closest pairs from the k nearest
+// neighbors of every point: if the pair (i, j) is among the k closest pairs,
+// fewer than k pairs (i, p) come before it, so j is among the k nearest
+// neighbors of i. Neighbors at equal distance are ordered by index, which
+// makes the result match the pair order of the brute-force algorithm.
+//
+// The pairs are merged into a max-heap of the k best so far, so memory stays
+// O(n + k) rather than holding the neighbors of every point at once. Every
+// pair is only taken from the query of its smaller index, and once the heap
+// is full its worst distance bounds the queries: no farther neighbor can
+// enter it.
+
+use crate::{Distance, KClosestNeighbor, Metric, Position, push_pair};
+use std::collections::BinaryHeap;
+
+/// A static vantage-point tree over a slice of positions, answering nearest
+/// neighbor queries by the distance `M`.
+struct VpTree<'a, const D: usize, M> {
+    positions: &'a [Position<D>],
+    order: Vec<usize>,
+    /// For the range of `order` starting at `i`, the distance from its
+    /// vantage point `order[i]` that splits the two halves.
+    radius: Vec<Distance>,
+    metric: M,
+}
+
+impl<'a, const D: usize, M: Metric> VpTree<'a, D, M> {
+    /// Build the tree in O(n log n) time.
+    fn new(positions: &'a [Position<D>], metric: M) -> Self {
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        let mut radius = vec![0; positions.len()];
+        build(positions, &mut order, &mut radius, metric);
+
+        VpTree {
+            positions,
+            order,
+            radius,
+            metric,
+        }
+    }
+
+    /// The `k` points nearest to `query` as `(distance, index)` pairs, nearest
+    /// first, with ties ordered by index. The point with index `exclude`, if
+    /// any, is skipped, and so are points farther than `limit`.
+    fn nearest(
+        &self,
+        query: &Position<D>,
+        k: usize,
+        exclude: Option<usize>,
+        limit: Distance,
+    ) -> Vec<(Distance, usize)> {
+        let mut best = BinaryHeap::with_capacity(k.min(self.order.len()));
+        if k > 0 {
+            self.search(query, k, exclude, limit, 0, self.order.len(), &mut best);
+        }
+
+        best.into_sorted_vec()
+    }
+
+    /// Search the subtree `order[lo..hi]`, keeping the `k` best candidates
+    /// within `limit` in the max-heap `best`.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        query: &Position<D>,
+        k: usize,
+        exclude: Option<usize>,
+        limit: Distance,
+        lo: usize,
+        hi: usize,
+        best: &mut BinaryHeap<(Distance, usize)>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+
+        let index = self.order[lo];
+        let distance = self.metric.distance(query, &self.positions[index]);
+
+        if exclude != Some(index) && distance <= limit {
+            best.push((distance, index));
+            if best.len() > k {
+                best.pop();
+            }
+        }
+
+        let radius = self.radius[lo];
+        let mid = lo + 1 + (hi - lo - 1) / 2;
+        let inside = (lo + 1, mid);
+        let outside = (mid, hi);
+
+        // Points inside are at most `radius` from the vantage point and
+        // points outside at least `radius`, so by the triangle inequality a
+        // half can only hold better points if the query is close enough to
+        // the boundary between them.
+        let (query_lo, query_hi) = self.metric.triangle_bounds(distance);
+        let (radius_lo, radius_hi) = self.metric.triangle_bounds(radius);
+        let reaches = |best: &BinaryHeap<(Distance, usize)>, half: (usize, usize)| {
+            let worst = match best.peek() {
+                Some(&(worst, _)) if best.len() >= k => worst.min(limit),
+                _ => limit,
+            };
+            let (_, worst_hi) = self.metric.triangle_bounds(worst);
+
+            if half == inside {
+                query_lo <= radius_hi.saturating_add(worst_hi)
+            } else {
+                radius_lo <= query_hi.saturating_add(worst_hi)
+            }
+        };
+
+        let (near, far) = if distance <= radius {
+            (inside, outside)
+        } else {
+            (outside, inside)
+        };
+
+        if reaches(best, near) {
+            self.search(query, k, exclude, limit, near.0, near.1, best);
+        }
+        if reaches(best, far) {
+            self.search(query, k, exclude, limit, far.0, far.1, best);
+        }
+    }
+}
+
+/// Lay out `order` as a vantage-point tree, with `radius` aligned to it.
+fn build<const D: usize>(
+    positions: &[Position<D>],
+    order: &mut [usize],
+    radius: &mut [Distance],
+    metric: impl Metric,
+) {
+    if order.len() <= 1 {
+        return;
+    }
+
+    let vantage = &positions[order[0]];
+    let rest = &mut order[1..];
+    let mid = rest.len() / 2;
+    rest.select_nth_unstable_by_key(mid, |&i| metric.distance(vantage, &positions[i]));
+    radius[0] = metric.distance(vantage, &positions[rest[mid]]);
+
+    let (inside, outside) = rest.split_at_mut(mid);
+    let (inside_radius, outside_radius) = radius[1..].split_at_mut(mid);
+    build(positions, inside, inside_radius, metric);
+    build(positions, outside, outside_radius, metric);
+}
+
+/// Finds the k closest pairs from a vantage-point tree query for the k
+/// nearest neighbors of every point. Slower than the KD-tree for the puzzle,
+/// but only relies on the triangle inequality of the metric.
+pub struct VpTreeAlgorithm;
+
+impl KClosestNeighbor for VpTreeAlgorithm {
+    fn closest_neighbors_by<const D: usize>(
+        &self,
+        positions: &[Position<D>],
+        k: usize,
+        metric: impl Metric,
+    ) -> Vec<(Distance, usize, usize)> {
+        let tree = VpTree::new(positions, metric);
+        let neighbors = k.min(positions.len().saturating_sub(1));
+        let mut best = BinaryHeap::with_capacity(k.min(neighbors.saturating_mul(positions.len())));
+
+        for (i, point) in positions.iter().enumerate() {
+            let limit = match best.peek() {
+                Some(&(worst, _, _)) if best.len() >= k => worst,
+                _ => Distance::MAX,
+            };
+
+            for (distance, j) in tree.nearest(point, neighbors, Some(i), limit) {
+                if j > i {
+                    push_pair(&mut best, (distance, i, j), k);
+                }
+            }
+        }
+
+        best.into_sorted_vec()
+    }
+}
@@ -0,0 +1,174 @@
+// Counting red tiles in rectangles
+//
+// The number of tiles in a rectangle is the number of tiles up to its right
+// edge minus the number left of its left edge, both within its rows. A batch
+// of queries is answered offline: sweeping the tiles by X into a Fenwick tree
+// over their (compressed) Y coordinates, every edge of every rectangle reads
+// the count of its rows when the sweep reaches it. That takes O((n + q) log n)
+// time and O(n + q) space.
+//
+// The emptiness checks of the rectangle search come one pair at a time, and
+// are answered online by a merge-sort tree instead: a segment tree over the
+// tiles sorted by X, every node holding the sorted Y coordinates of its
+// tiles. A rectangle covers O(log n) nodes, each counted by a binary search,
+// so a query takes O(log² n) time and the tree O(n log n) space.
+
+use crate::Point;
+use std::ops::{Bound, Range, RangeBounds};
+
+/// The number of `points` inside each of the `rectangles`, given by two
+/// opposite corners. Points on the border of a rectangle are inside.
+pub fn count_in_rectangles(points: &[Point], rectangles: &[(Point, Point)]) -> Vec<usize> {
+    let mut ys: Vec<i64> = points.iter().map(|point| point.1).collect();
+    ys.sort_unstable();
+    ys.dedup();
+
+    let mut points = points.to_vec();
+    points.sort_unstable_by_key(|point| point.0);
+
+    // The left edge of every rectangle counts the tiles with an X below it,
+    // and the right edge those with an X up to it, as `(x, right, index)`.
+    // Left edges come first, as they exclude the tiles at their own X.
+    let mut edges: Vec<(i64, bool, usize)> = Vec::with_capacity(2 * rectangles.len());
+    for (index, (a, b)) in rectangles.iter().enumerate() {
+        edges.push((a.0.min(b.0), false, index));
+        edges.push((a.0.max(b.0), true, index));
+    }
+    edges.sort_unstable();
+
+    let mut tree = Fenwick::new(ys.len());
+    let mut left = vec![0; rectangles.len()];
+    let mut right = vec![0; rectangles.len()];
+    let mut next = 0;
+    for (x, is_right, index) in edges {
+        while next < points.len() && (points[next].0 < x || is_right && points[next].0 == x) {
+            tree.add(ys.partition_point(|&y| y < points[next].1));
+            next += 1;
+        }
+
+        let (a, b) = rectangles[index];
+        let low = ys.partition_point(|&y| y < a.1.min(b.1));
+        let high = ys.partition_point(|&y| y <= a.1.max(b.1));
+        let rows = tree.prefix(high) - tree.prefix(low);
+        if is_right {
+            right[index] = rows;
+        } else {
+            left[index] = rows;
+        }
+    }
+
+    right
+        .iter()
+        .zip(&left)
+        .map(|(right, left)| right - left)
+        .collect()
+}
+
+/// A Fenwick tree counting values by their index.
+struct Fenwick {
+    tree: Vec<usize>,
+}
+
+impl Fenwick {
+    /// A tree for the indices below `size`, all counts zero.
+    fn new(size: usize) -> Self {
+        Fenwick {
+            tree: vec![0; size + 1],
+        }
+    }
+
+    /// Count one more value at `index`.
+    fn add(&mut self, index: usize) {
+        let mut i = index + 1;
+        while i < self.tree.len() {
+            self.tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// The number of values below `end`.
+    fn prefix(&self, end: usize) -> usize {
+        let mut sum = 0;
+        let mut i = end;
+        while i > 0 {
+            sum += self.tree[i];
+            i -= i & i.wrapping_neg();
+        }
+        sum
+    }
+}
+
+/// The red tiles in a merge-sort tree, counting the tiles in any rectangle.
+pub(crate) struct TileTree {
+    /// The X coordinates of the tiles in ascending order.
+    xs: Vec<i64>,
+    /// The sorted Y coordinates of the tiles below every node of a bottom-up
+    /// segment tree over `xs`: node `i` has the children `2i` and `2i + 1`,
+    /// and the leaves are the nodes from `xs.len()` on.
+    nodes: Vec<Vec<i64>>,
+}
+
+impl TileTree {
+    /// Index the red tiles `points` in O(n log² n) time and O(n log n)
+    /// space.
+    pub fn new(points: &[Point]) -> Self {
+        let mut points = points.to_vec();
+        points.sort_unstable_by_key(|point| point.0);
+
+        let n = points.len();
+        let mut nodes = vec![Vec::new(); 2 * n];
+        for (leaf, point) in nodes[n..].iter_mut().zip(&points) {
+            leaf.push(point.1);
+        }
+        for i in (1..n).rev() {
+            let mut ys = [&nodes[2 * i][..], &nodes[2 * i + 1][..]].concat();
+            ys.sort_unstable();
+            nodes[i] = ys;
+        }
+
+        TileTree {
+            xs: points.iter().map(|point| point.0).collect(),
+            nodes,
+        }
+    }
+
+    /// The number of tiles with an X coordinate in `xs` and a Y coordinate
+    /// in `ys`, in O(log² n) time.
+    pub fn count(&self, xs: impl RangeBounds<i64>, ys: impl RangeBounds<i64>) -> usize {
+        let n = self.xs.len();
+        let Range { start, end } = index_range(&self.xs, &xs);
+        let (mut lo, mut hi) = (start + n, end.max(start) + n);
+        let mut count = 0;
+
+        while lo < hi {
+            if lo % 2 == 1 {
+                count += index_range(&self.nodes[lo], &ys).len();
+                lo += 1;
+            }
+            if hi % 2 == 1 {
+                hi -= 1;
+                count += index_range(&self.nodes[hi], &ys).len();
+            }
+            lo /= 2;
+            hi /= 2;
+        }
+
+        count
+    }
+}
+
+/// The indices of the sorted `values` within `range`.
+fn index_range(values: &[i64], range: &impl RangeBounds<i64>) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&low) => values.partition_point(|&v| v < low),
+        Bound::Excluded(&low) => values.partition_point(|&v| v <= low),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&high) => values.partition_point(|&v| v <= high),
+        Bound::Excluded(&high) => values.partition_point(|&v| v < high),
+        Bound::Unbounded => values.len(),
+    };
+
+    start..end
+}
@@ -0,0 +1,178 @@
+// Closest and farthest pairs of red tiles
+//
+// The closest pair is found by divide and conquer: split the tiles at the
+// median X, solve both halves, and check the pairs across the split. Only the
+// tiles closer to the split line than the best distance so far can form a
+// closer pair, and sorted by Y each of them only has a constant number of
+// candidates after it, so with the halves merged by Y on the way up the search
+// takes O(n log n) time.
+//
+// The farthest pair are two corners of the convex hull that are antipodal:
+// they touch two parallel lines enclosing the hull. Rotating calipers walk all
+// antipodal pairs in one turn around the hull, advancing the opposite corner
+// while it gets farther from the current edge. That takes O(h) time for a
+// hull of h corners, after the O(n log n) hull itself.
+
+use crate::hull::{cross_sign, delta};
+use crate::{Answer, Day9Error, Objective, Point, convex_hull};
+use aoc_core::pairs;
+use std::cmp::Ordering;
+
+/// The two of `points` closest to each other by Euclidean distance, and their
+/// squared distance, in O(n log n) time.
+///
+/// Fails with [`Day9Error::TooFewTiles`] for fewer than two points, and with
+/// [`Day9Error::Overflow`] if the squared distance does not fit in an
+/// [`Answer`].
+pub fn closest_pair(points: &[Point]) -> Result<(Point, Point, Answer), Day9Error> {
+    if points.len() < 2 {
+        return Err(Day9Error::TooFewTiles {
+            found: points.len(),
+        });
+    }
+
+    let mut sorted = points.to_vec();
+    sorted.sort_unstable_by_key(|point| (point.0, point.1));
+    let mut best = (
+        squared_distance(&sorted[0], &sorted[1]),
+        sorted[0],
+        sorted[1],
+    );
+    let mut buffer = Vec::with_capacity(sorted.len());
+    closest(&mut sorted, &mut buffer, &mut best);
+
+    let (_, a, b) = best;
+    Ok((a, b, Objective::SquaredDiagonal.value(&a, &b)?))
+}
+
+/// Update `best` with the closest pair of `points`, sorted by X, and leave
+/// `points` sorted by Y. `buffer` is scratch space for merging.
+fn closest(points: &mut [Point], buffer: &mut Vec<Point>, best: &mut (u128, Point, Point)) {
+    if points.len() <= 3 {
+        for (a, b) in pairs(points) {
+            let distance = squared_distance(a, b);
+            if distance < best.0 {
+                *best = (distance, *a, *b);
+            }
+        }
+        points.sort_unstable_by_key(|point| point.1);
+        return;
+    }
+
+    let mid = points.len() / 2;
+    let split = points[mid].0;
+    let (left, right) = points.split_at_mut(mid);
+    closest(left, buffer, best);
+    closest(right, buffer, best);
+    merge_by_y(points, mid, buffer);
+
+    // The tiles near the split line, by Y
+    buffer.clear();
+    buffer.extend(
+        points
+            .iter()
+            .filter(|point| square(i128::from(point.0) - i128::from(split)) < best.0),
+    );
+    for (index, a) in buffer.iter().enumerate() {
+        for b in &buffer[index + 1..] {
+            if square(i128::from(b.1) - i128::from(a.1)) >= best.0 {
+                break;
+            }
+
+            let distance = squared_distance(a, b);
+            if distance < best.0 {
+                *best = (distance, *a, *b);
+            }
+        }
+    }
+}
+
+/// Merge the runs `points[..mid]` and `points[mid..]`, both sorted by Y, into
+/// one.
+fn merge_by_y(points: &mut [Point], mid: usize, buffer: &mut Vec<Point>) {
+    buffer.clear();
+    let (mut i, mut j) = (0, mid);
+    while i < mid && j < points.len() {
+        if points[j].1 < points[i].1 {
+            buffer.push(points[j]);
+            j += 1;
+        } else {
+            buffer.push(points[i]);
+            i += 1;
+        }
+    }
+    buffer.extend_from_slice(&points[i..mid]);
+    buffer.extend_from_slice(&points[j..]);
+
+    points.copy_from_slice(buffer);
+}
+
+/// The two of `points` farthest from each other by Euclidean distance, and
+/// their squared distance, in O(n log n) time.
+///
+/// Fails with [`Day9Error::TooFewTiles`] for fewer than two points, and with
+/// [`Day9Error::Overflow`] if the squared distance does not fit in an
+/// [`Answer`].
+pub fn farthest_pair(points: &[Point]) -> Result<(Point, Point, Answer), Day9Error> {
+    if points.len() < 2 {
+        return Err(Day9Error::TooFewTiles {
+            found: points.len(),
+        });
+    }
+
+    let hull = convex_hull(points);
+    let (a, b) = match hull[..] {
+        [only] => (only, only),
+        [a, b] => (a, b),
+        _ => antipodal_farthest(&hull),
+    };
+
+    Ok((a, b, Objective::SquaredDiagonal.value(&a, &b)?))
+}
+
+/// The farthest pair of corners of the convex `hull` of at least three
+/// corners, in counter-clockwise order.
+fn antipodal_farthest(hull: &[Point]) -> (Point, Point) {
+    let n = hull.len();
+    let mut best = (0, hull[0], hull[0]);
+    let mut consider = |a: &Point, b: &Point| {
+        let distance = squared_distance(a, b);
+        if distance > best.0 {
+            best = (distance, *a, *b);
+        }
+    };
+
+    let mut opposite = 1;
+    for i in 0..n {
+        let next = (i + 1) % n;
+        let edge = delta(&hull[i], &hull[next]);
+
+        // Advance while the next corner is farther from the edge
+        loop {
+            let step = delta(&hull[opposite], &hull[(opposite + 1) % n]);
+            if cross_sign(edge, step) != Ordering::Greater {
+                break;
+            }
+            opposite = (opposite + 1) % n;
+        }
+
+        consider(&hull[i], &hull[opposite]);
+        consider(&hull[next], &hull[opposite]);
+    }
+
+    (best.1, best.2)
+}
+
+/// The squared Euclidean distance between `a` and `b`, saturating at
+/// `u128::MAX`.
+fn squared_distance(a: &Point, b: &Point) -> u128 {
+    let dx = u128::from(a.0.abs_diff(b.0));
+    let dy = u128::from(a.1.abs_diff(b.1));
+
+    (dx * dx).saturating_add(dy * dy)
+}
+
+/// The square of a difference between two `i64`s.
+fn square(value: i128) -> u128 {
+    value.unsigned_abs().pow(2)
+}
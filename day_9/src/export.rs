@@ -0,0 +1,90 @@
+// Geometry export of the red tiles and the largest rectangle
+//
+// The SVG draws the floor in puzzle coordinates: the view box spans the
+// tiles, so X grows to the right and Y downwards like the rows of the input,
+// and a tile is a square of side 1 centered on its coordinates. Strokes do
+// not scale with the view box, so they stay visible however large the floor.
+// With the `serde` feature the same data is available as JSON.
+
+use crate::{Answer, Day9Error, Point, SearchOptions, largest_rectangle_of, parse_tiles};
+use std::fmt::Write;
+
+/// The red tiles of an input and the largest rectangle between two of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Geometry {
+    /// The distinct red tiles, in input order.
+    pub tiles: Vec<Point>,
+    /// The opposite corners of the largest rectangle.
+    pub corners: (Point, Point),
+    /// The value of the largest rectangle by the objective it was searched
+    /// with.
+    pub value: Answer,
+}
+
+impl Geometry {
+    /// Parse the red tiles of `input` and search the largest rectangle of
+    /// part 1 as configured by `options`.
+    pub fn new(input: &str, options: &SearchOptions) -> Result<Self, Day9Error> {
+        let tiles = parse_tiles(input)?.points;
+        let (a, b, value) = largest_rectangle_of(&tiles, options)?;
+
+        Ok(Geometry {
+            tiles,
+            corners: (a, b),
+            value,
+        })
+    }
+
+    /// The tiles as red squares and the largest rectangle as a green outline
+    /// in an SVG document.
+    pub fn to_svg(&self) -> String {
+        let (a, b) = self.corners;
+        let (min_x, max_x) = bounds(self.tiles.iter().map(|point| point.0));
+        let (min_y, max_y) = bounds(self.tiles.iter().map(|point| point.1));
+
+        let mut svg = String::new();
+        // Writing to a `String` cannot fail
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            min_x - 1.0,
+            min_y - 1.0,
+            max_x - min_x + 2.0,
+            max_y - min_y + 2.0,
+        );
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{}" height="{}" fill="#2e7d32" fill-opacity="0.3" stroke="#2e7d32" stroke-width="2" vector-effect="non-scaling-stroke"/>"##,
+            a.0.min(b.0) as f64 - 0.5,
+            a.1.min(b.1) as f64 - 0.5,
+            a.0.abs_diff(b.0) as f64 + 1.0,
+            a.1.abs_diff(b.1) as f64 + 1.0,
+        );
+        for point in &self.tiles {
+            let _ = writeln!(
+                svg,
+                r##"  <rect x="{}" y="{}" width="1" height="1" fill="#c62828" stroke="#c62828" vector-effect="non-scaling-stroke"/>"##,
+                point.0 as f64 - 0.5,
+                point.1 as f64 - 0.5,
+            );
+        }
+        svg.push_str("</svg>\n");
+
+        svg
+    }
+
+    /// The geometry as a JSON object with the fields `tiles`, `corners` and
+    /// `value`, every point as an `[x, y]` array.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+/// The smallest and largest of `values`, as floats for the SVG coordinates.
+fn bounds(values: impl Iterator<Item = i64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value as f64), max.max(value as f64))
+    })
+}
@@ -0,0 +1,85 @@
+// Convex hull by Andrew's monotone chain
+//
+// Sorted by X and then Y, the points are swept once left to right for the
+// lower hull and once right to left for the upper hull. Every sweep keeps a
+// stack of the hull so far and pops its last point while it does not make a
+// left turn with the new point, so each point is pushed and popped at most
+// once and the sort dominates the O(n log n) time.
+
+use crate::Point;
+use std::cmp::Ordering;
+
+/// The corners of the convex hull of `points`, counter-clockwise starting
+/// from the point with the smallest X (and of those the smallest Y).
+///
+/// Points on the edges of the hull and repeated points are left out, so a
+/// single distinct point gives a hull of one point and collinear points one
+/// of their two extremes.
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut points = points.to_vec();
+    points.sort_unstable_by_key(|point| (point.0, point.1));
+    points.dedup();
+
+    if points.len() <= 2 {
+        return points;
+    }
+
+    let mut hull: Vec<Point> = Vec::with_capacity(points.len() + 1);
+    let push = |hull: &mut Vec<Point>, point: Point, start: usize| {
+        while hull.len() >= start + 2
+            && turn(&hull[hull.len() - 2], &hull[hull.len() - 1], &point) != Ordering::Greater
+        {
+            hull.pop();
+        }
+        hull.push(point);
+    };
+
+    for &point in &points {
+        push(&mut hull, point, 0);
+    }
+
+    // The upper hull starts from the last point of the lower hull, which it
+    // must not pop
+    let start = hull.len() - 1;
+    for &point in points.iter().rev().skip(1) {
+        push(&mut hull, point, start);
+    }
+
+    // The upper hull ends where the lower hull started
+    hull.pop();
+    hull
+}
+
+/// Whether `a`, `b`, `c` make a left turn (`Greater`), a right turn (`Less`)
+/// or are collinear (`Equal`): the sign of the cross product of `a -> b` and
+/// `a -> c`, compared without overflow for any coordinates.
+fn turn(a: &Point, b: &Point, c: &Point) -> Ordering {
+    cross_sign(delta(a, b), delta(a, c))
+}
+
+/// The vector from `from` to `to`.
+pub(crate) fn delta(from: &Point, to: &Point) -> (i128, i128) {
+    (
+        i128::from(to.0) - i128::from(from.0),
+        i128::from(to.1) - i128::from(from.1),
+    )
+}
+
+/// The sign of the cross product of the vectors `u` and `v` between two
+/// points: `Greater` if `v` turns left from `u`.
+pub(crate) fn cross_sign(u: (i128, i128), v: (i128, i128)) -> Ordering {
+    compare_products((u.0, v.1), (u.1, v.0))
+}
+
+/// Compare the products `p.0 * p.1` and `q.0 * q.1` of differences between
+/// two `i64`s, whose magnitudes fit in a `u128` but not always in an `i128`.
+fn compare_products(p: (i128, i128), q: (i128, i128)) -> Ordering {
+    let sign = |(a, b): (i128, i128)| a.signum() * b.signum();
+    let magnitude = |(a, b): (i128, i128)| a.unsigned_abs() * b.unsigned_abs();
+
+    match (sign(p), sign(q)) {
+        (1, 1) => magnitude(p).cmp(&magnitude(q)),
+        (-1, -1) => magnitude(q).cmp(&magnitude(p)),
+        (p, q) => p.cmp(&q),
+    }
+}
@@ -1,8 +1,91 @@
-///! Advent of Code Day 9 – Movie Theater
+//! Advent of Code Day 9 – Movie Theater
+use crate::containment::TileTree;
+#[cfg(not(feature = "parallel"))]
+use aoc_core::index_pairs;
+#[cfg(feature = "parallel")]
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+use std::ops::Bound;
 use std::str::FromStr;
+use std::{error, fmt};
 
-/// A point on the theater floor grid.
-struct Point(u64, u64);
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub use aoc_core::Answer;
+
+pub use crate::containment::count_in_rectangles;
+pub use crate::distance::{closest_pair, farthest_pair};
+pub use crate::export::Geometry;
+pub use crate::hull::convex_hull;
+pub use crate::volume::{PointNd, largest_box, volume};
+
+mod containment;
+mod distance;
+mod export;
+mod hull;
+mod staircase;
+pub mod visualize;
+mod volume;
+
+/// Errors that can occur while parsing or solving the Day 9 puzzle.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Day9Error {
+    /// A line is not a valid point.
+    InvalidLine {
+        /// The 1-based line number in the input.
+        line: usize,
+        /// The offending line.
+        text: String,
+        /// Why the line is invalid.
+        reason: &'static str,
+    },
+    /// A rectangle needs two distinct red tiles as its corners.
+    TooFewTiles {
+        /// The number of distinct red tiles in the input.
+        found: usize,
+    },
+    /// Two consecutive red tiles of part 2 are in neither the same row nor
+    /// the same column, so they cannot be joined by a straight line.
+    DiagonalEdge {
+        /// The 1-based line number of the second of the two tiles.
+        line: usize,
+    },
+    /// The area, perimeter or squared diagonal of a rectangle does not fit in
+    /// an [`Answer`], in strict mode.
+    Overflow,
+    /// Reading the input failed.
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Day9Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Day9Error::InvalidLine { line, text, reason } => {
+                write!(f, "line {line}: invalid point {text:?}: {reason}")
+            }
+            Day9Error::TooFewTiles { found } => {
+                write!(f, "a rectangle needs two distinct red tiles, found {found}")
+            }
+            Day9Error::DiagonalEdge { line } => {
+                write!(
+                    f,
+                    "line {line}: not in the row or column of the previous tile"
+                )
+            }
+            Day9Error::Overflow => write!(f, "the size of a rectangle overflows"),
+            Day9Error::Io(kind) => write!(f, "could not read the input: {kind}"),
+        }
+    }
+}
+
+impl error::Error for Day9Error {}
+
+/// A point on the theater floor grid. Coordinates may be negative.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Point(pub i64, pub i64);
 
 impl FromStr for Point {
     type Err = &'static str;
@@ -18,8 +101,93 @@ impl FromStr for Point {
 }
 
 /// Parses the raw puzzle input into a list of `Point`s.
-fn parse_input(input: &str) -> Result<Vec<Point>, &'static str> {
-    input.lines().map(|line| line.parse()).collect()
+fn parse_input(input: &str) -> Result<Vec<Point>, Day9Error> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| parse_line(index + 1, line))
+        .collect()
+}
+
+/// Parse the points of the puzzle input lazily, reading it line by line from
+/// `reader`. Every point is yielded as soon as its line is read, so only the
+/// current line of the input is kept in memory.
+pub fn parse_reader<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Point, Day9Error>> {
+    reader.lines().enumerate().map(|(index, line)| {
+        let line = line.map_err(|err| Day9Error::Io(err.kind()))?;
+        parse_line(index + 1, &line)
+    })
+}
+
+/// Parse the line with 1-based number `number`.
+fn parse_line(number: usize, line: &str) -> Result<Point, Day9Error> {
+    line.parse().map_err(|reason| Day9Error::InvalidLine {
+        line: number,
+        text: line.to_string(),
+        reason,
+    })
+}
+
+/// The red tiles of an input without repeats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tiles {
+    /// The distinct red tiles, in input order.
+    pub points: Vec<Point>,
+    /// How many red tiles of the input repeat an earlier one.
+    pub duplicates: usize,
+}
+
+/// Parse the red tiles of `input` and drop the repeated ones, which only
+/// add rectangles of a single tile and work. Fails with
+/// [`Day9Error::TooFewTiles`] if fewer than two distinct tiles remain.
+pub fn parse_tiles(input: &str) -> Result<Tiles, Day9Error> {
+    collect_tiles(
+        input
+            .lines()
+            .enumerate()
+            .map(|(index, line)| parse_line(index + 1, line)),
+    )
+}
+
+/// Like [`parse_tiles`], but reading the input line by line from `reader`.
+/// Repeated tiles are dropped as they are read, so they take no memory.
+pub fn read_tiles<R: BufRead>(reader: R) -> Result<Tiles, Day9Error> {
+    collect_tiles(parse_reader(reader))
+}
+
+/// Collect the distinct `points`, failing on the first error.
+fn collect_tiles(
+    input: impl Iterator<Item = Result<Point, Day9Error>>,
+) -> Result<Tiles, Day9Error> {
+    let mut seen = HashSet::new();
+    let mut points = Vec::new();
+    let mut duplicates = 0;
+    for point in input {
+        let point = point?;
+        if seen.insert(point) {
+            points.push(point);
+        } else {
+            duplicates += 1;
+        }
+    }
+
+    if points.len() < 2 {
+        return Err(Day9Error::TooFewTiles {
+            found: points.len(),
+        });
+    }
+
+    Ok(Tiles { points, duplicates })
+}
+
+/// Remove the points that repeat an earlier one, keeping the order of the
+/// rest, and return how many were removed.
+pub fn dedup_points(points: &mut Vec<Point>) -> usize {
+    let mut seen = HashSet::with_capacity(points.len());
+    let before = points.len();
+    points.retain(|&point| seen.insert(point));
+
+    before - points.len()
 }
 
 /// Computes the axis-aligned rectangle area defined by two opposite corners.
@@ -27,8 +195,114 @@ fn parse_input(input: &str) -> Result<Vec<Point>, &'static str> {
 /// Example
 /// - Opposite corners `(2,5)` and `(11,1)` yield width `(11-2)+1 = 10`, height `(5-1)+1 = 5`,
 ///   so area is `10 * 5 = 50`.
-fn area(point: &Point, other: &Point) -> u64 {
-    (point.0.abs_diff(other.0) + 1) * (point.1.abs_diff(other.1) + 1)
+///
+/// Fails with [`Day9Error::Overflow`] if the area does not fit in an
+/// [`Answer`], which only happens for corners at the extremes of the `i64`
+/// range.
+fn area(point: &Point, other: &Point) -> Result<Answer, Day9Error> {
+    volume(&PointNd::from(*point), &PointNd::from(*other))
+}
+
+/// The smallest rectangle holding all of `points`, as its lower-left and
+/// upper-right corners, and its area, in one pass over the points. The
+/// corners need not be red tiles. The area saturates at `Answer::MAX`; `None`
+/// if there are no points.
+pub fn bounding_box(points: &[Point]) -> Option<(Point, Point, Answer)> {
+    let (first, rest) = points.split_first()?;
+    let (min, max) = rest.iter().fold((*first, *first), |(min, max), point| {
+        (
+            Point(min.0.min(point.0), min.1.min(point.1)),
+            Point(max.0.max(point.0), max.1.max(point.1)),
+        )
+    });
+
+    Some((min, max, area(&min, &max).unwrap_or(Answer::MAX)))
+}
+
+/// What makes a rectangle the largest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Objective {
+    /// The number of tiles it covers, as in the puzzle.
+    #[default]
+    Area,
+    /// The number of tiles along its four sides, counting corner tiles twice:
+    /// `2 (width + height)`.
+    Perimeter,
+    /// The squared distance between the centers of its corner tiles.
+    SquaredDiagonal,
+}
+
+impl Objective {
+    /// The value of the rectangle with opposite corners `point` and `other`.
+    /// Fails with [`Day9Error::Overflow`] if it does not fit in an
+    /// [`Answer`].
+    pub fn value(self, point: &Point, other: &Point) -> Result<Answer, Day9Error> {
+        let dx = Answer::from(point.0.abs_diff(other.0));
+        let dy = Answer::from(point.1.abs_diff(other.1));
+
+        let value = match self {
+            Objective::Area => return area(point, other),
+            Objective::Perimeter => Some(2 * (dx + dy + 2)),
+            Objective::SquaredDiagonal => dx
+                .checked_mul(dx)
+                .zip(dy.checked_mul(dy))
+                .and_then(|(dx, dy)| dx.checked_add(dy)),
+        };
+
+        value.ok_or(Day9Error::Overflow)
+    }
+}
+
+/// How part 1 searches the pairs of red tiles. Both algorithms give the same
+/// results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Algorithm {
+    /// Try every pair of red tiles, in O(n²) time.
+    #[default]
+    BruteForce,
+    /// Only pair the red tiles on the outer staircases of the floor, where
+    /// the corners of the largest rectangle must lie, in O(n log n) time.
+    Staircase,
+}
+
+/// Which other red tiles a rectangle may hold besides its two corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Emptiness {
+    /// Any number, as in the puzzle.
+    #[default]
+    Any,
+    /// None strictly inside the rectangle; red tiles on its border are
+    /// allowed.
+    Interior,
+    /// None inside the rectangle or on its border.
+    Closed,
+}
+
+/// Options controlling how the largest rectangle is searched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    /// How the pairs of red tiles are searched in part 1. Part 2 always
+    /// tries every pair, and so does part 1 unless `emptiness` is
+    /// [`Emptiness::Any`].
+    pub algorithm: Algorithm,
+    /// What is maximized.
+    pub objective: Objective,
+    /// Which rectangles are considered by the red tiles they hold.
+    pub emptiness: Emptiness,
+    /// Whether a value that does not fit in an [`Answer`] fails with
+    /// [`Day9Error::Overflow`], instead of saturating at `Answer::MAX`.
+    pub strict: bool,
+}
+
+impl SearchOptions {
+    /// The value of the rectangle with opposite corners `point` and `other`
+    /// by `self.objective`, saturated unless in strict mode.
+    fn value(&self, point: &Point, other: &Point) -> Result<Answer, Day9Error> {
+        match self.objective.value(point, other) {
+            Err(Day9Error::Overflow) if !self.strict => Ok(Answer::MAX),
+            value => value,
+        }
+    }
 }
 
 /// Part 1 solution: find the largest rectangle area using any two red tiles
@@ -37,35 +311,1011 @@ fn area(point: &Point, other: &Point) -> u64 {
 /// Complexity
 /// - Time: O(n²) over the number of points
 /// - Space: O(1)
-fn solution_part_1(input: &str) -> u64 {
-    let points = parse_input(input).expect("Failed to parse input");
+pub fn solution_part_1(input: &str) -> Result<Answer, Day9Error> {
+    solution_part_1_with_options(input, &SearchOptions::default())
+}
+
+/// Part 1 with the search configured by `options`.
+pub fn solution_part_1_with_options(
+    input: &str,
+    options: &SearchOptions,
+) -> Result<Answer, Day9Error> {
+    let (_, _, value) = largest_rectangle_with_options(input, options)?;
+
+    Ok(value)
+}
+
+/// The two red tiles at opposite corners of the largest rectangle of part 1,
+/// and its area.
+pub fn largest_rectangle(input: &str) -> Result<(Point, Point, Answer), Day9Error> {
+    largest_rectangle_with_options(input, &SearchOptions::default())
+}
+
+/// [`largest_rectangle`] with the search configured by `options`; the
+/// returned value is the one of `options.objective`.
+pub fn largest_rectangle_with_options(
+    input: &str,
+    options: &SearchOptions,
+) -> Result<(Point, Point, Answer), Day9Error> {
+    largest_rectangle_of(&parse_tiles(input)?.points, options)
+}
+
+/// Solve part 1 while reading the red tiles line by line from `reader`,
+/// without keeping the text of the input in memory.
+pub fn solution_part_1_from_reader<R: BufRead>(
+    reader: R,
+    options: &SearchOptions,
+) -> Result<Answer, Day9Error> {
+    let (_, _, value) = largest_rectangle_of(&read_tiles(reader)?.points, options)?;
+
+    Ok(value)
+}
+
+/// The largest rectangle between two of the distinct `points`, searched as
+/// configured by `options`.
+fn largest_rectangle_of(
+    points: &[Point],
+    options: &SearchOptions,
+) -> Result<(Point, Point, Answer), Day9Error> {
+    let (i, j, value) = match (options.emptiness, options.algorithm) {
+        (Emptiness::Any, Algorithm::BruteForce) => {
+            largest_value_where(points, options, |_, _| true)?
+        }
+        (Emptiness::Any, Algorithm::Staircase) => {
+            let (i, j) = staircase::largest_pair(points, options.objective);
+            (i, j, options.value(&points[i], &points[j])?)
+        }
+        (emptiness, _) => {
+            let tiles = TileIndex::new(points);
+            largest_value_where(points, options, |point, other| {
+                tiles.is_empty(point, other, emptiness)
+            })?
+        }
+    };
+
+    Ok((points[i], points[j], value))
+}
+
+/// The indices of two of `points` that are opposite corners of the largest
+/// rectangle by `options.objective` for which `keep` holds, and its value, trying
+/// every pair. Of equally large rectangles the first pair is returned.
+#[cfg(not(feature = "parallel"))]
+fn largest_value_where(
+    points: &[Point],
+    options: &SearchOptions,
+    keep: impl Fn(&Point, &Point) -> bool,
+) -> Result<(usize, usize, Answer), Day9Error> {
+    index_pairs(points.len()).try_fold((0, 0, 0), |max, (i, j)| {
+        let value = options.value(&points[i], &points[j])?;
+        if value > max.2 && keep(&points[i], &points[j]) {
+            Ok((i, j, value))
+        } else {
+            Ok(max)
+        }
+    })
+}
+
+/// The indices of two of `points` that are opposite corners of the largest
+/// rectangle by `options.objective` for which `keep` holds, and its value, trying
+/// every pair. Of equally large rectangles the first pair is returned. The
+/// rows of pairs are spread over the rayon thread pool, each worker keeping
+/// its own maximum.
+#[cfg(feature = "parallel")]
+fn largest_value_where(
+    points: &[Point],
+    options: &SearchOptions,
+    keep: impl Fn(&Point, &Point) -> bool + Sync,
+) -> Result<(usize, usize, Answer), Day9Error> {
+    (0..points.len())
+        .into_par_iter()
+        .try_fold(
+            || (0, 0, 0),
+            |max, index| {
+                Ok(larger(
+                    max,
+                    row_largest_value(points, index, options, &keep)?,
+                ))
+            },
+        )
+        .try_reduce(|| (0, 0, 0), |a, b| Ok(larger(a, b)))
+}
+
+/// The largest rectangle by `options.objective` for which `keep` holds between the
+/// point `index` and the points after it, as `(index, other index, value)`.
+#[cfg(feature = "parallel")]
+fn row_largest_value(
+    points: &[Point],
+    index: usize,
+    options: &SearchOptions,
+    keep: impl Fn(&Point, &Point) -> bool,
+) -> Result<(usize, usize, Answer), Day9Error> {
+    let point = &points[index];
+    let mut max = (0, 0, 0);
+
+    for (other_index, other_point) in points.iter().enumerate().skip(index + 1) {
+        let value = options.value(point, other_point)?;
+        if value > max.2 && keep(point, other_point) {
+            max = (index, other_index, value);
+        }
+    }
+
+    Ok(max)
+}
+
+/// The larger of the rectangles `a` and `b`, or the one of the first pair if
+/// they are equally large, so that the result does not depend on the order
+/// in which rows are combined.
+#[cfg(feature = "parallel")]
+fn larger(a: (usize, usize, Answer), b: (usize, usize, Answer)) -> (usize, usize, Answer) {
+    let key = |(i, j, value): (usize, usize, Answer)| (value, Reverse(i), Reverse(j));
+
+    if key(b) > key(a) { b } else { a }
+}
+
+/// The theater floor on a compressed grid: every distinct X and Y coordinate
+/// of a red tile gets its own row or column, and every nonempty range of
+/// coordinates between two of them shares a single one. A rectangle between
+/// red tiles covers whole cells, so checking the cells checks every tile.
+struct CompressedFloor {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+    /// `outside[y][x]` is the number of cells outside the loop in the cells
+    /// before `(x, y)`: a 2D prefix sum.
+    outside: Vec<Vec<usize>>,
+}
+
+impl CompressedFloor {
+    /// Compress the floor and mark the tiles outside the loop of `points`.
+    /// Fails if two consecutive points are not in the same row or column.
+    fn new(points: &[Point]) -> Result<Self, Day9Error> {
+        let xs = compressed_axis(points.iter().map(|point| point.0));
+        let ys = compressed_axis(points.iter().map(|point| point.1));
+
+        // One cell per coordinate and per gap, and a border of outside cells
+        // around it all
+        let width = xs.len() + 2;
+        let height = ys.len() + 2;
+        let mut loop_tiles = vec![vec![false; width]; height];
+
+        for (index, point) in points.iter().enumerate() {
+            let next = &points[(index + 1) % points.len()];
+            if point.0 != next.0 && point.1 != next.1 {
+                return Err(Day9Error::DiagonalEdge {
+                    line: (index + 1) % points.len() + 1,
+                });
+            }
+
+            let (x1, x2) = min_max(cell(&xs, point.0), cell(&xs, next.0));
+            let (y1, y2) = min_max(cell(&ys, point.1), cell(&ys, next.1));
 
-    let mut max = 0;
+            for row in &mut loop_tiles[y1..=y2] {
+                row[x1..=x2].fill(true);
+            }
+        }
 
-    // todo: this is the same as day 8, we could spend some time to optimize it
-    for (index, point) in points.iter().enumerate() {
-        for other_point in points.iter().skip(index + 1) {
-            let area = area(point, other_point);
-            if area > max {
-                max = area;
+        // Flood fill the outside from the border
+        let mut is_outside = vec![vec![false; width]; height];
+        let mut stack: Vec<(usize, usize)> = vec![(0, 0)];
+        is_outside[0][0] = true;
+        while let Some((x, y)) = stack.pop() {
+            let neighbors = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (x, y) in neighbors {
+                if x < width && y < height && !loop_tiles[y][x] && !is_outside[y][x] {
+                    is_outside[y][x] = true;
+                    stack.push((x, y));
+                }
             }
         }
+
+        let mut outside = vec![vec![0; width + 1]; height + 1];
+        for y in 0..height {
+            for x in 0..width {
+                outside[y + 1][x + 1] = outside[y][x + 1] + outside[y + 1][x] - outside[y][x]
+                    + usize::from(is_outside[y][x]);
+            }
+        }
+
+        Ok(CompressedFloor { xs, ys, outside })
     }
 
-    max
+    /// Whether the rectangle with opposite corners `point` and `other` only
+    /// covers red or green tiles.
+    fn is_inside(&self, point: &Point, other: &Point) -> bool {
+        let (x1, x2) = min_max(cell(&self.xs, point.0), cell(&self.xs, other.0));
+        let (y1, y2) = min_max(cell(&self.ys, point.1), cell(&self.ys, other.1));
+
+        let outside = self.outside[y2 + 1][x2 + 1] + self.outside[y1][x1]
+            - self.outside[y1][x2 + 1]
+            - self.outside[y2 + 1][x1];
+
+        outside == 0
+    }
+}
+
+/// The red tiles, checking which other tiles a rectangle between two of them
+/// holds in O(log² n) time.
+struct TileIndex {
+    tiles: TileTree,
+}
+
+impl TileIndex {
+    /// Index the red tiles `points` in O(n log² n) time and O(n log n)
+    /// space.
+    fn new(points: &[Point]) -> Self {
+        TileIndex {
+            tiles: TileTree::new(points),
+        }
+    }
+
+    /// Whether the rectangle with opposite corners `point` and `other` holds
+    /// no red tiles but its corners, as required by `emptiness`.
+    fn is_empty(&self, point: &Point, other: &Point, emptiness: Emptiness) -> bool {
+        let (x1, x2) = (point.0.min(other.0), point.0.max(other.0));
+        let (y1, y2) = (point.1.min(other.1), point.1.max(other.1));
+
+        match emptiness {
+            Emptiness::Any => true,
+            Emptiness::Interior => {
+                let xs = (Bound::Excluded(x1), Bound::Excluded(x2));
+                let ys = (Bound::Excluded(y1), Bound::Excluded(y2));
+                self.tiles.count(xs, ys) == 0
+            }
+            Emptiness::Closed => self.tiles.count(x1..=x2, y1..=y2) == 2,
+        }
+    }
+}
+
+/// The distinct `values` in ascending order.
+fn distinct(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut values: Vec<i64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    values
+}
+
+/// The starts of the cells of a compressed axis of the coordinates `values`
+/// in ascending order: every coordinate, and the one after it. That starts
+/// the gap up to the next coordinate, unless the next coordinate is right
+/// after it and there is no gap.
+fn compressed_axis(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    distinct(values.flat_map(|v| [Some(v), v.checked_add(1)]).flatten())
+}
+
+/// The cell of the compressed grid holding the coordinate `value` of the
+/// compressed axis `values`, after the border cell.
+fn cell(values: &[i64], value: i64) -> usize {
+    values.partition_point(|&v| v < value) + 1
+}
+
+/// `a` and `b` in ascending order.
+fn min_max(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// Part 2 solution: the red tiles, in order, form a loop of straight lines of
+/// green tiles that also fill the inside of the loop. Find the largest
+/// rectangle with two red tiles as opposite corners that only covers red or
+/// green tiles.
+///
+/// Complexity
+/// - Time: O(n²) over the number of points, on a compressed grid of O(n²) cells
+/// - Space: O(n²)
+pub fn solution_part_2(input: &str) -> Result<Answer, Day9Error> {
+    solution_part_2_with_options(input, &SearchOptions::default())
+}
+
+/// Part 2 with the rectangles compared by `options.objective`.
+pub fn solution_part_2_with_options(
+    input: &str,
+    options: &SearchOptions,
+) -> Result<Answer, Day9Error> {
+    part_2(&parse_input(input)?, options)
+}
+
+/// Solve part 2 while reading the red tiles line by line from `reader`,
+/// without keeping the text of the input in memory.
+pub fn solution_part_2_from_reader<R: BufRead>(
+    reader: R,
+    options: &SearchOptions,
+) -> Result<Answer, Day9Error> {
+    let points = parse_reader(reader).collect::<Result<Vec<_>, _>>()?;
+
+    part_2(&points, options)
+}
+
+/// The largest rectangle inside the loop of `points` by `options.objective`.
+fn part_2(points: &[Point], options: &SearchOptions) -> Result<Answer, Day9Error> {
+    // Repeated tiles stay, as they are where the loop meets itself
+    let found = points.iter().collect::<HashSet<_>>().len();
+    if found < 2 {
+        return Err(Day9Error::TooFewTiles { found });
+    }
+    let floor = CompressedFloor::new(points)?;
+    let tiles = (options.emptiness != Emptiness::Any).then(|| TileIndex::new(points));
+    let (_, _, value) = largest_value_where(points, options, |point, other| {
+        floor.is_inside(point, other)
+            && tiles
+                .as_ref()
+                .is_none_or(|tiles| tiles.is_empty(point, other, options.emptiness))
+    })?;
+
+    Ok(value)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc_core::index_pairs;
+    use aoc_core::rng::TestRng;
+
+    const STAIRCASE: SearchOptions = SearchOptions {
+        algorithm: Algorithm::Staircase,
+        objective: Objective::Area,
+        emptiness: Emptiness::Any,
+        strict: false,
+    };
+
+    const OBJECTIVES: [Objective; 3] = [
+        Objective::Area,
+        Objective::Perimeter,
+        Objective::SquaredDiagonal,
+    ];
 
     #[test]
     fn test_area() {
-        assert_eq!(area(&Point(2, 5), &Point(11, 1)), 50);
+        assert_eq!(area(&Point(2, 5), &Point(11, 1)), Ok(50));
+        assert_eq!(area(&Point(-2, 5), &Point(7, -1)), Ok(70));
+        assert_eq!(area(&Point(i64::MIN, 0), &Point(i64::MAX, 1)), Ok(1 << 65));
+        assert_eq!(
+            area(&Point(i64::MIN, i64::MIN), &Point(i64::MAX, i64::MAX)),
+            Err(Day9Error::Overflow)
+        );
     }
 
     #[test]
     fn test_solution_part_1() {
-        assert_eq!(solution_part_1(include_str!("sample_input.txt")), 50);
+        assert_eq!(solution_part_1(include_str!("sample_input.txt")), Ok(50));
+        assert_eq!(
+            solution_part_1_with_options(include_str!("sample_input.txt"), &STAIRCASE),
+            Ok(50)
+        );
+    }
+
+    #[test]
+    fn test_largest_rectangle() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(
+            largest_rectangle(input),
+            Ok((Point(11, 1), Point(2, 5), 50))
+        );
+
+        let (a, b, area) = largest_rectangle_with_options(input, &STAIRCASE).unwrap();
+        assert_eq!(super::area(&a, &b), Ok(area));
+        assert_eq!(area, 50);
+
+        assert_eq!(
+            largest_rectangle("3,4"),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_staircase_matches_brute_force() {
+        assert_eq!(
+            solution_part_1_with_options("", &STAIRCASE),
+            Err(Day9Error::TooFewTiles { found: 0 })
+        );
+
+        let mut rng = TestRng::new(1);
+
+        for _ in 0..200 {
+            let n = rng.below(30) + 2;
+            let size = rng.below(50) + 1;
+            let input: String = (0..n)
+                .map(|_| {
+                    format!(
+                        "{},{}\n",
+                        rng.below(size) as i64 - 25,
+                        rng.below(size) as i64 - 25
+                    )
+                })
+                .collect();
+
+            for objective in OBJECTIVES {
+                assert_eq!(
+                    solution_part_1_with_options(
+                        &input,
+                        &SearchOptions {
+                            algorithm: Algorithm::Staircase,
+                            objective,
+                            emptiness: Emptiness::Any,
+                            strict: false,
+                        }
+                    ),
+                    solution_part_1_with_options(
+                        &input,
+                        &SearchOptions {
+                            algorithm: Algorithm::BruteForce,
+                            objective,
+                            emptiness: Emptiness::Any,
+                            strict: false,
+                        }
+                    ),
+                    "{objective:?} {input}"
+                );
+            }
+        }
+
+        // Every point on both staircases
+        let input: String = (0..100).map(|i| format!("{i},{}\n", 99 - i)).collect();
+        for objective in OBJECTIVES {
+            assert_eq!(
+                solution_part_1_with_options(
+                    &input,
+                    &SearchOptions {
+                        algorithm: Algorithm::Staircase,
+                        objective,
+                        emptiness: Emptiness::Any,
+                        strict: false,
+                    }
+                ),
+                solution_part_1_with_options(
+                    &input,
+                    &SearchOptions {
+                        algorithm: Algorithm::BruteForce,
+                        objective,
+                        emptiness: Emptiness::Any,
+                        strict: false,
+                    }
+                ),
+                "{objective:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_objectives() {
+        let input = include_str!("sample_input.txt");
+        let options = |objective| SearchOptions {
+            objective,
+            ..SearchOptions::default()
+        };
+
+        assert_eq!(
+            solution_part_1_with_options(input, &options(Objective::Perimeter)),
+            Ok(30)
+        );
+        assert_eq!(
+            solution_part_1_with_options(input, &options(Objective::SquaredDiagonal)),
+            Ok(97)
+        );
+        assert_eq!(
+            solution_part_2_with_options(input, &options(Objective::Perimeter)),
+            Ok(22)
+        );
+        assert_eq!(
+            solution_part_2_with_options(input, &options(Objective::SquaredDiagonal)),
+            Ok(53)
+        );
+
+        let (a, b) = (Point(2, 3), Point(7, 1));
+        assert_eq!(Objective::Area.value(&a, &b), Ok(18));
+        assert_eq!(Objective::Perimeter.value(&a, &b), Ok(18));
+        assert_eq!(Objective::SquaredDiagonal.value(&a, &b), Ok(29));
+    }
+
+    #[test]
+    fn test_emptiness() {
+        let input = include_str!("sample_input.txt");
+        let options = |emptiness| SearchOptions {
+            emptiness,
+            ..SearchOptions::default()
+        };
+
+        assert_eq!(
+            solution_part_1_with_options(input, &options(Emptiness::Interior)),
+            Ok(40)
+        );
+        assert_eq!(
+            solution_part_1_with_options(input, &options(Emptiness::Closed)),
+            Ok(15)
+        );
+        // The largest rectangle of part 2 only holds red tiles on its border
+        assert_eq!(
+            solution_part_2_with_options(input, &options(Emptiness::Interior)),
+            Ok(24)
+        );
+        assert_eq!(
+            solution_part_2_with_options(input, &options(Emptiness::Closed)),
+            Ok(15)
+        );
+
+        let mut rng = TestRng::new(7);
+
+        for _ in 0..100 {
+            let n = rng.below(20) as usize + 2;
+            let mut points: Vec<Point> = (0..n)
+                .map(|_| Point(rng.below(12) as i64 - 6, rng.below(12) as i64 - 6))
+                .collect();
+            let input: String = points
+                .iter()
+                .map(|p| format!("{},{}\n", p.0, p.1))
+                .collect();
+            dedup_points(&mut points);
+            let n = points.len();
+            if n < 2 {
+                continue;
+            }
+
+            for emptiness in [Emptiness::Interior, Emptiness::Closed] {
+                let mut expected = 0;
+                for (i, j) in index_pairs(n) {
+                    let (a, b) = (points[i], points[j]);
+                    let (x1, x2) = (a.0.min(b.0), a.0.max(b.0));
+                    let (y1, y2) = (a.1.min(b.1), a.1.max(b.1));
+                    let holds = |k: usize| {
+                        let Point(x, y) = points[k];
+                        match emptiness {
+                            Emptiness::Interior => x1 < x && x < x2 && y1 < y && y < y2,
+                            _ => (x1..=x2).contains(&x) && (y1..=y2).contains(&y),
+                        }
+                    };
+                    if (0..n).all(|k| k == i || k == j || !holds(k)) {
+                        expected = expected.max(area(&a, &b).unwrap());
+                    }
+                }
+
+                assert_eq!(
+                    solution_part_1_with_options(&input, &options(emptiness)),
+                    Ok(expected),
+                    "{emptiness:?} {input}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        assert_eq!(convex_hull(&[]), vec![]);
+        assert_eq!(convex_hull(&[Point(3, 4), Point(3, 4)]), vec![Point(3, 4)]);
+        assert_eq!(
+            convex_hull(&[Point(2, 2), Point(0, 0), Point(1, 1), Point(3, 3)]),
+            vec![Point(0, 0), Point(3, 3)]
+        );
+
+        // The U shape: the inner corners and the tiles on the edges are
+        // left out
+        let points = parse_input("0,0\n10,0\n10,10\n8,10\n8,2\n2,2\n2,10\n0,10\n").unwrap();
+        assert_eq!(
+            convex_hull(&points),
+            vec![Point(0, 0), Point(10, 0), Point(10, 10), Point(0, 10)]
+        );
+
+        let points = parse_input(include_str!("sample_input.txt")).unwrap();
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Point(2, 3),
+                Point(7, 1),
+                Point(11, 1),
+                Point(11, 7),
+                Point(9, 7),
+                Point(2, 5),
+            ]
+        );
+
+        // Cross products that do not fit in an i128
+        let (min, max) = (i64::MIN, i64::MAX);
+        assert_eq!(
+            convex_hull(&[
+                Point(min, min),
+                Point(max, min),
+                Point(-1, -1),
+                Point(min, max)
+            ]),
+            vec![Point(min, min), Point(max, min), Point(min, max)]
+        );
+        assert_eq!(
+            convex_hull(&[Point(min, min), Point(max, max), Point(0, 0)]),
+            vec![Point(min, min), Point(max, max)]
+        );
+    }
+
+    #[test]
+    fn test_solution_part_2() {
+        assert_eq!(solution_part_2(include_str!("sample_input.txt")), Ok(24));
+
+        // A U shape: the widest rectangle would span the gap between its arms
+        let input = "0,0\n10,0\n10,10\n8,10\n8,2\n2,2\n2,10\n0,10\n";
+        assert_eq!(solution_part_2(input), Ok(33));
+
+        // The same U shape moved to negative coordinates
+        let input = "-5,-5\n5,-5\n5,5\n3,5\n3,-3\n-3,-3\n-3,5\n-5,5\n";
+        assert_eq!(solution_part_2(input), Ok(33));
+        assert_eq!(solution_part_1(input), Ok(121));
+
+        // A notch one tile wide: its two walls are on neighbouring columns,
+        // so no tile between them is outside the loop
+        let input = "0,0\n3,0\n3,5\n4,5\n4,0\n10,0\n10,10\n0,10\n";
+        assert_eq!(solution_part_2(input), Ok(121));
+
+        // The same notch two tiles wide, with a column outside between them
+        let input = "0,0\n3,0\n3,5\n5,5\n5,0\n10,0\n10,10\n0,10\n";
+        assert_eq!(solution_part_2(input), Ok(66));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let input = "1,2\n5,6\n1,2\n3,4\n5,6\n1,2\n";
+        assert_eq!(
+            parse_tiles(input),
+            Ok(Tiles {
+                points: vec![Point(1, 2), Point(5, 6), Point(3, 4)],
+                duplicates: 3,
+            })
+        );
+        assert_eq!(solution_part_1(input), Ok(25));
+
+        // A single tile repeated makes no rectangle
+        assert_eq!(
+            solution_part_1("3,4\n3,4\n"),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+        assert_eq!(
+            solution_part_2("3,4\n3,4\n"),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+
+        // Repeats no longer count as other tiles on the border
+        let options = SearchOptions {
+            emptiness: Emptiness::Closed,
+            ..SearchOptions::default()
+        };
+        assert_eq!(
+            solution_part_1_with_options("0,0\n0,0\n4,4\n", &options),
+            Ok(25)
+        );
+    }
+
+    #[test]
+    fn test_solve_from_reader() {
+        let input = include_str!("sample_input.txt");
+        assert_eq!(
+            solution_part_1_from_reader(input.as_bytes(), &SearchOptions::default()),
+            Ok(50)
+        );
+        assert_eq!(
+            solution_part_1_from_reader(input.as_bytes(), &STAIRCASE),
+            Ok(50)
+        );
+        assert_eq!(
+            solution_part_2_from_reader(input.as_bytes(), &SearchOptions::default()),
+            Ok(24)
+        );
+        assert_eq!(
+            read_tiles("1,2\n3,4\n1,2\n".as_bytes()),
+            parse_tiles("1,2\n3,4\n1,2\n")
+        );
+
+        assert_eq!(
+            parse_reader("1,2\n3\n".as_bytes()).collect::<Vec<_>>(),
+            vec![
+                Ok(Point(1, 2)),
+                Err(Day9Error::InvalidLine {
+                    line: 2,
+                    text: "3".to_string(),
+                    reason: "Missing comma",
+                })
+            ]
+        );
+        assert_eq!(
+            parse_line(2, "3").unwrap_err().to_string(),
+            r#"line 2: invalid point "3": Missing comma"#
+        );
+        assert_eq!(
+            solution_part_2_from_reader(&[0xff, b'\n'][..], &SearchOptions::default()),
+            Err(Day9Error::Io(io::ErrorKind::InvalidData))
+        );
+    }
+
+    #[test]
+    fn test_geometry() {
+        let input = include_str!("sample_input.txt");
+        let geometry = Geometry::new(input, &SearchOptions::default()).unwrap();
+        assert_eq!(geometry.tiles.len(), 8);
+        assert_eq!(geometry.corners, (Point(11, 1), Point(2, 5)));
+        assert_eq!(geometry.value, 50);
+
+        let svg = geometry.to_svg();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="1 0 11 8">"#));
+        assert!(svg.contains(r#"<rect x="1.5" y="0.5" width="10" height="5" "#));
+        assert_eq!(svg.matches("<rect").count(), 9);
+
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            Geometry::new("1,2\n-3,4\n", &SearchOptions::default())
+                .unwrap()
+                .to_json()
+                .unwrap(),
+            r#"{"tiles":[[1,2],[-3,4]],"corners":[[1,2],[-3,4]],"value":15}"#
+        );
+    }
+
+    #[test]
+    fn test_render_grid() {
+        let input = include_str!("sample_input.txt");
+        let geometry = Geometry::new(input, &SearchOptions::default()).unwrap();
+
+        assert_eq!(
+            visualize::render_grid(&geometry, 80, 40),
+            [
+                "OOOOO#OOO#",
+                "OOOOOOOOOO",
+                "#OOOO#OOOO",
+                "OOOOOOOOOO",
+                "#OOOOOO#OO",
+                "..........",
+                ".......#.#",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            visualize::render_grid(&geometry, 5, 3),
+            ["#O#O#", "#OO#O", "...##"].join("\n")
+        );
+
+        // Coordinates spanning the whole i64 range still fit
+        let input = format!("{0},{0}\n{1},{1}\n0,0\n", i64::MIN, i64::MAX);
+        let geometry = Geometry::new(&input, &SearchOptions::default()).unwrap();
+        assert_eq!(
+            visualize::render_grid(&geometry, 3, 3),
+            ["#OO", "O#O", "OO#"].join("\n")
+        );
+    }
+
+    #[test]
+    fn test_volume() {
+        assert_eq!(
+            volume(&PointNd([2, 5]), &PointNd([11, 1])),
+            area(&Point(2, 5), &Point(11, 1))
+        );
+        assert_eq!(volume(&PointNd([1, 2, 3]), &PointNd([4, 0, 3])), Ok(12));
+        assert_eq!(volume::<0>(&PointNd([]), &PointNd([])), Ok(1));
+        assert_eq!(
+            volume(&PointNd([i64::MIN; 3]), &PointNd([0; 3])),
+            Err(Day9Error::Overflow)
+        );
+
+        let input = "1,1,1\n3,0,2\n1,1,1\n-2,4,1\n";
+        assert_eq!(
+            largest_box::<3>(input),
+            Ok((PointNd([3, 0, 2]), PointNd([-2, 4, 1]), 60))
+        );
+        assert_eq!(
+            largest_box::<2>(include_str!("sample_input.txt")).map(|(_, _, volume)| volume),
+            Ok(50)
+        );
+        assert_eq!(
+            largest_box::<3>("1,2\n"),
+            Err(Day9Error::InvalidLine {
+                line: 1,
+                text: "1,2".to_string(),
+                reason: "Missing coordinate",
+            })
+        );
+        assert_eq!(
+            largest_box::<3>("1,2,3\n1,2,3\n"),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_closest_and_farthest_pair() {
+        let points = parse_input(include_str!("sample_input.txt")).unwrap();
+        assert_eq!(closest_pair(&points).map(|(_, _, d)| d), Ok(4));
+        // As far apart as (11,1) and (2,5)
+        assert_eq!(farthest_pair(&points), Ok((Point(2, 3), Point(11, 7), 97)));
+
+        assert_eq!(
+            closest_pair(&[Point(1, 1)]),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+        assert_eq!(
+            farthest_pair(&[Point(1, 1), Point(1, 1)]),
+            Ok((Point(1, 1), Point(1, 1), 0))
+        );
+
+        let mut rng = TestRng::new(3);
+
+        for _ in 0..200 {
+            let n = rng.below(60) + 2;
+            let size = rng.below(1000) + 1;
+            let points: Vec<Point> = (0..n)
+                .map(|_| Point(rng.below(size) as i64 - 500, rng.below(size) as i64 - 500))
+                .collect();
+
+            let distances = || {
+                index_pairs(points.len()).map(|(i, j)| {
+                    Objective::SquaredDiagonal
+                        .value(&points[i], &points[j])
+                        .unwrap()
+                })
+            };
+            let (a, b, closest) = closest_pair(&points).unwrap();
+            assert_eq!(Some(closest), distances().min(), "{points:?}");
+            assert_eq!(Objective::SquaredDiagonal.value(&a, &b), Ok(closest));
+
+            let (a, b, farthest) = farthest_pair(&points).unwrap();
+            assert_eq!(Some(farthest), distances().max(), "{points:?}");
+            assert_eq!(Objective::SquaredDiagonal.value(&a, &b), Ok(farthest));
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let points = parse_input(include_str!("sample_input.txt")).unwrap();
+        assert_eq!(bounding_box(&points), Some((Point(2, 1), Point(11, 7), 70)));
+        assert_eq!(
+            bounding_box(&[Point(-3, 4)]),
+            Some((Point(-3, 4), Point(-3, 4), 1))
+        );
+        assert_eq!(bounding_box(&[]), None);
+        assert_eq!(
+            bounding_box(&[Point(i64::MIN, i64::MAX), Point(i64::MAX, i64::MIN)]),
+            Some((
+                Point(i64::MIN, i64::MIN),
+                Point(i64::MAX, i64::MAX),
+                Answer::MAX
+            ))
+        );
+    }
+
+    #[test]
+    fn test_count_in_rectangles() {
+        let points = parse_input(include_str!("sample_input.txt")).unwrap();
+        assert_eq!(
+            count_in_rectangles(
+                &points,
+                &[
+                    (Point(2, 5), Point(11, 1)),
+                    (Point(9, 7), Point(2, 3)),
+                    (Point(3, 2), Point(6, 6)),
+                    (Point(7, 1), Point(7, 1)),
+                ]
+            ),
+            vec![6, 5, 0, 1]
+        );
+        assert_eq!(
+            count_in_rectangles(&[], &[(Point(0, 0), Point(1, 1))]),
+            vec![0]
+        );
+
+        let mut rng = TestRng::new(5);
+        let mut random_point = || Point(rng.below(20) as i64 - 10, rng.below(20) as i64 - 10);
+
+        let points: Vec<Point> = (0..200).map(|_| random_point()).collect();
+        let rectangles: Vec<(Point, Point)> =
+            (0..200).map(|_| (random_point(), random_point())).collect();
+        let expected: Vec<usize> = rectangles
+            .iter()
+            .map(|(a, b)| {
+                points
+                    .iter()
+                    .filter(|p| {
+                        (a.0.min(b.0)..=a.0.max(b.0)).contains(&p.0)
+                            && (a.1.min(b.1)..=a.1.max(b.1)).contains(&p.1)
+                    })
+                    .count()
+            })
+            .collect();
+        assert_eq!(count_in_rectangles(&points, &rectangles), expected);
+
+        // The merge-sort tree answers the same queries one at a time
+        let tree = TileTree::new(&points);
+        for ((a, b), &expected) in rectangles.iter().zip(&expected) {
+            let xs = a.0.min(b.0)..=a.0.max(b.0);
+            let ys = a.1.min(b.1)..=a.1.max(b.1);
+            assert_eq!(tree.count(xs, ys), expected);
+        }
+        assert_eq!(tree.count(.., ..), 200);
+        assert_eq!(tree.count(3..3, ..), 0);
+        assert_eq!(TileTree::new(&[]).count(.., ..), 0);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            solution_part_1("1,2\n3;4\n"),
+            Err(Day9Error::InvalidLine {
+                line: 2,
+                text: "3;4".to_string(),
+                reason: "Missing comma",
+            })
+        );
+        assert_eq!(
+            solution_part_2("1,2\n1,x\n"),
+            Err(Day9Error::InvalidLine {
+                line: 2,
+                text: "1,x".to_string(),
+                reason: "Invalid Y",
+            })
+        );
+        assert_eq!(
+            solution_part_2("0,0\n4,0\n6,3\n0,3\n"),
+            Err(Day9Error::DiagonalEdge { line: 3 })
+        );
+        assert_eq!(
+            solution_part_2("0,0\n"),
+            Err(Day9Error::TooFewTiles { found: 1 })
+        );
+    }
+
+    #[test]
+    fn test_wide_values() {
+        // Areas beyond a u64
+        let input = format!("{},0\n{},5\n", i64::MIN, i64::MAX);
+        assert_eq!(solution_part_1(&input), Ok(6 << 64));
+        assert_eq!(
+            solution_part_1_with_options(&input, &STAIRCASE),
+            Ok(6 << 64)
+        );
+
+        // Beyond a u128 the value saturates, unless in strict mode
+        let input = format!("{0},{0}\n{1},{1}\n0,0\n", i64::MIN, i64::MAX);
+        let strict = SearchOptions {
+            strict: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(solution_part_1(&input), Ok(Answer::MAX));
+        assert_eq!(
+            solution_part_1_with_options(&input, &STAIRCASE),
+            Ok(Answer::MAX)
+        );
+        assert_eq!(
+            solution_part_1_with_options(&input, &strict),
+            Err(Day9Error::Overflow)
+        );
+        assert_eq!(
+            solution_part_1_with_options(
+                &input,
+                &SearchOptions {
+                    strict: true,
+                    ..STAIRCASE
+                }
+            ),
+            Err(Day9Error::Overflow)
+        );
+
+        // Every objective is ordered exactly by the staircase, however large
+        let input = format!(
+            "{0},{1}\n{1},{0}\n{2},{2}\n{3},{3}\n",
+            i64::MIN,
+            i64::MAX,
+            i64::MIN / 2,
+            i64::MAX / 2 + 7
+        );
+        for objective in OBJECTIVES {
+            let options = SearchOptions {
+                objective,
+                strict: true,
+                ..SearchOptions::default()
+            };
+            assert_eq!(
+                solution_part_1_with_options(
+                    &input,
+                    &SearchOptions {
+                        algorithm: Algorithm::Staircase,
+                        ..options
+                    }
+                ),
+                solution_part_1_with_options(&input, &options),
+                "{objective:?}"
+            );
+        }
     }
 }
@@ -0,0 +1,127 @@
+//! Command line interface for Advent of Code Day 9 — Movie Theater
+//!
+//! Usage: `day_9 [--objective <area|perimeter|diagonal>] [--strict] [--visualize <output file>] [--render] <input file>`
+//!
+//! Prints the answers to both parts, and how many repeated red tiles part 1
+//! dropped, if any. With `--objective`, the largest rectangle is the one with
+//! the largest perimeter or squared diagonal instead of area. With `--strict`,
+//! a value too large for a `u128` is an error instead of saturating. With
+//! `--visualize`, the red tiles and the largest rectangle of part 1 are
+//! written to the output file as SVG, or as JSON if its name ends in `.json`,
+//! which needs the `serde` feature. With `--render`, they are first drawn in
+//! the terminal, scaled down to fit.
+
+use day_9::visualize::render_grid;
+use day_9::{
+    Geometry, Objective, SearchOptions, parse_tiles, solution_part_1_with_options,
+    solution_part_2_with_options,
+};
+use std::{env, fs, process};
+
+const USAGE: &str = "Usage: day_9 [--objective <area|perimeter|diagonal>] [--strict] [--visualize <output file>] [--render] <input file>";
+
+/// The most columns of the grid drawn by `--render`.
+const GRID_COLUMNS: usize = 80;
+
+/// The most rows of the grid drawn by `--render`.
+const GRID_ROWS: usize = 40;
+
+fn main() {
+    let mut options = SearchOptions::default();
+    let mut path = None;
+    let mut visualize = None;
+    let mut render_terminal = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--objective" => {
+                options.objective = match args.next().as_deref() {
+                    Some("area") => Objective::Area,
+                    Some("perimeter") => Objective::Perimeter,
+                    Some("diagonal") => Objective::SquaredDiagonal,
+                    _ => {
+                        eprintln!("{USAGE}");
+                        process::exit(2);
+                    }
+                }
+            }
+            "--strict" => options.strict = true,
+            "--visualize" => {
+                let Some(output) = args.next() else {
+                    eprintln!("{USAGE}");
+                    process::exit(2);
+                };
+                visualize = Some(output);
+            }
+            "--render" => render_terminal = true,
+            _ => path = Some(arg),
+        }
+    }
+
+    let Some(path) = path else {
+        eprintln!("{USAGE}");
+        process::exit(2);
+    };
+
+    let input = fs::read_to_string(&path).unwrap_or_else(|err| {
+        eprintln!("Could not read {path}: {err}");
+        process::exit(1);
+    });
+
+    if let Ok(tiles) = parse_tiles(&input)
+        && tiles.duplicates > 0
+    {
+        eprintln!("Dropped {} repeated red tiles", tiles.duplicates);
+    }
+
+    if visualize.is_some() || render_terminal {
+        let geometry = Geometry::new(&input, &options).unwrap_or_else(|err| {
+            eprintln!("Could not solve {path}: {err}");
+            process::exit(1);
+        });
+
+        if let Some(output) = visualize
+            && let Err(err) = fs::write(&output, render(&geometry, &output))
+        {
+            eprintln!("Could not write {output}: {err}");
+            process::exit(1);
+        }
+        if render_terminal {
+            println!("{}", render_grid(&geometry, GRID_COLUMNS, GRID_ROWS));
+        }
+    }
+
+    match (
+        solution_part_1_with_options(&input, &options),
+        solution_part_2_with_options(&input, &options),
+    ) {
+        (Ok(part_1), Ok(part_2)) => {
+            println!("Part 1: {part_1}");
+            println!("Part 2: {part_2}");
+        }
+        (Err(err), _) | (_, Err(err)) => {
+            eprintln!("Could not solve {path}: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+/// The contents of the `--visualize` output file `output`.
+fn render(geometry: &Geometry, output: &str) -> String {
+    if output.ends_with(".json") {
+        #[cfg(feature = "serde")]
+        return geometry.to_json().unwrap_or_else(|err| {
+            eprintln!("Could not serialize the geometry: {err}");
+            process::exit(1);
+        });
+
+        #[cfg(not(feature = "serde"))]
+        {
+            eprintln!("Writing JSON needs the serde feature");
+            process::exit(2);
+        }
+    }
+
+    geometry.to_svg()
+}
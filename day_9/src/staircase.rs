@@ -0,0 +1,162 @@
+// Largest rectangle between two points in O(n log n)
+//
+// Take the corners of the largest rectangle as a lower-left corner p and an
+// upper-right corner q (the other orientation is the same problem with the
+// Y axis mirrored). No point lies below and left of p, or the rectangle
+// between it and q would be larger, and likewise no point lies above and
+// right of q. So p lies on the lower-left staircase of the points and q on
+// the upper-right one. This holds for every objective, as all of them grow
+// with both the width and the height.
+//
+// Along both staircases X increases while Y decreases. The area
+// (qx - px + 1)(qy - py + 1) then has the property that the best q of a
+// later p never comes before the best q of an earlier p, so divide and
+// conquer over the lower staircase only scans O(h log h) pairs for
+// staircases of h points. The perimeter has the same property, and the
+// squared diagonal the opposite one: the best q of a later p never comes
+// after the best q of an earlier p.
+//
+// Values are computed from the signed differences qx - px and qy - py, so
+// they never exceed the value of the real rectangle between p and q. A pair
+// where q is not above and right of p never wins with a wrong value: q cannot
+// lie below and left of p, as it is on the upper staircase. They are ordered
+// exactly, though their magnitude can take all 128 bits of a `u128`.
+
+use crate::{Objective, Point};
+use std::cmp::Reverse;
+
+/// The indices of two of `points` that are opposite corners of the largest
+/// rectangle by `objective`.
+pub(crate) fn largest_pair(points: &[Point], objective: Objective) -> (usize, usize) {
+    let points: Vec<Corner> = points
+        .iter()
+        .enumerate()
+        .map(|(index, point)| (i128::from(point.0), i128::from(point.1), index))
+        .collect();
+    let mirrored: Vec<Corner> = points.iter().map(|&(x, y, index)| (x, -y, index)).collect();
+
+    let (_, i, j) = largest_dominating_value(points, objective)
+        .max(largest_dominating_value(mirrored, objective));
+
+    (i, j)
+}
+
+/// A point as `(x, y, index)`.
+type Corner = (i128, i128, usize);
+
+/// A signed value with a magnitude of up to 128 bits, ordered like the
+/// numbers. Magnitudes of exactly 2^128 saturate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Value {
+    Negative(Reverse<u128>),
+    NonNegative(u128),
+}
+
+impl Value {
+    const ZERO: Value = Value::NonNegative(0);
+
+    fn product(a: i128, b: i128) -> Value {
+        let magnitude = a.unsigned_abs().saturating_mul(b.unsigned_abs());
+        if a.signum() * b.signum() < 0 {
+            Value::Negative(Reverse(magnitude))
+        } else {
+            Value::NonNegative(magnitude)
+        }
+    }
+}
+
+/// The largest value of a rectangle with a lower-left corner and an
+/// upper-right corner in `points`, and the indices of both corners.
+fn largest_dominating_value(
+    mut points: Vec<Corner>,
+    objective: Objective,
+) -> (Value, usize, usize) {
+    points.sort_unstable();
+
+    // Points with no other point below and left of them, by ascending X
+    let mut lower: Vec<Corner> = Vec::new();
+    for &point in &points {
+        if lower.last().is_none_or(|last| point.1 < last.1) {
+            lower.push(point);
+        }
+    }
+
+    // Points with no other point above and right of them, by ascending X
+    let mut upper: Vec<Corner> = Vec::new();
+    for &point in points.iter().rev() {
+        if upper.last().is_none_or(|last| point.1 > last.1) {
+            upper.push(point);
+        }
+    }
+    upper.reverse();
+
+    let mut best = (Value::ZERO, 0, 0);
+    let search = Search {
+        lower: &lower,
+        upper: &upper,
+        objective,
+    };
+    search.run(0, lower.len(), 0, upper.len(), &mut best);
+
+    best
+}
+
+/// The divide and conquer over the lower staircase `lower`, pairing it with
+/// the upper staircase `upper`.
+struct Search<'a> {
+    lower: &'a [Corner],
+    upper: &'a [Corner],
+    objective: Objective,
+}
+
+impl Search<'_> {
+    /// Find the best corner in `upper[from..to]` for every corner in
+    /// `lower[lo..hi]`, updating `best` with the value and the indices of the
+    /// corners.
+    fn run(&self, lo: usize, hi: usize, from: usize, to: usize, best: &mut (Value, usize, usize)) {
+        if lo >= hi || from >= to {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let (px, py, p) = self.lower[mid];
+
+        let mut best_value = None;
+        let mut best_index = from;
+        for (index, &(qx, qy, _)) in self.upper.iter().enumerate().take(to).skip(from) {
+            let value = self.value(qx - px, qy - py);
+            if best_value.is_none_or(|best_value| value > best_value) {
+                best_value = Some(value);
+                best_index = index;
+            }
+        }
+
+        if let Some(value) = best_value
+            && value > best.0
+        {
+            *best = (value, p, self.upper[best_index].2);
+        }
+
+        if self.objective == Objective::SquaredDiagonal {
+            self.run(lo, mid, best_index, to, best);
+            self.run(mid + 1, hi, from, best_index + 1, best);
+        } else {
+            self.run(lo, mid, from, best_index + 1, best);
+            self.run(mid + 1, hi, best_index, to, best);
+        }
+    }
+
+    /// The value of a rectangle with the signed differences `dx` and `dy`
+    /// between its corners.
+    fn value(&self, dx: i128, dy: i128) -> Value {
+        match self.objective {
+            Objective::Area => Value::product(dx + 1, dy + 1),
+            Objective::Perimeter => Value::product(2, dx + dy + 2),
+            Objective::SquaredDiagonal => Value::NonNegative(
+                dx.unsigned_abs()
+                    .pow(2)
+                    .saturating_add(dy.unsigned_abs().pow(2)),
+            ),
+        }
+    }
+}
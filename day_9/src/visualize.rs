@@ -0,0 +1,74 @@
+// Terminal rendering of the theater floor for Day 9: Movie Theater
+//
+// Rasterizes the red tiles onto a character grid in the style of the puzzle
+// description: `#` for red tiles and `O` for the tiles of the largest
+// rectangle. Real inputs span coordinates far larger than a terminal, so the
+// floor is scaled down to fit: every character stands for a block of tiles,
+// and shows a red tile if any tile of the block is red. A floor that fits is
+// drawn one character per tile.
+
+use crate::{Geometry, bounding_box};
+
+/// Render the red tiles and the largest rectangle of `geometry` on a grid of
+/// at most `columns` by `rows` characters, the top row holding the smallest
+/// Y. The grid spans the bounding box of the tiles.
+pub fn render_grid(geometry: &Geometry, columns: usize, rows: usize) -> String {
+    let Some((min, max, _)) = bounding_box(&geometry.tiles) else {
+        return String::new();
+    };
+    if columns == 0 || rows == 0 {
+        return String::new();
+    }
+    let xs = Axis::new(min.0, max.0, columns);
+    let ys = Axis::new(min.1, max.1, rows);
+
+    let mut grid = vec![vec!['.'; xs.cells]; ys.cells];
+
+    let (a, b) = geometry.corners;
+    let (x1, x2) = (xs.cell(a.0.min(b.0)), xs.cell(a.0.max(b.0)));
+    let (y1, y2) = (ys.cell(a.1.min(b.1)), ys.cell(a.1.max(b.1)));
+    for row in &mut grid[y1..=y2] {
+        row[x1..=x2].fill('O');
+    }
+
+    for point in &geometry.tiles {
+        grid[ys.cell(point.1)][xs.cell(point.0)] = '#';
+    }
+
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The scaling of one axis of the floor onto the grid.
+struct Axis {
+    min: i128,
+    /// The number of coordinates from the smallest to the largest one.
+    span: i128,
+    /// The number of grid cells along the axis.
+    cells: usize,
+}
+
+impl Axis {
+    /// Fit the coordinates from `min` to `max` into at most `limit` cells.
+    fn new(min: i64, max: i64, limit: usize) -> Self {
+        let span = i128::from(max) - i128::from(min) + 1;
+        let cells = usize::try_from(span).map_or(limit, |span| span.min(limit));
+
+        Axis {
+            min: i128::from(min),
+            span,
+            cells,
+        }
+    }
+
+    /// The cell holding the coordinate `value`.
+    fn cell(&self, value: i64) -> usize {
+        let offset = i128::from(value) - self.min;
+
+        // The offset and the cell count are below 2^64, so their product
+        // fits in an `i128`
+        (offset * self.cells as i128 / self.span) as usize
+    }
+}
@@ -0,0 +1,79 @@
+// Bounding boxes between points in any number of dimensions
+//
+// The rectangle of part 1 is the two-dimensional case of the axis-aligned
+// bounding box of two points: its volume is the product over the axes of the
+// number of tiles (or cubes) between the two coordinates. Points are parsed
+// like the junction boxes of day 8, so follow-ups in three dimensions read
+// the same input format.
+
+use crate::{Answer, Day9Error, Point};
+use aoc_core::{index_pairs, parse_coordinates};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// A point with `D` integer coordinates, `X,Y,Z` by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointNd<const D: usize = 3>(pub [i64; D]);
+
+impl<const D: usize> FromStr for PointNd<D> {
+    type Err = &'static str;
+
+    /// Parse `D` comma-separated coordinates.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_coordinates(s).map(PointNd)
+    }
+}
+
+impl From<Point> for PointNd<2> {
+    fn from(point: Point) -> Self {
+        PointNd([point.0, point.1])
+    }
+}
+
+/// The number of unit cells in the axis-aligned bounding box with opposite
+/// corners `point` and `other`, both included.
+///
+/// Fails with [`Day9Error::Overflow`] if the volume does not fit in an
+/// [`Answer`].
+pub fn volume<const D: usize>(point: &PointNd<D>, other: &PointNd<D>) -> Result<Answer, Day9Error> {
+    point
+        .0
+        .iter()
+        .zip(&other.0)
+        .try_fold(1 as Answer, |volume, (a, b)| {
+            volume.checked_mul(Answer::from(a.abs_diff(*b)) + 1)
+        })
+        .ok_or(Day9Error::Overflow)
+}
+
+/// The two of the points of `input` at opposite corners of the largest
+/// bounding box, and its volume, trying every pair in O(n²) time. Of equally
+/// large boxes the first pair is returned.
+pub fn largest_box<const D: usize>(
+    input: &str,
+) -> Result<(PointNd<D>, PointNd<D>, Answer), Day9Error> {
+    let mut seen = HashSet::new();
+    let mut points = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let point: PointNd<D> = line.parse().map_err(|reason| Day9Error::InvalidLine {
+            line: index + 1,
+            text: line.to_string(),
+            reason,
+        })?;
+        if seen.insert(point) {
+            points.push(point);
+        }
+    }
+    if points.len() < 2 {
+        return Err(Day9Error::TooFewTiles {
+            found: points.len(),
+        });
+    }
+
+    let (i, j, largest) = index_pairs(points.len()).try_fold((0, 0, 0), |max, (i, j)| {
+        let size = volume(&points[i], &points[j])?;
+        Ok::<_, Day9Error>(if size > max.2 { (i, j, size) } else { max })
+    })?;
+
+    Ok((points[i], points[j], largest))
+}